@@ -5,8 +5,8 @@ use bcrypt;
 use chrono;
 use futures::stream::TryStreamExt;
 use mongodb::{
-    bson::doc,
-    options::{FindOneOptions, FindOptions},
+    bson::{doc, Document},
+    options::{FindOneOptions, FindOptions, UpdateOptions},
     Database,
 };
 use serde::{Deserialize, Serialize};
@@ -19,7 +19,11 @@ use crate::dto::{
     CreateUserRequest, UpdateUserRequest, DeleteUserRequest, SearchUserRequest, UserPageParams,
 };
 use crate::index_manager::IndexManager;
-use crate::models::{Binding, Card, Collection, Config, Type, User, Vod};
+use crate::models::{
+    Art, Audio, Binding, Card, CollectTask, Collection, Comment, Config, GallerySeries, Image, Type, User, Vod,
+    VodMergeLog, COMMENT_STATUS_APPROVED, COMMENT_STATUS_PENDING, COMMENT_STATUS_REJECTED,
+    IMAGE_REVIEW_APPROVED, IMAGE_REVIEW_REJECTED,
+};
 use crate::scheduled_task::ScheduledTaskManager;
 use crate::template::TERA;
 
@@ -44,6 +48,8 @@ pub struct TypeRequest {
 pub struct BindingRequest {
     pub source_flag: String, // e.g., "my_api_source"
     pub external_id: String, // e.g., "123"
+    #[serde(default)]
+    pub external_name: Option<String>, // 外部分类名称
     pub local_type_id: i32,
 }
 
@@ -74,9 +80,42 @@ pub struct CollectionRequest {
     pub collect_remove_ad: i32,
     pub collect_convert_webp: i32,
     pub collect_download_retry: i32,
+    #[serde(default)]
+    pub collect_format: i32,
+    #[serde(default)]
+    pub collect_match: i32,
+    #[serde(default)]
+    pub collect_update_policy: std::collections::HashMap<String, String>,
+    #[serde(default)]
+    pub collect_cron: String,
+    #[serde(default)]
+    pub collect_cron_status: i32,
     pub collect_status: i32,
 }
 
+impl CollectionRequest {
+    // 校验定时采集配置：启用时必须填写 cron 表达式，填写了就必须可解析
+    fn validate_cron(&self) -> Result<(), String> {
+        if self.collect_cron.trim().is_empty() {
+            if self.collect_cron_status == 1 {
+                return Err("启用定时采集时必须填写 cron 表达式".to_string());
+            }
+            return Ok(());
+        }
+        crate::scheduled_task::parse_cron(&self.collect_cron).map(|_| ())
+    }
+
+    // 校验采集规则，保存时就拒绝无效的正则，避免到采集时才逐页失败
+    fn validate_rules(&self) -> Result<(), String> {
+        crate::collect_rules::CollectRules::parse(
+            &self.collect_filter,
+            &self.collect_filter_from,
+            self.collect_remove_ad,
+        )
+        .map(|_| ())
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct VodRequest {
     pub vod_name: String,
@@ -93,6 +132,20 @@ pub struct VodRequest {
     pub vod_content: Option<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ArtRequest {
+    pub art_name: String,
+    pub type_id: i32,
+    pub art_status: i32,
+    pub art_pic: Option<String>,
+    pub art_author: Option<String>,
+    pub art_from: Option<String>,
+    pub art_remarks: Option<String>,
+    pub art_blurb: Option<String>,
+    pub art_tag: Option<String>,
+    pub art_content: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct BatchDeleteRequest {
     pub ids: Vec<String>,
@@ -142,6 +195,13 @@ pub async fn create_collection(admin: crate::jwt_auth::AdminUser,
     db: web::Data<Database>,
     collection_req: web::Json<CollectionRequest>,
 ) -> impl Responder {
+    if let Err(e) = collection_req
+        .validate_cron()
+        .and_then(|_| collection_req.validate_rules())
+    {
+        return HttpResponse::BadRequest().json(json!({"success": false, "message": e}));
+    }
+
     let collection = db.collection::<Collection>("collections");
 
     let new_collection = Collection {
@@ -160,6 +220,13 @@ pub async fn create_collection(admin: crate::jwt_auth::AdminUser,
         collect_remove_ad: collection_req.collect_remove_ad,
         collect_convert_webp: collection_req.collect_convert_webp,
         collect_download_retry: collection_req.collect_download_retry,
+        collect_format: collection_req.collect_format,
+        collect_match: collection_req.collect_match,
+        collect_update_policy: collection_req.collect_update_policy.clone(),
+        collect_cron: collection_req.collect_cron.trim().to_string(),
+        collect_cron_status: collection_req.collect_cron_status,
+        collect_last_run: None,
+        collect_next_run: None,
         collect_status: collection_req.collect_status,
         created_at: mongodb::bson::DateTime::now(),
         updated_at: mongodb::bson::DateTime::now(),
@@ -224,8 +291,10 @@ pub async fn start_collection_collect(admin: crate::jwt_auth::AdminUser,
         }
     };
 
-    // 如果没有绑定分类，返回错误
-    if bindings_count == 0 {
+    let dry_run = collect_req.as_ref().is_some_and(|req| req.dry_run);
+
+    // 如果没有绑定分类，返回错误（预览模式会在报告中列出缺少的绑定）
+    if bindings_count == 0 && !dry_run {
         return HttpResponse::Ok().json(json!({
             "success": false,
             "message": "请先绑定分类",
@@ -239,6 +308,11 @@ pub async fn start_collection_collect(admin: crate::jwt_auth::AdminUser,
         .and_then(|req| req.hours)
         .map(|h| format!("采集任务已启动 ({}小时内)", h))
         .unwrap_or_else(|| "采集任务已启动 (全部数据)".to_string());
+    let hours_text = if dry_run {
+        hours_text.replacen("采集任务", "预览任务", 1)
+    } else {
+        hours_text
+    };
 
     let hours_param = collect_req
         .as_ref()
@@ -256,6 +330,8 @@ pub async fn start_collection_collect(admin: crate::jwt_auth::AdminUser,
             collection,
             hours_param,
             task_id_clone,
+            dry_run,
+            crate::jobs::JOB_TRIGGER_MANUAL,
         )
         .await
         {
@@ -274,6 +350,8 @@ pub async fn start_collection_collect(admin: crate::jwt_auth::AdminUser,
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CollectRequest {
     pub hours: Option<i32>,
+    #[serde(default)]
+    pub dry_run: bool, // 仅预览，不写入视频
 }
 
 // PUT /api/admin/collections/{id}
@@ -282,12 +360,20 @@ pub async fn update_collection(admin: crate::jwt_auth::AdminUser,
     db: web::Data<Database>,
     collection_req: web::Json<CollectionRequest>,
 ) -> impl Responder {
+    if let Err(e) = collection_req
+        .validate_cron()
+        .and_then(|_| collection_req.validate_rules())
+    {
+        return HttpResponse::BadRequest().json(json!({"success": false, "message": e}));
+    }
+
     let collection = db.collection::<Collection>("collections");
     let collection_id = match mongodb::bson::oid::ObjectId::parse_str(&path.into_inner()) {
         Ok(id) => id,
         Err(_) => return HttpResponse::BadRequest().body("Invalid collection ID"),
     };
 
+    // 定时配置可能已变化，下次运行时间交给调度循环重新计算
     let update_doc = doc! {
         "$set": {
             "collect_name": &collection_req.collect_name,
@@ -304,9 +390,15 @@ pub async fn update_collection(admin: crate::jwt_auth::AdminUser,
             "collect_remove_ad": collection_req.collect_remove_ad,
             "collect_convert_webp": collection_req.collect_convert_webp,
             "collect_download_retry": collection_req.collect_download_retry,
+            "collect_format": collection_req.collect_format,
+            "collect_match": collection_req.collect_match,
+            "collect_update_policy": mongodb::bson::to_bson(&collection_req.collect_update_policy).unwrap_or_default(),
+            "collect_cron": collection_req.collect_cron.trim(),
+            "collect_cron_status": collection_req.collect_cron_status,
             "collect_status": collection_req.collect_status,
             "updated_at": mongodb::bson::DateTime::now(),
-        }
+        },
+        "$unset": { "collect_next_run": "" }
     };
 
     match collection
@@ -331,12 +423,16 @@ pub async fn update_collection(admin: crate::jwt_auth::AdminUser,
 }
 
 // GET /api/admin/collect/progress/{task_id}
-pub async fn get_collect_progress(admin: crate::jwt_auth::AdminUser, path: web::Path<String>) -> impl Responder {
+pub async fn get_collect_progress(
+    admin: crate::jwt_auth::AdminUser,
+    db: web::Data<Database>,
+    path: web::Path<String>,
+) -> impl Responder {
 
     let task_id = path.into_inner();
 
-    // 从内存中获取任务进度（简化版本）
-    let progress = crate::collect_handlers::get_task_progress(&task_id)
+    // 从后台任务记录获取进度
+    let progress = crate::collect_handlers::get_task_progress(&db, &task_id)
         .await
         .unwrap_or(crate::collect_handlers::CollectProgress {
             status: "not_found".to_string(),
@@ -347,17 +443,42 @@ pub async fn get_collect_progress(admin: crate::jwt_auth::AdminUser, path: web::
             log: "任务不存在".to_string(),
         });
 
+    let report = crate::collect_handlers::get_dry_run_report(&db, &task_id).await;
+
     HttpResponse::Ok().json(json!({
         "success": true,
-        "progress": progress
+        "progress": progress,
+        "report": report
     }))
 }
 
+// GET /api/admin/collect/dry-run-report/{task_id}
+pub async fn download_dry_run_report(
+    _admin: crate::jwt_auth::AdminUser,
+    db: web::Data<Database>,
+    path: web::Path<String>,
+) -> impl Responder {
+    let task_id = path.into_inner();
+
+    match crate::collect_handlers::get_dry_run_report(&db, &task_id).await {
+        Some(report) => HttpResponse::Ok()
+            .insert_header((
+                "Content-Disposition",
+                format!("attachment; filename=\"dry-run-{}.json\"", task_id),
+            ))
+            .json(report),
+        None => HttpResponse::NotFound().json(json!({
+            "success": false,
+            "message": "预览报告不存在"
+        })),
+    }
+}
+
 // GET /api/admin/collect/running-tasks
-pub async fn get_running_tasks(admin: crate::jwt_auth::AdminUser) -> impl Responder {
+pub async fn get_running_tasks(admin: crate::jwt_auth::AdminUser, db: web::Data<Database>) -> impl Responder {
 
-    // 获取所有运行中的任务（从collect_handlers中的全局存储获取）
-    let tasks = crate::collect_handlers::get_all_running_tasks().await;
+    // 获取所有运行中的采集任务（来自后台任务记录，包括其他实例上的任务）
+    let tasks = crate::collect_handlers::get_all_running_tasks(&db).await;
 
     HttpResponse::Ok().json(json!({
         "success": true,
@@ -366,17 +487,21 @@ pub async fn get_running_tasks(admin: crate::jwt_auth::AdminUser) -> impl Respon
 }
 
 // POST /api/admin/collect/stop/{task_id}
-pub async fn stop_collect_task(admin: crate::jwt_auth::AdminUser, path: web::Path<String>) -> impl Responder {
+pub async fn stop_collect_task(
+    admin: crate::jwt_auth::AdminUser,
+    db: web::Data<Database>,
+    path: web::Path<String>,
+) -> impl Responder {
 
     let task_id = path.into_inner();
 
     // 调用collect_handlers中的停止任务函数
-    let stopped = crate::collect_handlers::stop_task(&task_id).await;
+    let stopped = crate::collect_handlers::stop_task(&db, &task_id).await;
 
     if stopped {
         HttpResponse::Ok().json(json!({
             "success": true,
-            "message": "任务已成功停止"
+            "message": "已请求停止任务，将在当前视频采集完成后停止"
         }))
     } else {
         HttpResponse::NotFound().json(json!({
@@ -386,6 +511,211 @@ pub async fn stop_collect_task(admin: crate::jwt_auth::AdminUser, path: web::Pat
     }
 }
 
+#[derive(Deserialize)]
+pub struct CollectTaskQuery {
+    pub page: Option<u64>,
+    pub limit: Option<i64>,
+    pub status: Option<i32>,
+}
+
+// GET /api/admin/collect-tasks
+pub async fn get_collect_tasks(
+    _admin: crate::jwt_auth::AdminUser,
+    db: web::Data<Database>,
+    query: web::Query<CollectTaskQuery>,
+) -> impl Responder {
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query.limit.unwrap_or(20).clamp(1, 100);
+
+    let mut filter = doc! {};
+    if let Some(status) = query.status {
+        filter.insert("task_status", status);
+    }
+
+    let collection = db.collection::<CollectTask>("collect_tasks");
+    let total = match collection.count_documents(filter.clone(), None).await {
+        Ok(total) => total,
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(json!({
+                "success": false,
+                "message": format!("查询采集任务失败: {}", e)
+            }))
+        }
+    };
+
+    let find_options = FindOptions::builder()
+        .sort(doc! { "created_at": -1 })
+        .skip((page - 1) * limit as u64)
+        .limit(limit)
+        .build();
+
+    let tasks: Vec<CollectTask> = match collection.find(filter, find_options).await {
+        Ok(cursor) => cursor.try_collect().await.unwrap_or_default(),
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(json!({
+                "success": false,
+                "message": format!("查询采集任务失败: {}", e)
+            }))
+        }
+    };
+
+    HttpResponse::Ok().json(json!({
+        "success": true,
+        "tasks": tasks,
+        "total": total,
+        "page": page,
+        "limit": limit
+    }))
+}
+
+// POST /api/admin/collect-tasks/{task_id}/resume
+pub async fn resume_collect_task(
+    _admin: crate::jwt_auth::AdminUser,
+    db: web::Data<Database>,
+    path: web::Path<String>,
+) -> impl Responder {
+    let task_id = path.into_inner();
+
+    match crate::collect_handlers::resume_collect_task(&db, &task_id).await {
+        Ok(true) => HttpResponse::Ok().json(json!({
+            "success": true,
+            "message": "任务已从断点继续采集",
+            "task_id": task_id
+        })),
+        Ok(false) => HttpResponse::Ok().json(json!({
+            "success": true,
+            "message": "任务已被重新开始，无需重复操作",
+            "task_id": task_id
+        })),
+        Err(e) => HttpResponse::BadRequest().json(json!({
+            "success": false,
+            "message": e.to_string()
+        })),
+    }
+}
+
+#[derive(Deserialize)]
+pub struct CollectRulesTestRequest {
+    #[serde(default)]
+    pub collect_filter: String,
+    #[serde(default)]
+    pub collect_filter_from: String,
+    #[serde(default)]
+    pub collect_remove_ad: i32,
+    pub payload: String, // 资源站接口返回的JSON或XML样例
+}
+
+// POST /api/admin/collect-rules/test
+pub async fn test_collect_rules(
+    _admin: crate::jwt_auth::AdminUser,
+    req: web::Json<CollectRulesTestRequest>,
+) -> impl Responder {
+    let rules = match crate::collect_rules::CollectRules::parse(
+        &req.collect_filter,
+        &req.collect_filter_from,
+        req.collect_remove_ad,
+    ) {
+        Ok(rules) => rules,
+        Err(e) => {
+            return HttpResponse::BadRequest().json(json!({
+                "success": false,
+                "message": e
+            }))
+        }
+    };
+
+    let response = match crate::collect_handlers::parse_collect_response(&req.payload, 0) {
+        Ok(response) => response,
+        Err(e) => {
+            return HttpResponse::BadRequest().json(json!({
+                "success": false,
+                "message": format!("样例数据解析失败: {}", e)
+            }))
+        }
+    };
+
+    let results: Vec<serde_json::Value> = response
+        .list
+        .into_iter()
+        .map(|mut vod| {
+            let outcome = rules.apply(&mut vod);
+            json!({
+                "vod_name": vod.vod_name,
+                "accepted": outcome.accepted,
+                "reason": outcome.reason,
+                "vod_play_from": vod.vod_play_from,
+                "vod_play_url": vod.vod_play_url,
+                "vod_content": vod.vod_content,
+            })
+        })
+        .collect();
+
+    HttpResponse::Ok().json(json!({
+        "success": true,
+        "results": results
+    }))
+}
+
+#[derive(Deserialize)]
+pub struct MergeLogQuery {
+    pub task_id: Option<String>,
+    pub collect_id: Option<String>,
+    pub page: Option<u64>,
+    pub limit: Option<i64>,
+}
+
+// GET /api/admin/vod-merge-logs
+pub async fn get_vod_merge_logs(
+    _admin: crate::jwt_auth::AdminUser,
+    db: web::Data<Database>,
+    query: web::Query<MergeLogQuery>,
+) -> impl Responder {
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query.limit.unwrap_or(50).clamp(1, 200);
+
+    let mut filter = doc! {};
+    if let Some(task_id) = query.task_id.as_deref().filter(|s| !s.is_empty()) {
+        filter.insert("task_id", task_id);
+    }
+    if let Some(collect_id) = query.collect_id.as_deref().filter(|s| !s.is_empty()) {
+        match mongodb::bson::oid::ObjectId::parse_str(collect_id) {
+            Ok(id) => {
+                filter.insert("collect_id", id);
+            }
+            Err(_) => return HttpResponse::BadRequest().body("Invalid collection ID"),
+        }
+    }
+
+    let collection = db.collection::<VodMergeLog>("vod_merge_logs");
+    let total = collection
+        .count_documents(filter.clone(), None)
+        .await
+        .unwrap_or(0);
+
+    let find_options = FindOptions::builder()
+        .sort(doc! { "created_at": -1 })
+        .skip((page - 1) * limit as u64)
+        .limit(limit)
+        .build();
+
+    match collection.find(filter, find_options).await {
+        Ok(cursor) => {
+            let logs: Vec<VodMergeLog> = cursor.try_collect().await.unwrap_or_default();
+            HttpResponse::Ok().json(json!({
+                "success": true,
+                "logs": logs,
+                "total": total,
+                "page": page,
+                "limit": limit
+            }))
+        }
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "success": false,
+            "message": format!("查询合并报告失败: {}", e)
+        })),
+    }
+}
+
 // DELETE /api/admin/collections/{id}
 pub async fn delete_collection(admin: crate::jwt_auth::AdminUser, 
     path: web::Path<String>,
@@ -427,6 +757,76 @@ pub struct VodsQuery {
     pub type_id: Option<i32>,
     pub status: Option<i32>,
     pub search: Option<String>,
+    pub source: Option<String>,
+    pub year: Option<String>,
+    pub area: Option<String>,
+    pub need_vip: Option<i32>,
+}
+
+impl VodsQuery {
+    fn filter(&self) -> VodFilter {
+        VodFilter {
+            type_id: self.type_id,
+            status: self.status,
+            search: self.search.clone(),
+            source: self.source.clone(),
+            year: self.year.clone(),
+            area: self.area.clone(),
+            need_vip: self.need_vip,
+        }
+    }
+}
+
+// 视频筛选条件，视频列表与按筛选批量操作共用
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct VodFilter {
+    pub type_id: Option<i32>,
+    pub status: Option<i32>,
+    pub search: Option<String>, // 名称关键词
+    pub source: Option<String>, // 播放源名称
+    pub year: Option<String>,
+    pub area: Option<String>,
+    pub need_vip: Option<i32>,
+}
+
+impl VodFilter {
+    pub fn to_document(&self) -> Document {
+        let mut filter_doc = doc! {};
+
+        // 分类筛选
+        if let Some(type_id) = self.type_id {
+            filter_doc.insert("type_id", type_id);
+        }
+
+        // 状态筛选
+        if let Some(status) = self.status {
+            filter_doc.insert("vod_status", status);
+        }
+
+        // 搜索功能
+        if let Some(search_term) = non_empty(&self.search) {
+            filter_doc.insert("vod_name", doc! {"$regex": regex::escape(search_term), "$options": "i"});
+        }
+
+        if let Some(source) = non_empty(&self.source) {
+            filter_doc.insert("vod_play_urls.source_name", source);
+        }
+        if let Some(year) = non_empty(&self.year) {
+            filter_doc.insert("vod_year", year);
+        }
+        if let Some(area) = non_empty(&self.area) {
+            filter_doc.insert("vod_area", area);
+        }
+        if let Some(need_vip) = self.need_vip {
+            filter_doc.insert("need_vip", need_vip);
+        }
+
+        filter_doc
+    }
+}
+
+fn non_empty(value: &Option<String>) -> Option<&str> {
+    value.as_deref().map(str::trim).filter(|v| !v.is_empty())
 }
 
 // GET /api/admin/vods
@@ -440,24 +840,7 @@ pub async fn get_vods_admin(admin: crate::jwt_auth::AdminUser,
     let skip = (page - 1) * limit;
 
     // 构建查询条件
-    let mut filter_doc = doc! {};
-
-    // 分类筛选
-    if let Some(type_id) = query.type_id {
-        filter_doc.insert("type_id", type_id);
-    }
-
-    // 状态筛选
-    if let Some(status) = query.status {
-        filter_doc.insert("vod_status", status);
-    }
-
-    // 搜索功能
-    if let Some(search_term) = &query.search {
-        if !search_term.trim().is_empty() {
-            filter_doc.insert("vod_name", doc! {"$regex": search_term, "$options": "i"});
-        }
-    }
+    let filter_doc = query.filter().to_document();
 
     let collection = db.collection::<Vod>("vods");
     let find_options = FindOptions::builder()
@@ -517,9 +900,24 @@ pub async fn create_vod(admin: crate::jwt_auth::AdminUser,
 ) -> impl Responder {
     let collection = db.collection::<Vod>("vods");
 
+    let vod_id = match crate::db::next_sequence(&db, "vod_id").await {
+        Ok(id) => id,
+        Err(e) => {
+            eprintln!("Failed to allocate vod_id: {}", e);
+            return HttpResponse::InternalServerError().json(json!({
+                "success": false,
+                "message": "Failed to create video"
+            }));
+        }
+    };
+
     let new_vod = Vod {
         id: None,
+        vod_id: Some(vod_id),
         vod_name: vod_req.vod_name.clone(),
+        vod_name_key: Some(crate::vod_match::normalize_vod_name(&vod_req.vod_name)),
+        vod_douban_id: None,
+        vod_pic_source: None,
         type_id: vod_req.type_id,
         vod_status: vod_req.vod_status,
         vod_class: vod_req.vod_class.clone(),
@@ -537,15 +935,22 @@ pub async fn create_vod(admin: crate::jwt_auth::AdminUser,
         vod_hits_week: Some(0),
         vod_hits_month: Some(0),
         vod_score: Some("0.0".to_string()),
+        vod_score_all: Some(0),
+        vod_score_num: Some(0),
         need_vip: 0,
         vod_play_urls: vec![], // Empty initially
     };
 
     match collection.insert_one(new_vod, None).await {
-        Ok(_) => HttpResponse::Created().json(json!({
-            "success": true,
-            "message": "Video created successfully"
-        })),
+        Ok(result) => {
+            if let Some(id) = result.inserted_id.as_object_id() {
+                crate::search_index::refresh_entries(&db, "vod", &[id]).await;
+            }
+            HttpResponse::Created().json(json!({
+                "success": true,
+                "message": "Video created successfully"
+            }))
+        }
         Err(e) => {
             eprintln!("Failed to create video: {}", e);
             HttpResponse::InternalServerError().json(json!({
@@ -571,6 +976,7 @@ pub async fn update_vod(admin: crate::jwt_auth::AdminUser,
     let update_doc = doc! {
         "$set": {
             "vod_name": &vod_req.vod_name,
+            "vod_name_key": crate::vod_match::normalize_vod_name(&vod_req.vod_name),
             "type_id": vod_req.type_id,
             "vod_status": vod_req.vod_status,
             "vod_class": &vod_req.vod_class,
@@ -591,6 +997,7 @@ pub async fn update_vod(admin: crate::jwt_auth::AdminUser,
     {
         Ok(result) => {
             if result.matched_count > 0 {
+                crate::search_index::refresh_entries(&db, "vod", &[vod_id]).await;
                 HttpResponse::Ok().json(json!({
                     "success": true,
                     "message": "Video updated successfully"
@@ -626,6 +1033,9 @@ pub async fn delete_vod(admin: crate::jwt_auth::AdminUser,
     match collection.delete_one(doc! {"_id": vod_id}, None).await {
         Ok(result) => {
             if result.deleted_count > 0 {
+                if let Err(e) = delete_vod_relations(&db, &[vod_id]).await {
+                    eprintln!("Failed to delete video relations: {}", e);
+                }
                 HttpResponse::Ok()
                     .json(json!({"success": true, "message": "Video deleted successfully"}))
             } else {
@@ -669,10 +1079,13 @@ pub async fn batch_delete_vods(admin: crate::jwt_auth::AdminUser,
 
     // Delete all valid videos
     match collection
-        .delete_many(doc! {"_id": {"$in": object_ids}}, None)
+        .delete_many(doc! {"_id": {"$in": &object_ids}}, None)
         .await
     {
         Ok(result) => {
+            if let Err(e) = delete_vod_relations(&db, &object_ids).await {
+                eprintln!("Failed to delete video relations: {}", e);
+            }
             let response = json!({
                 "success": true,
                 "message": "Videos deleted successfully",
@@ -693,42 +1106,311 @@ pub async fn batch_delete_vods(admin: crate::jwt_auth::AdminUser,
     }
 }
 
-// --- Website Configuration Management API ---
-
-// GET /api/admin/configs
-pub async fn get_configs(admin: crate::jwt_auth::AdminUser, db: web::Data<Database>) -> impl Responder {
-    let collection = db.collection::<Config>("configs");
-    let find_options = FindOptions::builder().sort(doc! {"config_sort": 1}).build();
+// --- Article Management API ---
 
-    match collection.find(None, find_options).await {
-        Ok(cursor) => {
-            let configs: Vec<Config> = cursor.try_collect().await.unwrap_or_else(|_| vec![]);
-            HttpResponse::Ok().json(configs)
-        }
-        Err(e) => {
-            eprintln!("Failed to fetch configs: {}", e);
-            HttpResponse::InternalServerError().body("Failed to fetch configs")
-        }
-    }
+#[derive(Debug, Deserialize)]
+pub struct ArtsQuery {
+    pub page: Option<u32>,
+    pub limit: Option<u32>,
+    pub type_id: Option<i32>,
+    pub status: Option<i32>,
+    pub search: Option<String>,
 }
 
-// GET /api/admin/configs/{key}
-pub async fn get_config_by_key(admin: crate::jwt_auth::AdminUser, 
-    path: web::Path<String>,
+// GET /api/admin/arts
+pub async fn get_arts_admin(_admin: crate::jwt_auth::AdminUser,
     db: web::Data<Database>,
+    query: web::Query<ArtsQuery>,
 ) -> impl Responder {
-    let collection = db.collection::<Config>("configs");
-    let config_key = path.into_inner();
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query.limit.unwrap_or(20).min(100);
+    let skip = (page - 1) * limit;
 
-    match collection
-        .find_one(doc! {"config_key": &config_key}, None)
-        .await
-    {
-        Ok(Some(config)) => HttpResponse::Ok().json(config),
-        Ok(None) => HttpResponse::NotFound().body("Config not found"),
-        Err(e) => {
-            eprintln!("Failed to fetch config: {}", e);
-            HttpResponse::InternalServerError().body("Failed to fetch config")
+    let mut filter_doc = doc! {};
+    if let Some(type_id) = query.type_id {
+        filter_doc.insert("type_id", type_id);
+    }
+    if let Some(status) = query.status {
+        filter_doc.insert("art_status", status);
+    }
+    if let Some(search_term) = query.search.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+        filter_doc.insert("art_name", doc! {"$regex": regex::escape(search_term), "$options": "i"});
+    }
+
+    let collection = db.collection::<Art>("arts");
+    let total = match collection.count_documents(filter_doc.clone(), None).await {
+        Ok(count) => count,
+        Err(e) => {
+            eprintln!("Failed to count arts: {}", e);
+            return HttpResponse::InternalServerError().json(json!({
+                "code": 0,
+                "msg": "Failed to count articles",
+                "page": page,
+                "limit": limit,
+                "total": 0,
+                "arts": []
+            }));
+        }
+    };
+
+    // 列表不返回正文，减小响应体积
+    let find_options = FindOptions::builder()
+        .sort(doc! {"art_pubdate": -1})
+        .skip(skip as u64)
+        .limit(limit as i64)
+        .projection(doc! {"art_content": 0})
+        .build();
+
+    match collection.find(filter_doc, find_options).await {
+        Ok(cursor) => {
+            let arts: Vec<Art> = cursor.try_collect().await.unwrap_or_else(|_| vec![]);
+            HttpResponse::Ok().json(json!({
+                "code": 1,
+                "msg": "success",
+                "page": page,
+                "limit": limit,
+                "total": total,
+                "arts": arts
+            }))
+        }
+        Err(e) => {
+            eprintln!("Failed to fetch arts: {}", e);
+            HttpResponse::InternalServerError().json(json!({
+                "code": 0,
+                "msg": "Failed to fetch articles",
+                "page": page,
+                "limit": limit,
+                "total": 0,
+                "arts": []
+            }))
+        }
+    }
+}
+
+// GET /api/admin/arts/{id}
+pub async fn get_art_admin(_admin: crate::jwt_auth::AdminUser,
+    path: web::Path<String>,
+    db: web::Data<Database>,
+) -> impl Responder {
+    let art_id = match mongodb::bson::oid::ObjectId::parse_str(path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().body("Invalid article ID"),
+    };
+
+    match db.collection::<Art>("arts").find_one(doc! {"_id": art_id}, None).await {
+        Ok(Some(art)) => HttpResponse::Ok().json(json!({"success": true, "art": art})),
+        Ok(None) => HttpResponse::NotFound()
+            .json(json!({"success": false, "message": "Article not found"})),
+        Err(e) => {
+            eprintln!("Failed to fetch article: {}", e);
+            HttpResponse::InternalServerError()
+                .json(json!({"success": false, "message": "Failed to fetch article"}))
+        }
+    }
+}
+
+// POST /api/admin/arts
+pub async fn create_art(_admin: crate::jwt_auth::AdminUser,
+    db: web::Data<Database>,
+    art_req: web::Json<ArtRequest>,
+) -> impl Responder {
+    if art_req.art_name.trim().is_empty() {
+        return HttpResponse::BadRequest()
+            .json(json!({"success": false, "message": "文章标题不能为空"}));
+    }
+
+    let new_art = Art {
+        id: None,
+        art_name: art_req.art_name.trim().to_string(),
+        type_id: art_req.type_id,
+        art_status: art_req.art_status,
+        art_pic: art_req.art_pic.clone(),
+        art_author: art_req.art_author.clone(),
+        art_from: art_req.art_from.clone(),
+        art_remarks: art_req.art_remarks.clone(),
+        art_pubdate: mongodb::bson::DateTime::now(),
+        art_content: art_req.art_content.clone(),
+        art_blurb: art_req.art_blurb.clone(),
+        art_tag: art_req.art_tag.clone(),
+        art_hits: Some(0),
+        art_pic_source: None,
+    };
+
+    match db.collection::<Art>("arts").insert_one(new_art, None).await {
+        Ok(result) => {
+            if let Some(id) = result.inserted_id.as_object_id() {
+                crate::search_index::refresh_entries(&db, "art", &[id]).await;
+            }
+            HttpResponse::Created().json(json!({
+                "success": true,
+                "message": "Article created successfully"
+            }))
+        }
+        Err(e) => {
+            eprintln!("Failed to create article: {}", e);
+            HttpResponse::InternalServerError().json(json!({
+                "success": false,
+                "message": "Failed to create article"
+            }))
+        }
+    }
+}
+
+// PUT /api/admin/arts/{id}
+pub async fn update_art(_admin: crate::jwt_auth::AdminUser,
+    path: web::Path<String>,
+    db: web::Data<Database>,
+    art_req: web::Json<ArtRequest>,
+) -> impl Responder {
+    let art_id = match mongodb::bson::oid::ObjectId::parse_str(path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().body("Invalid article ID"),
+    };
+
+    let update_doc = doc! {
+        "$set": {
+            "art_name": art_req.art_name.trim(),
+            "type_id": art_req.type_id,
+            "art_status": art_req.art_status,
+            "art_pic": &art_req.art_pic,
+            "art_author": &art_req.art_author,
+            "art_from": &art_req.art_from,
+            "art_remarks": &art_req.art_remarks,
+            "art_blurb": &art_req.art_blurb,
+            "art_tag": &art_req.art_tag,
+            "art_content": &art_req.art_content,
+        }
+    };
+
+    match db
+        .collection::<Art>("arts")
+        .update_one(doc! {"_id": art_id}, update_doc, None)
+        .await
+    {
+        Ok(result) if result.matched_count > 0 => {
+            crate::search_index::refresh_entries(&db, "art", &[art_id]).await;
+            HttpResponse::Ok().json(json!({
+                "success": true,
+                "message": "Article updated successfully"
+            }))
+        }
+        Ok(_) => HttpResponse::NotFound().json(json!({
+            "success": false,
+            "message": "Article not found"
+        })),
+        Err(e) => {
+            eprintln!("Failed to update article: {}", e);
+            HttpResponse::InternalServerError().json(json!({
+                "success": false,
+                "message": "Failed to update article"
+            }))
+        }
+    }
+}
+
+// DELETE /api/admin/arts/{id}
+pub async fn delete_art(_admin: crate::jwt_auth::AdminUser,
+    path: web::Path<String>,
+    db: web::Data<Database>,
+) -> impl Responder {
+    let art_id = match mongodb::bson::oid::ObjectId::parse_str(path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().body("Invalid article ID"),
+    };
+
+    match db.collection::<Art>("arts").delete_one(doc! {"_id": art_id}, None).await {
+        Ok(result) if result.deleted_count > 0 => {
+            crate::search_index::refresh_entries(&db, "art", &[art_id]).await;
+            HttpResponse::Ok()
+                .json(json!({"success": true, "message": "Article deleted successfully"}))
+        }
+        Ok(_) => HttpResponse::NotFound()
+            .json(json!({"success": false, "message": "Article not found"})),
+        Err(e) => {
+            eprintln!("Failed to delete article: {}", e);
+            HttpResponse::InternalServerError()
+                .json(json!({"success": false, "message": "Failed to delete article"}))
+        }
+    }
+}
+
+// DELETE /api/admin/arts
+pub async fn batch_delete_arts(_admin: crate::jwt_auth::AdminUser,
+    db: web::Data<Database>,
+    batch_req: web::Json<BatchDeleteRequest>,
+) -> impl Responder {
+    let object_ids: Vec<mongodb::bson::oid::ObjectId> = batch_req
+        .ids
+        .iter()
+        .filter_map(|id| mongodb::bson::oid::ObjectId::parse_str(id).ok())
+        .collect();
+
+    if object_ids.is_empty() {
+        return HttpResponse::BadRequest().json(json!({
+            "success": false,
+            "message": "No valid article IDs provided"
+        }));
+    }
+
+    match db
+        .collection::<Art>("arts")
+        .delete_many(doc! {"_id": {"$in": &object_ids}}, None)
+        .await
+    {
+        Ok(result) => {
+            crate::search_index::refresh_entries(&db, "art", &object_ids).await;
+            HttpResponse::Ok().json(json!({
+                "success": true,
+                "message": "Articles deleted successfully",
+                "deleted_count": result.deleted_count
+            }))
+        }
+        Err(e) => {
+            eprintln!("Failed to batch delete articles: {}", e);
+            HttpResponse::InternalServerError().json(json!({
+                "success": false,
+                "message": "Failed to delete articles"
+            }))
+        }
+    }
+}
+
+// --- Website Configuration Management API ---
+
+// GET /api/admin/configs
+pub async fn get_configs(admin: crate::jwt_auth::AdminUser, db: web::Data<Database>) -> impl Responder {
+    let collection = db.collection::<Config>("configs");
+    let find_options = FindOptions::builder().sort(doc! {"config_sort": 1}).build();
+
+    match collection.find(None, find_options).await {
+        Ok(cursor) => {
+            let configs: Vec<Config> = cursor.try_collect().await.unwrap_or_else(|_| vec![]);
+            HttpResponse::Ok().json(configs)
+        }
+        Err(e) => {
+            eprintln!("Failed to fetch configs: {}", e);
+            HttpResponse::InternalServerError().body("Failed to fetch configs")
+        }
+    }
+}
+
+// GET /api/admin/configs/{key}
+pub async fn get_config_by_key(admin: crate::jwt_auth::AdminUser, 
+    path: web::Path<String>,
+    db: web::Data<Database>,
+) -> impl Responder {
+    let collection = db.collection::<Config>("configs");
+    let config_key = path.into_inner();
+
+    match collection
+        .find_one(doc! {"config_key": &config_key}, None)
+        .await
+    {
+        Ok(Some(config)) => HttpResponse::Ok().json(config),
+        Ok(None) => HttpResponse::NotFound().body("Config not found"),
+        Err(e) => {
+            eprintln!("Failed to fetch config: {}", e);
+            HttpResponse::InternalServerError().body("Failed to fetch config")
         }
     }
 }
@@ -1098,66 +1780,225 @@ pub async fn get_collection_binding_status(admin: crate::jwt_auth::AdminUser,
     }))
 }
 
-// POST /api/admin/bindings
-pub async fn create_or_update_binding(admin: crate::jwt_auth::AdminUser, 
+// GET /api/admin/collections/{id}/binding-suggestions
+// 为未绑定的外部分类推荐本地分类
+pub async fn get_binding_suggestions(_admin: crate::jwt_auth::AdminUser,
+    path: web::Path<String>,
     db: web::Data<Database>,
-    binding_req: web::Json<BindingRequest>,
 ) -> impl Responder {
-    let collection = db.collection::<Binding>("bindings");
-
-    let binding_id = format!("{}_{}", binding_req.source_flag, binding_req.external_id);
+    let collection_id = match mongodb::bson::oid::ObjectId::parse_str(path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => {
+            return HttpResponse::BadRequest()
+                .json(json!({"success": false, "message": "Invalid collection ID"}))
+        }
+    };
 
-    // Fetch local type name for the binding
-    let type_collection = db.collection::<Type>("types");
-    let local_type_name = match type_collection
-        .find_one(doc! {"type_id": binding_req.local_type_id}, None)
+    let collection = match db
+        .collection::<Collection>("collections")
+        .find_one(doc! {"_id": collection_id}, None)
         .await
     {
-        Ok(Some(t)) => t.type_name,
-        _ => "Unknown Type".to_string(), // Default if type not found
+        Ok(Some(c)) => c,
+        Ok(None) => {
+            return HttpResponse::NotFound()
+                .json(json!({"success": false, "message": "Collection not found"}))
+        }
+        Err(e) => {
+            eprintln!("Failed to fetch collection: {}", e);
+            return HttpResponse::InternalServerError()
+                .json(json!({"success": false, "message": "Failed to fetch collection"}));
+        }
     };
 
-    let now = mongodb::bson::DateTime::now();
-    let new_binding = Binding {
-        id: binding_id.clone(),
-        source_flag: binding_req.source_flag.clone(),
-        external_id: binding_req.external_id.clone(),
-        local_type_id: binding_req.local_type_id,
-        local_type_name: local_type_name.clone(),
-        created_at: now,
-        updated_at: now,
+    let categories = match crate::collect_handlers::fetch_collect_categories(&collection).await {
+        Ok(categories) => categories,
+        Err(e) => {
+            eprintln!("Failed to fetch collect categories: {}", e);
+            return HttpResponse::Ok()
+                .json(json!({"success": false, "message": format!("获取采集源分类失败: {}", e)}));
+        }
     };
 
-    match collection.insert_one(new_binding, None).await {
-        Ok(_) => HttpResponse::Created()
-            .json(json!({"success": true, "message": "Binding created/updated"})),
+    match crate::binding_suggest::suggest_bindings(&db, &collection, &categories).await {
+        Ok(suggestions) => HttpResponse::Ok().json(json!({
+            "success": true,
+            "source_flag": collection.collect_name,
+            "suggestions": suggestions
+        })),
         Err(e) => {
-            // If it's a duplicate key error, try to update instead (upsert behavior)
-            if e.to_string().contains("E11000 duplicate key error") {
-                let update_doc = doc! {"$set": {
-                    "source_flag": &binding_req.source_flag,
-                    "external_id": &binding_req.external_id,
-                    "local_type_id": binding_req.local_type_id,
-                    "local_type_name": local_type_name.clone(),
-                    "updated_at": mongodb::bson::DateTime::now()
-                }};
-                match collection
-                    .update_one(doc! {"_id": binding_id}, update_doc, None)
-                    .await
-                {
-                    Ok(_) => HttpResponse::Ok()
-                        .json(json!({"success": true, "message": "Binding updated"})),
-                    Err(e) => {
-                        eprintln!("Failed to update binding: {}", e);
-                        HttpResponse::InternalServerError()
-                            .json(json!({"success": false, "message": "Failed to update binding"}))
-                    }
-                }
-            } else {
-                eprintln!("Failed to create binding: {}", e);
-                HttpResponse::InternalServerError()
-                    .json(json!({"success": false, "message": "Failed to create binding"}))
-            }
+            eprintln!("Failed to build binding suggestions: {}", e);
+            HttpResponse::InternalServerError()
+                .json(json!({"success": false, "message": "Failed to build binding suggestions"}))
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AcceptBindingItem {
+    pub external_id: String,
+    #[serde(default)]
+    pub external_name: Option<String>,
+    pub local_type_id: i32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AcceptBindingsRequest {
+    pub items: Vec<AcceptBindingItem>,
+}
+
+// POST /api/admin/collections/{id}/binding-suggestions/accept
+// 批量接受推荐结果，已存在的绑定会被覆盖
+pub async fn accept_binding_suggestions(_admin: crate::jwt_auth::AdminUser,
+    path: web::Path<String>,
+    db: web::Data<Database>,
+    req: web::Json<AcceptBindingsRequest>,
+) -> impl Responder {
+    let collection_id = match mongodb::bson::oid::ObjectId::parse_str(path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => {
+            return HttpResponse::BadRequest()
+                .json(json!({"success": false, "message": "Invalid collection ID"}))
+        }
+    };
+
+    let collection = match db
+        .collection::<Collection>("collections")
+        .find_one(doc! {"_id": collection_id}, None)
+        .await
+    {
+        Ok(Some(c)) => c,
+        Ok(None) => {
+            return HttpResponse::NotFound()
+                .json(json!({"success": false, "message": "Collection not found"}))
+        }
+        Err(e) => {
+            eprintln!("Failed to fetch collection: {}", e);
+            return HttpResponse::InternalServerError()
+                .json(json!({"success": false, "message": "Failed to fetch collection"}));
+        }
+    };
+
+    let types: Vec<Type> = match db.collection::<Type>("types").find(doc! {}, None).await {
+        Ok(cursor) => cursor.try_collect().await.unwrap_or_default(),
+        Err(e) => {
+            eprintln!("Failed to fetch types: {}", e);
+            return HttpResponse::InternalServerError()
+                .json(json!({"success": false, "message": "Failed to fetch types"}));
+        }
+    };
+
+    let bindings_collection = db.collection::<Binding>("bindings");
+    let options = mongodb::options::UpdateOptions::builder().upsert(true).build();
+    let mut saved = 0;
+    let mut skipped = Vec::new();
+
+    for item in &req.items {
+        let Some(local_type) = types.iter().find(|t| t.type_id == item.local_type_id) else {
+            skipped.push(item.external_id.clone());
+            continue;
+        };
+
+        let now = mongodb::bson::DateTime::now();
+        let binding_id = format!("{}_{}", collection.collect_name, item.external_id);
+        let mut set_doc = doc! {
+            "source_flag": &collection.collect_name,
+            "external_id": &item.external_id,
+            "local_type_id": local_type.type_id,
+            "local_type_name": &local_type.type_name,
+            "updated_at": now
+        };
+        // 未提供外部名称时保留已有值，避免把推荐依据清空
+        if let Some(name) = item.external_name.as_deref().filter(|n| !n.is_empty()) {
+            set_doc.insert("external_name", name);
+        }
+        let update_doc = doc! {
+            "$set": set_doc,
+            "$setOnInsert": { "created_at": now }
+        };
+
+        match bindings_collection
+            .update_one(doc! {"_id": &binding_id}, update_doc, options.clone())
+            .await
+        {
+            Ok(_) => saved += 1,
+            Err(e) => {
+                eprintln!("Failed to save binding {}: {}", binding_id, e);
+                skipped.push(item.external_id.clone());
+            }
+        }
+    }
+
+    HttpResponse::Ok().json(json!({
+        "success": true,
+        "saved": saved,
+        "skipped": skipped,
+        "message": format!("已保存 {} 个绑定", saved)
+    }))
+}
+
+// POST /api/admin/bindings
+pub async fn create_or_update_binding(admin: crate::jwt_auth::AdminUser, 
+    db: web::Data<Database>,
+    binding_req: web::Json<BindingRequest>,
+) -> impl Responder {
+    let collection = db.collection::<Binding>("bindings");
+
+    let binding_id = format!("{}_{}", binding_req.source_flag, binding_req.external_id);
+
+    // Fetch local type name for the binding
+    let type_collection = db.collection::<Type>("types");
+    let local_type_name = match type_collection
+        .find_one(doc! {"type_id": binding_req.local_type_id}, None)
+        .await
+    {
+        Ok(Some(t)) => t.type_name,
+        _ => "Unknown Type".to_string(), // Default if type not found
+    };
+
+    let now = mongodb::bson::DateTime::now();
+    let new_binding = Binding {
+        id: binding_id.clone(),
+        source_flag: binding_req.source_flag.clone(),
+        external_id: binding_req.external_id.clone(),
+        external_name: binding_req.external_name.clone(),
+        local_type_id: binding_req.local_type_id,
+        local_type_name: local_type_name.clone(),
+        created_at: now,
+        updated_at: now,
+    };
+
+    match collection.insert_one(new_binding, None).await {
+        Ok(_) => HttpResponse::Created()
+            .json(json!({"success": true, "message": "Binding created/updated"})),
+        Err(e) => {
+            // If it's a duplicate key error, try to update instead (upsert behavior)
+            if e.to_string().contains("E11000 duplicate key error") {
+                let update_doc = doc! {"$set": {
+                    "source_flag": &binding_req.source_flag,
+                    "external_id": &binding_req.external_id,
+                    "external_name": &binding_req.external_name,
+                    "local_type_id": binding_req.local_type_id,
+                    "local_type_name": local_type_name.clone(),
+                    "updated_at": mongodb::bson::DateTime::now()
+                }};
+                match collection
+                    .update_one(doc! {"_id": binding_id}, update_doc, None)
+                    .await
+                {
+                    Ok(_) => HttpResponse::Ok()
+                        .json(json!({"success": true, "message": "Binding updated"})),
+                    Err(e) => {
+                        eprintln!("Failed to update binding: {}", e);
+                        HttpResponse::InternalServerError()
+                            .json(json!({"success": false, "message": "Failed to update binding"}))
+                    }
+                }
+            } else {
+                eprintln!("Failed to create binding: {}", e);
+                HttpResponse::InternalServerError()
+                    .json(json!({"success": false, "message": "Failed to create binding"}))
+            }
         }
     }
 }
@@ -1235,6 +2076,80 @@ pub async fn get_indexes_data(admin: crate::jwt_auth::AdminUser, db: web::Data<D
     }
 }
 
+// --- Search Index API ---
+
+// GET /api/admin/search/status
+pub async fn get_search_index_status(_admin: crate::jwt_auth::AdminUser, db: web::Data<Database>) -> impl Responder {
+    let counts = match crate::search_index::entry_counts(db.get_ref()).await {
+        Ok(counts) => counts,
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(json!({
+                "success": false,
+                "message": format!("获取搜索索引状态失败: {}", e)
+            }))
+        }
+    };
+    let kinds: Vec<serde_json::Value> = crate::search_index::SEARCH_KINDS
+        .iter()
+        .map(|(kind, label)| json!({
+            "kind": kind,
+            "label": label,
+            "count": counts.get(*kind).copied().unwrap_or(0)
+        }))
+        .collect();
+
+    // 最近一次写入的条目时间，用于判断同步是否正常运行
+    let options = mongodb::options::FindOneOptions::builder()
+        .sort(doc! { "indexed_at": -1 })
+        .projection(doc! { "indexed_at": 1 })
+        .build();
+    let last_indexed_at = db
+        .collection::<mongodb::bson::Document>(crate::search_index::SEARCH_COLLECTION)
+        .find_one(doc! {}, options)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|entry| entry.get_datetime("indexed_at").ok().map(|t| t.timestamp_millis()));
+
+    HttpResponse::Ok().json(json!({
+        "success": true,
+        "data": {
+            "kinds": kinds,
+            "total": counts.values().sum::<u64>(),
+            "last_indexed_at": last_indexed_at,
+            "syncing": crate::search_index::is_syncing()
+        }
+    }))
+}
+
+// POST /api/admin/search/sync
+pub async fn sync_search_index(_admin: crate::jwt_auth::AdminUser, db: web::Data<Database>) -> impl Responder {
+    if crate::search_index::is_syncing() {
+        return HttpResponse::Ok().json(json!({
+            "success": false,
+            "message": "搜索索引正在同步中，请稍后再试"
+        }));
+    }
+
+    // 全量扫描耗时较长，放到后台执行
+    let indexer = crate::search_index::SearchIndexer::new(db.get_ref().clone());
+    tokio::spawn(async move {
+        match indexer.sync_with_lock().await {
+            Ok(Some(stats)) => println!(
+                "✅ 手动同步搜索索引完成: 扫描 {} 条，更新 {} 条，删除 {} 条",
+                stats.scanned, stats.indexed, stats.removed
+            ),
+            Ok(None) => println!("⏭️ 其他实例正在同步搜索索引，本次跳过"),
+            Err(e) => eprintln!("❌ 手动同步搜索索引失败: {}", e),
+        }
+    });
+
+    HttpResponse::Ok().json(json!({
+        "success": true,
+        "message": "已开始同步搜索索引"
+    }))
+}
+
 // GET /api/admin/statistics
 pub async fn get_statistics(admin: crate::jwt_auth::AdminUser, db: web::Data<Database>) -> impl Responder {
 
@@ -1362,7 +2277,7 @@ pub async fn stop_scheduled_task(admin: crate::jwt_auth::AdminUser,
 #[derive(Debug, Deserialize)]
 pub struct ScheduledTaskConfigRequest {
     pub enabled: bool,
-    pub interval_hours: Option<i32>,
+    pub max_concurrent: Option<i32>,
 }
 
 pub async fn update_scheduled_task_config(admin: crate::jwt_auth::AdminUser, 
@@ -1370,7 +2285,7 @@ pub async fn update_scheduled_task_config(admin: crate::jwt_auth::AdminUser,
     config: web::Json<ScheduledTaskConfigRequest>,
 ) -> impl Responder {
     match task_manager
-        .update_config(config.enabled, config.interval_hours)
+        .update_config(config.enabled, config.max_concurrent)
         .await
     {
         Ok(true) => HttpResponse::Ok().json(json!({
@@ -1438,218 +2353,186 @@ impl Default for BatchDeleteProgress {
     }
 }
 
-// 类型别名简化复杂类型
-type BatchDeleteProgressMap = std::collections::HashMap<
-    String,
-    (
-        BatchDeleteProgress,
-        String,
-        Option<tokio::task::JoinHandle<()>>,
-    ),
->;
-type BatchDeleteProgressStore = tokio::sync::RwLock<BatchDeleteProgressMap>;
-
-// 全局批量删除任务进度存储
-static BATCH_DELETE_PROGRESS: std::sync::OnceLock<BatchDeleteProgressStore> =
-    std::sync::OnceLock::new();
-
-// 初始化批量删除任务进度存储
-fn get_batch_delete_progress_store() -> &'static BatchDeleteProgressStore {
-    BATCH_DELETE_PROGRESS.get_or_init(|| tokio::sync::RwLock::new(std::collections::HashMap::new()))
+impl From<&crate::jobs::Job> for BatchDeleteProgress {
+    fn from(job: &crate::jobs::Job) -> Self {
+        // 已中断的任务对页面而言等同于已停止
+        let status = if job.status == crate::jobs::JOB_STATUS_INTERRUPTED {
+            crate::jobs::JOB_STATUS_STOPPED
+        } else {
+            job.status.as_str()
+        };
+        Self {
+            status: status.to_string(),
+            processed_count: job.progress.current,
+            deleted_count: job.progress.success,
+            total_count: job.progress.total,
+            log: job.log.clone(),
+        }
+    }
 }
 
 // 获取批量删除任务进度
-pub async fn get_batch_delete_progress(task_id: &str) -> Option<BatchDeleteProgress> {
-    let store = get_batch_delete_progress_store();
-    let progress_map = store.read().await;
-    progress_map
-        .get(task_id)
-        .map(|(progress, _, _)| progress.clone())
+pub async fn get_batch_delete_progress(db: &Database, task_id: &str) -> Option<BatchDeleteProgress> {
+    crate::jobs::get_job(db, task_id)
+        .await
+        .filter(|job| job.job_type == crate::jobs::JOB_TYPE_BATCH_DELETE_SOURCE)
+        .map(|job| BatchDeleteProgress::from(&job))
 }
 
 // 更新批量删除任务进度
-async fn update_batch_delete_progress(
-    task_id: &str,
-    progress: BatchDeleteProgress,
-    task_name: String,
-) {
-    let store = get_batch_delete_progress_store();
-    let mut progress_map = store.write().await;
-    if let Some((current_progress, current_name, handle)) = progress_map.get_mut(task_id) {
-        *current_progress = progress;
-        *current_name = task_name;
-        // 保持原有的handle不变，不需要克隆
-    } else {
-        progress_map.insert(task_id.to_string(), (progress, task_name, None));
+async fn update_batch_delete_progress(db: &Database, task_id: &str, progress: &BatchDeleteProgress) {
+    let job_progress = crate::jobs::JobProgress {
+        current: progress.processed_count,
+        total: progress.total_count,
+        success: progress.deleted_count,
+        failed: 0,
+    };
+    if let Err(e) = crate::jobs::update_job(db, task_id, &job_progress, &progress.log).await {
+        eprintln!("更新批量删除任务进度失败 {}: {}", task_id, e);
     }
 }
 
-// 停止批量删除任务
-pub async fn stop_batch_delete_task(task_id: &str) -> bool {
-    let store = get_batch_delete_progress_store();
-    let mut progress_map = store.write().await;
-
-    if let Some((mut progress, task_name, handle)) = progress_map.remove(task_id) {
-        // 取消任务
-        if let Some(task_handle) = handle {
-            task_handle.abort();
-        }
-
-        // 标记任务为已停止
-        progress.status = "stopped".to_string();
-        progress.log = "任务已手动停止".to_string();
-
-        // 将任务重新插入，但状态为已停止且清除句柄
-        progress_map.insert(task_id.to_string(), (progress, task_name, None));
-
-        true
-    } else {
-        false
-    }
+// 停止批量删除任务，由执行任务的实例在下一个检查点结束
+pub async fn stop_batch_delete_task(db: &Database, task_id: &str) -> bool {
+    crate::jobs::request_cancel(db, task_id).await.unwrap_or(false)
 }
 
 // 获取所有运行中的批量删除任务
-pub async fn get_all_batch_delete_tasks() -> Vec<serde_json::Value> {
-    let store = get_batch_delete_progress_store();
-    let progress_map = store.read().await;
-
-    let mut tasks = Vec::new();
-    for (task_id, (progress, task_name, _)) in progress_map.iter() {
-        tasks.push(json!({
-            "task_id": task_id,
-            "task_name": task_name,
-            "status": progress.status,
-            "processed_count": progress.processed_count,
-            "deleted_count": progress.deleted_count,
-            "total_count": progress.total_count,
-            "log": progress.log
-        }));
-    }
+pub async fn get_all_batch_delete_tasks(db: &Database) -> Vec<serde_json::Value> {
+    let running = crate::jobs::running_jobs(
+        db,
+        doc! { "job_type": crate::jobs::JOB_TYPE_BATCH_DELETE_SOURCE },
+    )
+    .await
+    .unwrap_or_default();
 
-    tasks
+    running
+        .iter()
+        .map(|job| {
+            let progress = BatchDeleteProgress::from(job);
+            json!({
+                "task_id": &job.job_id,
+                "task_name": &job.job_name,
+                "status": progress.status,
+                "processed_count": progress.processed_count,
+                "deleted_count": progress.deleted_count,
+                "total_count": progress.total_count,
+                "log": progress.log
+            })
+        })
+        .collect()
 }
 
-// 启动批量删除任务
-pub async fn start_batch_delete_source(db: web::Data<Database>, source_name: String) -> String {
-    let task_id = uuid::Uuid::new_v4().to_string();
-    let task_id_clone = task_id.clone();
-
-    let collection = db.collection::<Vod>("vods");
-    const BATCH_SIZE: i64 = 2000;
-
-    // 获取总视频数量
-    let total_count = match collection.count_documents(None, None).await {
-        Ok(count) => count as i64,
-        Err(e) => {
-            eprintln!("Failed to count vods: {}", e);
-
-            // 初始化失败状态
-            let failed_progress = BatchDeleteProgress {
-                status: "failed".to_string(),
-                processed_count: 0,
-                deleted_count: 0,
-                total_count: 0,
-                log: "无法获取视频总数".to_string(),
-            };
+// 批量删除任务租约名称及有效期（秒），多实例部署时同一时间只有一个实例执行
+const BATCH_DELETE_LOCK_NAME: &str = "batch_delete_source";
+const BATCH_DELETE_LOCK_TTL_SECS: i64 = 60;
+
+// 启动批量删除任务，job_id 不为空时重新执行该任务；任务结束或被停止时释放租约
+pub async fn start_batch_delete_source(
+    db: &Database,
+    source_name: String,
+    job_id: Option<String>,
+) -> Result<String, String> {
+    // 其他实例可能也在执行批量删除，通过租约保证全局只有一个任务
+    let lock = crate::leader_lock::LeaseLock::new(db, BATCH_DELETE_LOCK_NAME, BATCH_DELETE_LOCK_TTL_SECS);
+    let lease = match lock.acquire_guard().await {
+        Ok(Some(lease)) => lease,
+        Ok(None) => return Err("其他实例正在执行批量删除任务，请等待完成后重试".to_string()),
+        Err(e) => return Err(format!("获取批量删除任务锁失败: {}", e)),
+    };
 
-            update_batch_delete_progress(
+    // 重新执行时任务已由 jobs::retry_job 标记为运行中
+    let task_id = match job_id {
+        Some(job_id) => job_id,
+        None => {
+            let task_id = uuid::Uuid::new_v4().to_string();
+            crate::jobs::start_job(
+                db,
                 &task_id,
-                failed_progress,
-                format!("批量删除播放源: {}", source_name),
+                crate::jobs::JOB_TYPE_BATCH_DELETE_SOURCE,
+                &format!("批量删除播放源: {}", source_name),
+                doc! { "source_name": &source_name },
+                1,
             )
-            .await;
-            return task_id;
+            .await
+            .map_err(|e| format!("创建批量删除任务失败: {}", e))?;
+            task_id
         }
     };
 
-    let total_count_u64 = total_count as u64;
-
-    // 初始化进度
-    let initial_progress = BatchDeleteProgress {
-        status: "running".to_string(),
-        processed_count: 0,
-        deleted_count: 0,
-        total_count: total_count_u64,
-        log: "开始批量删除播放源任务".to_string(),
-    };
-
-    update_batch_delete_progress(
-        &task_id,
-        initial_progress,
-        format!("批量删除播放源: {}", source_name),
-    )
-    .await;
+    const BATCH_SIZE: i64 = 2000;
 
     // 启动后台任务
-    let db_clone = db.clone();
-    let source_name_clone = source_name.clone();
-    let task_handle = tokio::spawn(async move {
-        if let Err(e) =
-            execute_batch_delete_inner(db_clone, &task_id_clone, &source_name_clone, BATCH_SIZE)
-                .await
-        {
-            eprintln!("Batch delete failed: {}", e);
-
-            let failed_progress = BatchDeleteProgress {
-                status: "failed".to_string(),
-                processed_count: 0,
-                deleted_count: 0,
-                total_count: total_count_u64,
-                log: format!("批量删除失败: {}", e),
-            };
-            update_batch_delete_progress(
-                &task_id_clone,
-                failed_progress,
-                format!("批量删除播放源: {}", source_name_clone),
-            )
-            .await;
+    let db = db.clone();
+    let task_id_clone = task_id.clone();
+    tokio::spawn(async move {
+        let _heartbeat = crate::jobs::spawn_heartbeat(&db, &task_id_clone);
+        let mut progress = BatchDeleteProgress {
+            status: crate::jobs::JOB_STATUS_RUNNING.to_string(),
+            processed_count: 0,
+            deleted_count: 0,
+            total_count: 0,
+            log: "开始批量删除播放源任务".to_string(),
+        };
+
+        let status = match execute_batch_delete_inner(&db, &task_id_clone, &source_name, BATCH_SIZE, &lease, &mut progress).await {
+            Ok(status) => status,
+            Err(e) => {
+                eprintln!("Batch delete failed: {}", e);
+                progress.log = format!("批量删除失败: {}", e);
+                crate::jobs::JOB_STATUS_FAILED
+            }
+        };
+        let job_progress = crate::jobs::JobProgress {
+            current: progress.processed_count,
+            total: progress.total_count,
+            success: progress.deleted_count,
+            failed: 0,
+        };
+        if let Err(e) = crate::jobs::finish_job(&db, &task_id_clone, status, Some(&job_progress), &progress.log).await {
+            eprintln!("保存批量删除任务状态失败 {}: {}", task_id_clone, e);
         }
     });
 
-    // 将任务句柄存储到进度Map中
-    let store = get_batch_delete_progress_store();
-    let mut progress_map = store.write().await;
-    if let Some((_, _, handle_ref)) = progress_map.get_mut(&task_id) {
-        *handle_ref = Some(task_handle);
-    } else {
-        progress_map.insert(
-            task_id.clone(),
-            (
-                BatchDeleteProgress {
-                    status: "running".to_string(),
-                    processed_count: 0,
-                    deleted_count: 0,
-                    total_count: total_count_u64,
-                    log: "开始批量删除播放源任务".to_string(),
-                },
-                format!("批量删除播放源: {}", source_name),
-                Some(task_handle),
-            ),
-        );
-    }
-
-    task_id
+    Ok(task_id)
 }
 
-// 执行批量删除的核心逻辑
+// 执行批量删除的核心逻辑，返回任务的结束状态
 async fn execute_batch_delete_inner(
-    db: web::Data<Database>,
+    db: &Database,
     task_id: &str,
     source_name: &str,
     batch_size: i64,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    lease: &crate::leader_lock::LeaseGuard,
+    progress: &mut BatchDeleteProgress,
+) -> Result<&'static str, Box<dyn std::error::Error + Send + Sync>> {
     let collection = db.collection::<Vod>("vods");
 
-    let mut processed_count = 0u64;
-    let mut deleted_count = 0u64;
     let mut last_id: Option<mongodb::bson::oid::ObjectId> = None;
 
     // 获取总视频数量
-    let total_count = collection.count_documents(None, None).await?;
-    let total_count_u64 = total_count as u64;
+    progress.total_count = collection.count_documents(None, None).await?;
+    update_batch_delete_progress(db, task_id, progress).await;
 
     // 分批处理视频
     loop {
+        if crate::jobs::is_cancel_requested(db, task_id).await {
+            progress.log = format!(
+                "任务已手动停止：处理了 {} 个视频，删除了 {} 个播放源",
+                progress.processed_count, progress.deleted_count
+            );
+            return Ok(crate::jobs::JOB_STATUS_STOPPED);
+        }
+
+        // 租约被其他实例接管后不能再继续修改数据，标记为中断等待重试
+        if lease.is_lost() {
+            progress.log = format!(
+                "批量删除任务锁已丢失：处理了 {} 个视频，删除了 {} 个播放源",
+                progress.processed_count, progress.deleted_count
+            );
+            return Ok(crate::jobs::JOB_STATUS_INTERRUPTED);
+        }
+
         // 构建查询，使用大于last_id来获取下一批
         let mut filter = doc! {};
 
@@ -1666,23 +2549,6 @@ async fn execute_batch_delete_inner(
         let mut vods_in_batch: Vec<Vod> = cursor.try_collect().await.unwrap_or_else(|_| vec![]);
 
         if vods_in_batch.is_empty() {
-            // 更新最终状态
-            let completed_progress = BatchDeleteProgress {
-                status: "completed".to_string(),
-                processed_count: total_count_u64,
-                deleted_count,
-                total_count: total_count_u64,
-                log: format!(
-                    "批量删除完成：处理了 {} 个视频，删除了 {} 个播放源",
-                    processed_count, deleted_count
-                ),
-            };
-            update_batch_delete_progress(
-                task_id,
-                completed_progress,
-                format!("批量删除播放源: {}", source_name),
-            )
-            .await;
             break;
         }
 
@@ -1703,7 +2569,7 @@ async fn execute_batch_delete_inner(
                     if play_source.source_name != source_name {
                         new_play_urls.push(play_source.to_owned());
                     } else {
-                        deleted_count += 1;
+                        progress.deleted_count += 1;
                         has_changed = true;
                     }
                 }
@@ -1734,53 +2600,41 @@ async fn execute_batch_delete_inner(
                 }
             }
 
-            processed_count += 1;
-
-            // 每处理100个视频更新一次进度
-            if processed_count % 100 == 0 {
-                let progress = BatchDeleteProgress {
-                    status: "running".to_string(),
-                    processed_count,
-                    deleted_count,
-                    total_count: total_count_u64,
-                    log: format!(
-                        "正在处理中... 已处理 {}/{} 个视频",
-                        processed_count, total_count_u64
-                    ),
-                };
-                update_batch_delete_progress(
-                    task_id,
-                    progress,
-                    format!("批量删除播放源: {}", source_name),
-                )
-                .await;
+            progress.processed_count += 1;
+
+            // 每处理100个视频更新一次进度，并检查任务是否被停止
+            if progress.processed_count.is_multiple_of(100) {
+                if crate::jobs::is_cancel_requested(db, task_id).await {
+                    progress.log = format!(
+                        "任务已手动停止：处理了 {} 个视频，删除了 {} 个播放源",
+                        progress.processed_count, progress.deleted_count
+                    );
+                    return Ok(crate::jobs::JOB_STATUS_STOPPED);
+                }
+
+                progress.log = format!(
+                    "正在处理中... 已处理 {}/{} 个视频",
+                    progress.processed_count, progress.total_count
+                );
+                update_batch_delete_progress(db, task_id, progress).await;
             }
         }
 
         // 如果这一批没有达到BATCH_SIZE，说明已经处理完了所有数据
         if vods_in_batch.len() < batch_size as usize {
-            // 更新最终状态
-            let completed_progress = BatchDeleteProgress {
-                status: "completed".to_string(),
-                processed_count: total_count_u64,
-                deleted_count,
-                total_count: total_count_u64,
-                log: format!(
-                    "批量删除完成：处理了 {} 个视频，删除了 {} 个播放源",
-                    processed_count, deleted_count
-                ),
-            };
-            update_batch_delete_progress(
-                task_id,
-                completed_progress,
-                format!("批量删除播放源: {}", source_name),
-            )
-            .await;
             break;
         }
     }
 
-    Ok(())
+    // 更新最终状态
+    progress.status = crate::jobs::JOB_STATUS_COMPLETED.to_string();
+    progress.log = format!(
+        "批量删除完成：处理了 {} 个视频，删除了 {} 个播放源",
+        progress.processed_count, progress.deleted_count
+    );
+    progress.processed_count = progress.total_count.max(progress.processed_count);
+
+    Ok(crate::jobs::JOB_STATUS_COMPLETED)
 }
 
 // POST /api/admin/batch-delete-source
@@ -1798,7 +2652,7 @@ pub async fn batch_delete_source(admin: crate::jwt_auth::AdminUser,
     }
 
     // 检查是否存在正在运行的任务
-    let running_tasks = get_all_batch_delete_tasks().await;
+    let running_tasks = get_all_batch_delete_tasks(&db).await;
     let has_running = running_tasks.iter().any(|task| task["status"] == "running");
 
     if has_running {
@@ -1809,24 +2663,29 @@ pub async fn batch_delete_source(admin: crate::jwt_auth::AdminUser,
     }
 
     // 启动后台任务
-    let task_id = start_batch_delete_source(db, source_name.to_string()).await;
-
-    HttpResponse::Ok().json(json!({
-        "success": true,
-        "message": "批量删除任务已启动",
-        "task_id": task_id,
-        "source_name": source_name
-    }))
+    match start_batch_delete_source(&db, source_name.to_string(), None).await {
+        Ok(task_id) => HttpResponse::Ok().json(json!({
+            "success": true,
+            "message": "批量删除任务已启动",
+            "task_id": task_id,
+            "source_name": source_name
+        })),
+        Err(message) => HttpResponse::Conflict().json(json!({
+            "success": false,
+            "message": message
+        })),
+    }
 }
 
 // GET /api/admin/batch-delete/progress/{task_id}
 pub async fn get_batch_delete_progress_handler(admin: crate::jwt_auth::AdminUser, 
+    db: web::Data<Database>,
     path: web::Path<String>,
 ) -> impl Responder {
 
     let task_id = path.into_inner();
 
-    let progress = get_batch_delete_progress(&task_id)
+    let progress = get_batch_delete_progress(&db, &task_id)
         .await
         .unwrap_or_else(|| BatchDeleteProgress {
             status: "not_found".to_string(),
@@ -1843,9 +2702,11 @@ pub async fn get_batch_delete_progress_handler(admin: crate::jwt_auth::AdminUser
 }
 
 // GET /api/admin/batch-delete/running-tasks
-pub async fn get_running_batch_delete_tasks_handler(admin: crate::jwt_auth::AdminUser) -> impl Responder {
+pub async fn get_running_batch_delete_tasks_handler(admin: crate::jwt_auth::AdminUser,
+    db: web::Data<Database>,
+) -> impl Responder {
 
-    let tasks = get_all_batch_delete_tasks().await;
+    let tasks = get_all_batch_delete_tasks(&db).await;
 
     HttpResponse::Ok().json(json!({
         "success": true,
@@ -1855,17 +2716,18 @@ pub async fn get_running_batch_delete_tasks_handler(admin: crate::jwt_auth::Admi
 
 // POST /api/admin/batch-delete/stop/{task_id}
 pub async fn stop_batch_delete_task_handler(admin: crate::jwt_auth::AdminUser, 
+    db: web::Data<Database>,
     path: web::Path<String>,
 ) -> impl Responder {
 
     let task_id = path.into_inner();
 
-    let stopped = stop_batch_delete_task(&task_id).await;
+    let stopped = stop_batch_delete_task(&db, &task_id).await;
 
     if stopped {
         HttpResponse::Ok().json(json!({
             "success": true,
-            "message": "批量删除任务已成功停止"
+            "message": "已请求停止批量删除任务"
         }))
     } else {
         HttpResponse::NotFound().json(json!({
@@ -1875,6 +2737,1286 @@ pub async fn stop_batch_delete_task_handler(admin: crate::jwt_auth::AdminUser,
     }
 }
 
+// --- Bulk Video Operations API ---
+
+// 按筛选批量操作的动作
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum VodBulkAction {
+    SetType { type_id: i32 },
+    SetStatus { status: i32 },
+    SetVip { need_vip: i32 },
+    RemoveSource { source_name: String },
+    RenameSource { source_name: String, new_source_name: String },
+    Delete,
+}
+
+impl VodBulkAction {
+    fn describe(&self) -> String {
+        match self {
+            VodBulkAction::SetType { type_id } => format!("修改分类为 {}", type_id),
+            VodBulkAction::SetStatus { status } => format!("设置状态为 {}", status),
+            VodBulkAction::SetVip { need_vip } => format!("设置VIP等级为 {}", need_vip),
+            VodBulkAction::RemoveSource { source_name } => format!("删除播放源 {}", source_name),
+            VodBulkAction::RenameSource { source_name, new_source_name } => {
+                format!("重命名播放源 {} 为 {}", source_name, new_source_name)
+            }
+            VodBulkAction::Delete => "删除视频".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct VodBulkRequest {
+    #[serde(default)]
+    pub filter: VodFilter,
+    // 筛选条件为空时必须显式确认，才会作用于全部视频
+    #[serde(default)]
+    pub confirm_all: bool,
+    #[serde(flatten)]
+    pub action: VodBulkAction,
+}
+
+// 每批处理的视频数量
+const VOD_BULK_BATCH_SIZE: i64 = 500;
+
+// 校验批量操作参数
+async fn validate_vod_bulk_request(db: &Database, request: &VodBulkRequest) -> Result<(), String> {
+    if request.filter.to_document().is_empty() && !request.confirm_all {
+        return Err("未设置筛选条件，如需对全部视频操作请确认 confirm_all".to_string());
+    }
+
+    match &request.action {
+        VodBulkAction::SetType { type_id } => {
+            let exists = db
+                .collection::<Type>("types")
+                .find_one(doc! { "type_id": type_id }, None)
+                .await
+                .map_err(|e| format!("查询分类失败: {}", e))?
+                .is_some();
+            if !exists {
+                return Err("目标分类不存在".to_string());
+            }
+        }
+        VodBulkAction::SetStatus { status } => {
+            if !(0..=2).contains(status) {
+                return Err("状态必须在0-2之间".to_string());
+            }
+        }
+        VodBulkAction::SetVip { need_vip } => {
+            if !(0..=5).contains(need_vip) {
+                return Err("VIP等级必须在0-5之间".to_string());
+            }
+        }
+        VodBulkAction::RemoveSource { source_name } => {
+            if source_name.trim().is_empty() {
+                return Err("播放源名称不能为空".to_string());
+            }
+        }
+        VodBulkAction::RenameSource { source_name, new_source_name } => {
+            if source_name.trim().is_empty() || new_source_name.trim().is_empty() {
+                return Err("播放源名称不能为空".to_string());
+            }
+            if source_name.trim() == new_source_name.trim() {
+                return Err("新旧播放源名称相同".to_string());
+            }
+        }
+        VodBulkAction::Delete => {}
+    }
+    Ok(())
+}
+
+// 启动按筛选批量操作任务，job_id 不为空时重新执行该任务
+pub async fn start_vod_bulk(
+    db: &Database,
+    request: VodBulkRequest,
+    job_id: Option<String>,
+) -> Result<String, String> {
+    validate_vod_bulk_request(db, &request).await?;
+
+    // 重新执行时任务已由 jobs::retry_job 标记为运行中
+    let job_id = match job_id {
+        Some(job_id) => job_id,
+        None => {
+            let params = mongodb::bson::to_document(&request).map_err(|e| format!("保存任务参数失败: {}", e))?;
+            let job_id = uuid::Uuid::new_v4().to_string();
+            crate::jobs::start_job(
+                db,
+                &job_id,
+                crate::jobs::JOB_TYPE_VOD_BULK,
+                &format!("批量操作视频: {}", request.action.describe()),
+                params,
+                1,
+            )
+            .await
+            .map_err(|e| format!("创建批量操作任务失败: {}", e))?;
+            job_id
+        }
+    };
+
+    let db = db.clone();
+    let job_id_clone = job_id.clone();
+    tokio::spawn(async move {
+        let _heartbeat = crate::jobs::spawn_heartbeat(&db, &job_id_clone);
+        let mut progress = crate::jobs::JobProgress::default();
+
+        let (status, log) = match execute_vod_bulk(&db, &job_id_clone, &request, &mut progress).await {
+            Ok(status) if status == crate::jobs::JOB_STATUS_STOPPED => (
+                status,
+                format!("任务已手动停止：已处理 {}/{} 个视频，修改 {} 个", progress.current, progress.total, progress.success),
+            ),
+            Ok(status) => (
+                status,
+                format!("批量操作完成：处理了 {} 个视频，修改 {} 个", progress.current, progress.success),
+            ),
+            Err(e) => {
+                eprintln!("Vod bulk operation failed: {}", e);
+                (crate::jobs::JOB_STATUS_FAILED, format!("批量操作失败: {}", e))
+            }
+        };
+        if let Err(e) = crate::jobs::finish_job(&db, &job_id_clone, status, Some(&progress), &log).await {
+            eprintln!("保存批量操作任务状态失败 {}: {}", job_id_clone, e);
+        }
+    });
+
+    Ok(job_id)
+}
+
+// 按 _id 顺序分批处理符合筛选条件的视频，返回任务的结束状态
+async fn execute_vod_bulk(
+    db: &Database,
+    job_id: &str,
+    request: &VodBulkRequest,
+    progress: &mut crate::jobs::JobProgress,
+) -> Result<&'static str, Box<dyn std::error::Error + Send + Sync>> {
+    let collection = db.collection::<Document>("vods");
+    let filter = request.filter.to_document();
+
+    progress.total = collection.count_documents(filter.clone(), None).await?;
+    crate::jobs::update_job(db, job_id, progress, &format!("共 {} 个视频符合条件", progress.total)).await?;
+
+    let mut last_id: Option<mongodb::bson::oid::ObjectId> = None;
+    loop {
+        if crate::jobs::is_cancel_requested(db, job_id).await {
+            return Ok(crate::jobs::JOB_STATUS_STOPPED);
+        }
+
+        let mut batch_filter = filter.clone();
+        if let Some(last) = last_id {
+            batch_filter.insert("_id", doc! { "$gt": last });
+        }
+        let find_options = FindOptions::builder()
+            .sort(doc! { "_id": 1 })
+            .limit(VOD_BULK_BATCH_SIZE)
+            .projection(doc! { "_id": 1 })
+            .build();
+        let ids: Vec<mongodb::bson::oid::ObjectId> = collection
+            .find(batch_filter, find_options)
+            .await?
+            .try_collect::<Vec<Document>>()
+            .await?
+            .iter()
+            .filter_map(|vod| vod.get_object_id("_id").ok())
+            .collect();
+
+        let Some(last) = ids.last() else {
+            break;
+        };
+        last_id = Some(*last);
+
+        let batch_len = ids.len() as u64;
+        progress.success += apply_vod_bulk_action(db, ids, &request.action).await?;
+        progress.current += batch_len;
+        crate::jobs::update_job(
+            db,
+            job_id,
+            progress,
+            &format!("正在处理中... 已处理 {}/{} 个视频", progress.current, progress.total),
+        )
+        .await?;
+
+        if batch_len < VOD_BULK_BATCH_SIZE as u64 {
+            break;
+        }
+    }
+
+    Ok(crate::jobs::JOB_STATUS_COMPLETED)
+}
+
+// 对一批视频执行操作，返回实际修改（或删除）的数量
+async fn apply_vod_bulk_action(
+    db: &Database,
+    ids: Vec<mongodb::bson::oid::ObjectId>,
+    action: &VodBulkAction,
+) -> Result<u64, mongodb::error::Error> {
+    let collection = db.collection::<Document>("vods");
+    let by_ids = doc! { "_id": { "$in": &ids } };
+
+    let modified = match action {
+        VodBulkAction::SetType { type_id } => {
+            collection
+                .update_many(by_ids, doc! { "$set": { "type_id": type_id } }, None)
+                .await?
+                .modified_count
+        }
+        VodBulkAction::SetStatus { status } => {
+            let modified = collection
+                .update_many(by_ids, doc! { "$set": { "vod_status": status } }, None)
+                .await?
+                .modified_count;
+            // 上下架会改变视频是否可搜索
+            crate::search_index::refresh_entries(db, "vod", &ids).await;
+            modified
+        }
+        VodBulkAction::SetVip { need_vip } => {
+            collection
+                .update_many(by_ids, doc! { "$set": { "need_vip": need_vip } }, None)
+                .await?
+                .modified_count
+        }
+        VodBulkAction::RemoveSource { source_name } => {
+            collection
+                .update_many(
+                    by_ids,
+                    doc! { "$pull": { "vod_play_urls": { "source_name": source_name.trim() } } },
+                    None,
+                )
+                .await?
+                .modified_count
+        }
+        VodBulkAction::RenameSource { source_name, new_source_name } => {
+            // 已有同名目标播放源的视频跳过，避免同一视频出现重复的播放源
+            let mut filter = by_ids;
+            filter.insert(
+                "$and",
+                vec![
+                    doc! { "vod_play_urls.source_name": source_name.trim() },
+                    doc! { "vod_play_urls.source_name": { "$ne": new_source_name.trim() } },
+                ],
+            );
+            let options = UpdateOptions::builder()
+                .array_filters(vec![doc! { "source.source_name": source_name.trim() }])
+                .build();
+            collection
+                .update_many(
+                    filter,
+                    doc! { "$set": { "vod_play_urls.$[source].source_name": new_source_name.trim() } },
+                    options,
+                )
+                .await?
+                .modified_count
+        }
+        VodBulkAction::Delete => {
+            let deleted = collection.delete_many(by_ids, None).await?.deleted_count;
+            delete_vod_relations(db, &ids).await?;
+            deleted
+        }
+    };
+
+    Ok(modified)
+}
+
+// 删除视频关联的评分、收藏、观看记录、评论和搜索索引条目
+async fn delete_vod_relations(
+    db: &Database,
+    ids: &[mongodb::bson::oid::ObjectId],
+) -> Result<(), mongodb::error::Error> {
+    let vod_type = crate::history_handlers::media_type_bson(&crate::models::MediaType::Vod);
+    let by_media = doc! { "media_type": vod_type, "media_id": { "$in": ids } };
+
+    db.collection::<Document>(crate::rating_handlers::RATING_COLLECTION)
+        .delete_many(doc! { "vod_id": { "$in": ids } }, None)
+        .await?;
+    for collection in [
+        crate::favorite_handlers::FAVORITE_COLLECTION,
+        crate::history_handlers::HISTORY_COLLECTION,
+    ] {
+        db.collection::<Document>(collection)
+            .delete_many(by_media.clone(), None)
+            .await?;
+    }
+
+    // 评论的点赞记录按评论ID关联，需要先查出被删除的评论
+    let comments = db.collection::<Document>(crate::comment_handlers::COMMENT_COLLECTION);
+    let options = FindOptions::builder().projection(doc! { "_id": 1 }).build();
+    let comment_ids: Vec<mongodb::bson::oid::ObjectId> = comments
+        .find(by_media.clone(), options)
+        .await?
+        .try_collect::<Vec<_>>()
+        .await?
+        .iter()
+        .filter_map(|d| d.get_object_id("_id").ok())
+        .collect();
+    if !comment_ids.is_empty() {
+        comments
+            .delete_many(doc! { "_id": { "$in": &comment_ids } }, None)
+            .await?;
+        db.collection::<Document>(crate::comment_handlers::COMMENT_LIKE_COLLECTION)
+            .delete_many(doc! { "comment_id": { "$in": &comment_ids } }, None)
+            .await?;
+    }
+    crate::search_index::remove_entries(db, ids).await?;
+    Ok(())
+}
+
+// POST /api/admin/vods/bulk
+pub async fn bulk_vods(
+    _admin: crate::jwt_auth::AdminUser,
+    db: web::Data<Database>,
+    request: web::Json<VodBulkRequest>,
+) -> impl Responder {
+    let request = request.into_inner();
+
+    // 没有符合条件的视频时不创建任务
+    let matched = match db
+        .collection::<Vod>("vods")
+        .count_documents(request.filter.to_document(), None)
+        .await
+    {
+        Ok(count) => count,
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(json!({
+                "success": false,
+                "message": format!("查询视频失败: {}", e)
+            }));
+        }
+    };
+    if matched == 0 {
+        return HttpResponse::BadRequest().json(json!({
+            "success": false,
+            "message": "没有符合筛选条件的视频"
+        }));
+    }
+
+    match start_vod_bulk(&db, request, None).await {
+        Ok(job_id) => HttpResponse::Ok().json(json!({
+            "success": true,
+            "message": format!("批量操作任务已启动，共 {} 个视频符合条件", matched),
+            "job_id": job_id,
+            "matched": matched
+        })),
+        Err(message) => HttpResponse::BadRequest().json(json!({
+            "success": false,
+            "message": message
+        })),
+    }
+}
+
+// --- Background Jobs API ---
+
+#[derive(Deserialize)]
+pub struct JobQuery {
+    #[serde(rename = "type")]
+    pub job_type: Option<String>,
+    pub status: Option<String>,
+    pub page: Option<u64>,
+    pub limit: Option<i64>,
+}
+
+// GET /api/admin/jobs
+pub async fn get_jobs(
+    _admin: crate::jwt_auth::AdminUser,
+    db: web::Data<Database>,
+    query: web::Query<JobQuery>,
+) -> impl Responder {
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query.limit.unwrap_or(20).clamp(1, 100);
+
+    let mut filter = doc! {};
+    if let Some(job_type) = query.job_type.as_deref().filter(|t| !t.is_empty()) {
+        filter.insert("job_type", job_type);
+    }
+    if let Some(status) = query.status.as_deref().filter(|s| !s.is_empty()) {
+        filter.insert("status", status);
+    }
+
+    match crate::jobs::list_jobs(&db, filter, page, limit).await {
+        Ok((jobs, total)) => HttpResponse::Ok().json(json!({
+            "success": true,
+            "jobs": jobs,
+            "total": total,
+            "page": page,
+            "limit": limit
+        })),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "success": false,
+            "message": format!("查询后台任务失败: {}", e)
+        })),
+    }
+}
+
+// GET /api/admin/jobs/{job_id}
+pub async fn get_job_detail(
+    _admin: crate::jwt_auth::AdminUser,
+    db: web::Data<Database>,
+    path: web::Path<String>,
+) -> impl Responder {
+    let job_id = path.into_inner();
+
+    match crate::jobs::get_job(&db, &job_id).await {
+        Some(job) => HttpResponse::Ok().json(json!({
+            "success": true,
+            "job": job
+        })),
+        None => HttpResponse::NotFound().json(json!({
+            "success": false,
+            "message": "任务不存在"
+        })),
+    }
+}
+
+// POST /api/admin/jobs/{job_id}/cancel
+pub async fn cancel_job(
+    _admin: crate::jwt_auth::AdminUser,
+    db: web::Data<Database>,
+    path: web::Path<String>,
+) -> impl Responder {
+    let job_id = path.into_inner();
+
+    match crate::jobs::request_cancel(&db, &job_id).await {
+        Ok(true) => HttpResponse::Ok().json(json!({
+            "success": true,
+            "message": "已请求停止任务"
+        })),
+        Ok(false) => HttpResponse::NotFound().json(json!({
+            "success": false,
+            "message": "任务不存在或已经结束"
+        })),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "success": false,
+            "message": format!("停止任务失败: {}", e)
+        })),
+    }
+}
+
+// POST /api/admin/jobs/{job_id}/retry
+pub async fn retry_job(
+    _admin: crate::jwt_auth::AdminUser,
+    db: web::Data<Database>,
+    path: web::Path<String>,
+) -> impl Responder {
+    let job_id = path.into_inner();
+
+    match crate::jobs::retry_job(&db, &job_id).await {
+        Ok(true) => HttpResponse::Ok().json(json!({
+            "success": true,
+            "message": "任务已重新开始",
+            "job_id": job_id
+        })),
+        Ok(false) => HttpResponse::Ok().json(json!({
+            "success": true,
+            "message": "任务已被重新开始，无需重复操作",
+            "job_id": job_id
+        })),
+        Err(e) => HttpResponse::BadRequest().json(json!({
+            "success": false,
+            "message": e.to_string()
+        })),
+    }
+}
+
+// --- Audio Management API ---
+
+#[derive(Debug, Deserialize)]
+pub struct AudiosQuery {
+    pub page: Option<u64>,
+    pub limit: Option<u64>,
+    pub category: Option<String>,
+    pub status: Option<String>,
+    pub search: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AudioRequest {
+    pub title: String,
+    pub en_title: Option<String>,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub category: String,
+    pub cover: Option<String>,
+    #[serde(default)]
+    pub audios: Vec<String>,
+    #[serde(default)]
+    pub track_names: Vec<String>,
+    #[serde(default)]
+    pub need_vip: i32,
+    pub status: Option<String>,
+}
+
+impl AudioRequest {
+    // 校验并整理音轨，曲目名与音频地址一一对应，缺失的曲目名留空
+    fn normalized_tracks(&self) -> (Vec<String>, Vec<String>) {
+        let mut audios = Vec::new();
+        let mut track_names = Vec::new();
+        for (index, url) in self.audios.iter().enumerate() {
+            let url = url.trim();
+            if url.is_empty() {
+                continue;
+            }
+            audios.push(url.to_string());
+            track_names.push(
+                self.track_names
+                    .get(index)
+                    .map(|name| name.trim().to_string())
+                    .unwrap_or_default(),
+            );
+        }
+        (audios, track_names)
+    }
+
+    fn validate(&self) -> Result<(), &'static str> {
+        if self.title.trim().is_empty() {
+            return Err("专辑标题不能为空");
+        }
+        if self.category.trim().is_empty() {
+            return Err("分类不能为空");
+        }
+        if !(0..=5).contains(&self.need_vip) {
+            return Err("VIP等级必须在 0 到 5 之间");
+        }
+        if let Some(status) = &self.status
+            && !["processing", "published", "failed"].contains(&status.as_str())
+        {
+            return Err("无效的状态");
+        }
+        Ok(())
+    }
+}
+
+// GET /api/admin/audios
+pub async fn get_audios_admin(
+    _admin: crate::jwt_auth::AdminUser,
+    db: web::Data<Database>,
+    query: web::Query<AudiosQuery>,
+) -> impl Responder {
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query.limit.unwrap_or(20).clamp(1, 100);
+
+    let mut filter = doc! {};
+    if let Some(category) = query.category.as_deref().filter(|c| !c.is_empty()) {
+        filter.insert("category", category);
+    }
+    if let Some(status) = query.status.as_deref().filter(|s| !s.is_empty()) {
+        // 管理员创建的旧数据没有 status 字段，视为已发布
+        if status == "published" {
+            filter.insert("status", doc! { "$in": ["published", null] });
+        } else {
+            filter.insert("status", status);
+        }
+    }
+    if let Some(search) = query.search.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+        filter.insert("title", doc! { "$regex": regex::escape(search), "$options": "i" });
+    }
+
+    let collection = db.collection::<Audio>("audios");
+    let total = match collection.count_documents(filter.clone(), None).await {
+        Ok(count) => count,
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(json!({
+                "success": false,
+                "message": format!("查询音频失败: {}", e)
+            }));
+        }
+    };
+
+    let options = FindOptions::builder()
+        .sort(doc! { "created_at": -1 })
+        .skip((page - 1) * limit)
+        .limit(limit as i64)
+        .build();
+    match collection.find(filter, options).await {
+        Ok(cursor) => {
+            let audios: Vec<Audio> = cursor.try_collect().await.unwrap_or_default();
+            HttpResponse::Ok().json(json!({
+                "success": true,
+                "audios": audios,
+                "total": total,
+                "page": page,
+                "limit": limit
+            }))
+        }
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "success": false,
+            "message": format!("查询音频失败: {}", e)
+        })),
+    }
+}
+
+// GET /api/admin/audios/{id}
+pub async fn get_audio_admin(
+    _admin: crate::jwt_auth::AdminUser,
+    path: web::Path<String>,
+    db: web::Data<Database>,
+) -> impl Responder {
+    let Ok(audio_id) = mongodb::bson::oid::ObjectId::parse_str(path.into_inner()) else {
+        return HttpResponse::BadRequest().json(json!({"success": false, "message": "无效的音频ID"}));
+    };
+
+    match db.collection::<Audio>("audios").find_one(doc! { "_id": audio_id }, None).await {
+        Ok(Some(audio)) => HttpResponse::Ok().json(json!({"success": true, "audio": audio})),
+        Ok(None) => HttpResponse::NotFound().json(json!({"success": false, "message": "音频不存在"})),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "success": false,
+            "message": format!("查询音频失败: {}", e)
+        })),
+    }
+}
+
+// POST /api/admin/audios
+pub async fn create_audio(
+    _admin: crate::jwt_auth::AdminUser,
+    db: web::Data<Database>,
+    req: web::Json<AudioRequest>,
+) -> impl Responder {
+    if let Err(message) = req.validate() {
+        return HttpResponse::BadRequest().json(json!({"success": false, "message": message}));
+    }
+
+    let (audios, track_names) = req.normalized_tracks();
+    let now = mongodb::bson::DateTime::now();
+    let audio = Audio {
+        id: None,
+        title: req.title.trim().to_string(),
+        en_title: req.en_title.clone(),
+        description: req.description.clone(),
+        tags: req.tags.clone(),
+        category: req.category.trim().to_string(),
+        cover: crate::models::ImageItem {
+            url: req.cover.clone().unwrap_or_default(),
+            width: 0,
+            height: 0,
+        },
+        audios,
+        track_names,
+        need_vip: req.need_vip,
+        uploader: None,
+        status: req.status.clone().unwrap_or_else(|| "published".to_string()),
+        created_at: now,
+        updated_at: now,
+    };
+
+    match db.collection::<Audio>("audios").insert_one(&audio, None).await {
+        Ok(result) => {
+            if let Some(id) = result.inserted_id.as_object_id() {
+                crate::search_index::refresh_entries(&db, "audio", &[id]).await;
+            }
+            HttpResponse::Created().json(json!({
+                "success": true,
+                "message": "音频专辑创建成功",
+                "id": result.inserted_id
+            }))
+        }
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "success": false,
+            "message": format!("创建音频专辑失败: {}", e)
+        })),
+    }
+}
+
+// PUT /api/admin/audios/{id}
+pub async fn update_audio(
+    _admin: crate::jwt_auth::AdminUser,
+    path: web::Path<String>,
+    db: web::Data<Database>,
+    req: web::Json<AudioRequest>,
+) -> impl Responder {
+    let Ok(audio_id) = mongodb::bson::oid::ObjectId::parse_str(path.into_inner()) else {
+        return HttpResponse::BadRequest().json(json!({"success": false, "message": "无效的音频ID"}));
+    };
+    if let Err(message) = req.validate() {
+        return HttpResponse::BadRequest().json(json!({"success": false, "message": message}));
+    }
+
+    let (audios, track_names) = req.normalized_tracks();
+    let mut set_doc = doc! {
+        "title": req.title.trim(),
+        "en_title": &req.en_title,
+        "description": &req.description,
+        "tags": &req.tags,
+        "category": req.category.trim(),
+        "cover.url": req.cover.clone().unwrap_or_default(),
+        "audios": audios,
+        "track_names": track_names,
+        "need_vip": req.need_vip,
+        "updated_at": mongodb::bson::DateTime::now(),
+    };
+    if let Some(status) = &req.status {
+        set_doc.insert("status", status);
+    }
+
+    match db
+        .collection::<Audio>("audios")
+        .update_one(doc! { "_id": audio_id }, doc! { "$set": set_doc }, None)
+        .await
+    {
+        Ok(result) if result.matched_count > 0 => {
+            crate::search_index::refresh_entries(&db, "audio", &[audio_id]).await;
+            HttpResponse::Ok().json(json!({"success": true, "message": "音频专辑更新成功"}))
+        }
+        Ok(_) => HttpResponse::NotFound().json(json!({"success": false, "message": "音频不存在"})),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "success": false,
+            "message": format!("更新音频专辑失败: {}", e)
+        })),
+    }
+}
+
+// DELETE /api/admin/audios/{id}
+pub async fn delete_audio(
+    _admin: crate::jwt_auth::AdminUser,
+    path: web::Path<String>,
+    db: web::Data<Database>,
+) -> impl Responder {
+    let Ok(audio_id) = mongodb::bson::oid::ObjectId::parse_str(path.into_inner()) else {
+        return HttpResponse::BadRequest().json(json!({"success": false, "message": "无效的音频ID"}));
+    };
+
+    match db.collection::<Audio>("audios").delete_one(doc! { "_id": audio_id }, None).await {
+        Ok(result) if result.deleted_count > 0 => {
+            crate::search_index::refresh_entries(&db, "audio", &[audio_id]).await;
+            HttpResponse::Ok().json(json!({"success": true, "message": "音频专辑已删除"}))
+        }
+        Ok(_) => HttpResponse::NotFound().json(json!({"success": false, "message": "音频不存在"})),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "success": false,
+            "message": format!("删除音频专辑失败: {}", e)
+        })),
+    }
+}
+
+// --- Gallery Moderation API ---
+
+#[derive(Deserialize)]
+pub struct GalleryReviewQuery {
+    pub review_status: Option<String>,
+    pub status: Option<String>,
+    pub search: Option<String>,
+    pub page: Option<u64>,
+    pub limit: Option<u64>,
+}
+
+#[derive(Deserialize)]
+pub struct ReviewGalleriesRequest {
+    pub ids: Vec<String>,
+    pub action: String, // approve, reject, delete
+    pub reason: Option<String>,
+}
+
+// 按用户ID查询用户名，返回 ID(hex) -> 用户名
+async fn load_user_names(
+    db: &Database,
+    user_ids: &[mongodb::bson::oid::ObjectId],
+) -> std::collections::HashMap<String, String> {
+    let options = FindOptions::builder().projection(doc! { "user_name": 1 }).build();
+    match db
+        .collection::<Document>("users")
+        .find(doc! { "_id": { "$in": user_ids } }, options)
+        .await
+    {
+        Ok(cursor) => cursor
+            .try_collect::<Vec<Document>>()
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|u| {
+                Some((u.get_object_id("_id").ok()?.to_hex(), u.get_str("user_name").ok()?.to_string()))
+            })
+            .collect(),
+        Err(_) => Default::default(),
+    }
+}
+
+// GET /api/admin/images
+pub async fn get_galleries_admin(
+    _admin: crate::jwt_auth::AdminUser,
+    db: web::Data<Database>,
+    query: web::Query<GalleryReviewQuery>,
+) -> impl Responder {
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query.limit.unwrap_or(20).clamp(1, 100);
+
+    let mut filter = doc! {};
+    if let Some(review_status) = query.review_status.as_deref().filter(|s| !s.is_empty()) {
+        filter.insert("review_status", review_status);
+    }
+    if let Some(status) = query.status.as_deref().filter(|s| !s.is_empty()) {
+        filter.insert("status", status);
+    }
+    if let Some(search) = query.search.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+        filter.insert("title", doc! { "$regex": regex::escape(search), "$options": "i" });
+    }
+
+    let collection = db.collection::<Image>(crate::image_handlers::IMAGE_COLLECTION);
+    let total = match collection.count_documents(filter.clone(), None).await {
+        Ok(count) => count,
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(json!({
+                "success": false,
+                "message": format!("查询图集失败: {}", e)
+            }));
+        }
+    };
+
+    let options = FindOptions::builder()
+        .sort(doc! { "created_at": -1 })
+        .skip((page - 1) * limit)
+        .limit(limit as i64)
+        .build();
+    let images: Vec<Image> = match collection.find(filter, options).await {
+        Ok(cursor) => cursor.try_collect().await.unwrap_or_default(),
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(json!({
+                "success": false,
+                "message": format!("查询图集失败: {}", e)
+            }));
+        }
+    };
+
+    // 补充投稿人用户名
+    let uploader_ids: Vec<mongodb::bson::oid::ObjectId> = images.iter().map(|i| i.uploader).collect();
+    let uploader_names = load_user_names(&db, &uploader_ids).await;
+
+    // 补充章节所属连载的标题
+    let series_ids: Vec<mongodb::bson::oid::ObjectId> = images.iter().filter_map(|i| i.series_id).collect();
+    let series_options = FindOptions::builder().projection(doc! { "title": 1 }).build();
+    let series_titles: std::collections::HashMap<String, String> = match db
+        .collection::<Document>(crate::series_handlers::SERIES_COLLECTION)
+        .find(doc! { "_id": { "$in": &series_ids } }, series_options)
+        .await
+    {
+        Ok(cursor) => cursor
+            .try_collect::<Vec<Document>>()
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|s| {
+                Some((s.get_object_id("_id").ok()?.to_hex(), s.get_str("title").ok()?.to_string()))
+            })
+            .collect(),
+        Err(_) => Default::default(),
+    };
+
+    HttpResponse::Ok().json(json!({
+        "success": true,
+        "images": images,
+        "uploader_names": uploader_names,
+        "series_titles": series_titles,
+        "total": total,
+        "page": page,
+        "limit": limit
+    }))
+}
+
+// POST /api/admin/images/review
+pub async fn review_galleries(
+    _admin: crate::jwt_auth::AdminUser,
+    db: web::Data<Database>,
+    req: web::Json<ReviewGalleriesRequest>,
+) -> impl Responder {
+    let ids: Vec<mongodb::bson::oid::ObjectId> = req
+        .ids
+        .iter()
+        .filter_map(|id| mongodb::bson::oid::ObjectId::parse_str(id).ok())
+        .collect();
+    if ids.is_empty() {
+        return HttpResponse::BadRequest().json(json!({
+            "success": false,
+            "message": "请选择要处理的图集"
+        }));
+    }
+
+    let now = mongodb::bson::DateTime::now();
+    let update = match req.action.as_str() {
+        "approve" => doc! {
+            "$set": { "review_status": IMAGE_REVIEW_APPROVED, "reviewed_at": now, "updated_at": now },
+            "$unset": { "review_reason": "" },
+        },
+        "reject" => {
+            let Some(reason) = req.reason.as_deref().map(str::trim).filter(|r| !r.is_empty()) else {
+                return HttpResponse::BadRequest().json(json!({
+                    "success": false,
+                    "message": "请填写驳回原因"
+                }));
+            };
+            doc! {
+                "$set": {
+                    "review_status": IMAGE_REVIEW_REJECTED,
+                    "review_reason": reason,
+                    "reviewed_at": now,
+                    "updated_at": now,
+                },
+            }
+        }
+        "delete" => {
+            return match crate::image_handlers::delete_galleries(&db, &ids).await {
+                Ok(count) => HttpResponse::Ok().json(json!({
+                    "success": true,
+                    "message": format!("已删除 {} 个图集", count),
+                    "count": count
+                })),
+                Err(e) => HttpResponse::InternalServerError().json(json!({
+                    "success": false,
+                    "message": format!("删除图集失败: {}", e)
+                })),
+            };
+        }
+        _ => {
+            return HttpResponse::BadRequest().json(json!({
+                "success": false,
+                "message": "未知的操作类型"
+            }));
+        }
+    };
+
+    match db
+        .collection::<Image>(crate::image_handlers::IMAGE_COLLECTION)
+        .update_many(doc! { "_id": { "$in": &ids } }, update, None)
+        .await
+    {
+        Ok(result) => {
+            // 连载封面只取审核通过的章节
+            if req.action == "approve" {
+                crate::series_handlers::fill_series_covers(&db, &ids).await;
+            }
+            crate::search_index::refresh_entries(&db, "gallery", &ids).await;
+            HttpResponse::Ok().json(json!({
+                "success": true,
+                "message": format!("已处理 {} 个图集", result.modified_count),
+                "count": result.modified_count
+            }))
+        }
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "success": false,
+            "message": format!("审核图集失败: {}", e)
+        })),
+    }
+}
+
+#[derive(Deserialize)]
+pub struct SeriesReviewQuery {
+    pub review_status: Option<String>,
+    pub page: Option<u64>,
+    pub limit: Option<u64>,
+}
+
+// GET /api/admin/series
+pub async fn get_series_admin(
+    _admin: crate::jwt_auth::AdminUser,
+    db: web::Data<Database>,
+    query: web::Query<SeriesReviewQuery>,
+) -> impl Responder {
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query.limit.unwrap_or(20).clamp(1, 100);
+
+    let mut filter = doc! {};
+    if let Some(review_status) = query.review_status.as_deref().filter(|s| !s.is_empty()) {
+        filter.insert("review_status", review_status);
+    }
+
+    let collection = db.collection::<GallerySeries>(crate::series_handlers::SERIES_COLLECTION);
+    let total = match collection.count_documents(filter.clone(), None).await {
+        Ok(count) => count,
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(json!({
+                "success": false,
+                "message": format!("查询连载失败: {}", e)
+            }));
+        }
+    };
+    let options = FindOptions::builder()
+        .sort(doc! { "created_at": -1 })
+        .skip((page - 1) * limit)
+        .limit(limit as i64)
+        .build();
+    let series: Vec<GallerySeries> = match collection.find(filter, options).await {
+        Ok(cursor) => cursor.try_collect().await.unwrap_or_default(),
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(json!({
+                "success": false,
+                "message": format!("查询连载失败: {}", e)
+            }));
+        }
+    };
+
+    let uploader_ids: Vec<mongodb::bson::oid::ObjectId> = series.iter().map(|s| s.uploader).collect();
+    let uploader_names = load_user_names(&db, &uploader_ids).await;
+
+    HttpResponse::Ok().json(json!({
+        "success": true,
+        "series": series,
+        "uploader_names": uploader_names,
+        "total": total,
+        "page": page,
+        "limit": limit
+    }))
+}
+
+// POST /api/admin/series/review
+pub async fn review_series(
+    _admin: crate::jwt_auth::AdminUser,
+    db: web::Data<Database>,
+    req: web::Json<ReviewGalleriesRequest>,
+) -> impl Responder {
+    let ids: Vec<mongodb::bson::oid::ObjectId> = req
+        .ids
+        .iter()
+        .filter_map(|id| mongodb::bson::oid::ObjectId::parse_str(id).ok())
+        .collect();
+    if ids.is_empty() {
+        return HttpResponse::BadRequest().json(json!({
+            "success": false,
+            "message": "请选择要处理的连载"
+        }));
+    }
+
+    let now = mongodb::bson::DateTime::now();
+    let update = match req.action.as_str() {
+        "approve" => doc! {
+            "$set": { "review_status": IMAGE_REVIEW_APPROVED, "reviewed_at": now },
+            "$unset": { "review_reason": "" },
+        },
+        "reject" => {
+            let Some(reason) = req.reason.as_deref().map(str::trim).filter(|r| !r.is_empty()) else {
+                return HttpResponse::BadRequest().json(json!({
+                    "success": false,
+                    "message": "请填写驳回原因"
+                }));
+            };
+            doc! {
+                "$set": { "review_status": IMAGE_REVIEW_REJECTED, "review_reason": reason, "reviewed_at": now },
+            }
+        }
+        _ => {
+            return HttpResponse::BadRequest().json(json!({
+                "success": false,
+                "message": "未知的操作类型"
+            }));
+        }
+    };
+
+    match db
+        .collection::<GallerySeries>(crate::series_handlers::SERIES_COLLECTION)
+        .update_many(doc! { "_id": { "$in": &ids } }, update, None)
+        .await
+    {
+        Ok(result) => HttpResponse::Ok().json(json!({
+            "success": true,
+            "message": format!("已处理 {} 个连载", result.modified_count),
+            "count": result.modified_count
+        })),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "success": false,
+            "message": format!("审核连载失败: {}", e)
+        })),
+    }
+}
+
+// --- Comment Moderation API ---
+
+#[derive(Deserialize)]
+pub struct CommentAdminQuery {
+    pub status: Option<i32>,
+    pub media_type: Option<crate::models::MediaType>,
+    pub keyword: Option<String>,
+    pub page: Option<u64>,
+    pub limit: Option<u64>,
+}
+
+#[derive(Deserialize)]
+pub struct ModerateCommentsRequest {
+    pub ids: Vec<String>,
+    pub action: String, // approve, reject, delete
+}
+
+// GET /api/admin/comments
+pub async fn get_comments_admin(
+    _admin: crate::jwt_auth::AdminUser,
+    db: web::Data<Database>,
+    query: web::Query<CommentAdminQuery>,
+) -> impl Responder {
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query.limit.unwrap_or(20).clamp(1, 100);
+
+    let mut filter = doc! {};
+    if let Some(status) = query.status {
+        filter.insert("status", status);
+    }
+    if let Some(media_type) = &query.media_type {
+        filter.insert("media_type", crate::history_handlers::media_type_bson(media_type));
+    }
+    if let Some(keyword) = query.keyword.as_deref().map(str::trim).filter(|k| !k.is_empty()) {
+        let pattern = regex::escape(keyword);
+        filter.insert(
+            "$or",
+            vec![
+                doc! { "content": { "$regex": &pattern, "$options": "i" } },
+                doc! { "user_name": { "$regex": &pattern, "$options": "i" } },
+                doc! { "media_name": { "$regex": &pattern, "$options": "i" } },
+            ],
+        );
+    }
+
+    let collection = db.collection::<Comment>(crate::comment_handlers::COMMENT_COLLECTION);
+    let total = match collection.count_documents(filter.clone(), None).await {
+        Ok(count) => count,
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(json!({
+                "success": false,
+                "message": format!("查询评论失败: {}", e)
+            }));
+        }
+    };
+
+    let options = FindOptions::builder()
+        .sort(doc! { "created_at": -1 })
+        .skip((page - 1) * limit)
+        .limit(limit as i64)
+        .build();
+    match collection.find(filter, options).await {
+        Ok(cursor) => {
+            let comments: Vec<Comment> = cursor.try_collect().await.unwrap_or_default();
+            HttpResponse::Ok().json(json!({
+                "success": true,
+                "comments": comments,
+                "total": total,
+                "page": page,
+                "limit": limit
+            }))
+        }
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "success": false,
+            "message": format!("查询评论失败: {}", e)
+        })),
+    }
+}
+
+// POST /api/admin/comments/moderate
+pub async fn moderate_comments(
+    _admin: crate::jwt_auth::AdminUser,
+    db: web::Data<Database>,
+    req: web::Json<ModerateCommentsRequest>,
+) -> impl Responder {
+    let ids: Vec<mongodb::bson::oid::ObjectId> = req
+        .ids
+        .iter()
+        .filter_map(|id| mongodb::bson::oid::ObjectId::parse_str(id).ok())
+        .collect();
+    if ids.is_empty() {
+        return HttpResponse::BadRequest().json(json!({
+            "success": false,
+            "message": "请选择要处理的评论"
+        }));
+    }
+
+    let status = match req.action.as_str() {
+        "approve" => COMMENT_STATUS_APPROVED,
+        "reject" => COMMENT_STATUS_REJECTED,
+        "delete" => {
+            return match crate::comment_handlers::delete_comments(&db, &ids, None).await {
+                Ok(count) => HttpResponse::Ok().json(json!({
+                    "success": true,
+                    "message": format!("已删除 {} 条评论", count),
+                    "count": count
+                })),
+                Err(e) => HttpResponse::InternalServerError().json(json!({
+                    "success": false,
+                    "message": format!("删除评论失败: {}", e)
+                })),
+            };
+        }
+        _ => {
+            return HttpResponse::BadRequest().json(json!({
+                "success": false,
+                "message": "未知的操作类型"
+            }));
+        }
+    };
+
+    match db
+        .collection::<Comment>(crate::comment_handlers::COMMENT_COLLECTION)
+        .update_many(
+            doc! { "_id": { "$in": &ids } },
+            doc! { "$set": { "status": status, "updated_at": mongodb::bson::DateTime::now() } },
+            None,
+        )
+        .await
+    {
+        Ok(result) => HttpResponse::Ok().json(json!({
+            "success": true,
+            "message": format!("已处理 {} 条评论", result.modified_count),
+            "count": result.modified_count
+        })),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "success": false,
+            "message": format!("审核评论失败: {}", e)
+        })),
+    }
+}
+
+// POST /api/admin/comments/{comment_id}/ban-user
+// 禁用评论作者账号，并驳回其所有待审核评论
+pub async fn ban_comment_user(
+    _admin: crate::jwt_auth::AdminUser,
+    db: web::Data<Database>,
+    path: web::Path<String>,
+) -> impl Responder {
+    let Ok(comment_id) = mongodb::bson::oid::ObjectId::parse_str(path.into_inner()) else {
+        return HttpResponse::BadRequest().json(json!({
+            "success": false,
+            "message": "无效的评论ID"
+        }));
+    };
+
+    let comments = db.collection::<Comment>(crate::comment_handlers::COMMENT_COLLECTION);
+    let comment = match comments.find_one(doc! { "_id": comment_id }, None).await {
+        Ok(Some(comment)) => comment,
+        Ok(None) => {
+            return HttpResponse::NotFound().json(json!({
+                "success": false,
+                "message": "评论不存在"
+            }));
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(json!({
+                "success": false,
+                "message": format!("查询评论失败: {}", e)
+            }));
+        }
+    };
+
+    if let Err(e) = db
+        .collection::<User>("users")
+        .update_one(
+            doc! { "_id": comment.user_id },
+            doc! { "$set": { "user_status": 0, "updated_at": mongodb::bson::DateTime::now() } },
+            None,
+        )
+        .await
+    {
+        return HttpResponse::InternalServerError().json(json!({
+            "success": false,
+            "message": format!("禁用用户失败: {}", e)
+        }));
+    }
+
+    let rejected = comments
+        .update_many(
+            doc! { "user_id": comment.user_id, "status": COMMENT_STATUS_PENDING },
+            doc! { "$set": { "status": COMMENT_STATUS_REJECTED, "updated_at": mongodb::bson::DateTime::now() } },
+            None,
+        )
+        .await
+        .map(|r| r.modified_count)
+        .unwrap_or(0);
+
+    HttpResponse::Ok().json(json!({
+        "success": true,
+        "message": format!("已禁用用户 {}，驳回待审核评论 {} 条", comment.user_name, rejected),
+        "rejected": rejected
+    }))
+}
+
 // ============= 卡卷管理功能 =============
 
 // GET /admin/cards
@@ -1,36 +1,87 @@
 use actix_web::{web, HttpResponse, Responder};
 use mongodb::{Database, bson::doc, options::FindOptions};
-use crate::dto::{ApiParams, JsonResponse, VodApiListEntry, VodId, Category, VideoFilterParams, CategoryHierarchy};
+use crate::dto::{ApiParams, JsonResponse, VodApiBriefEntry, VodApiListEntry, VodId, Category, VideoFilterParams, CategoryHierarchy, RankParams};
+use crate::maccms_xml::{self, PLAY_SEPARATOR};
 use crate::models;
+use crate::site_data::{SiteDataManager, RANK_BOARDS, RANK_LIMIT};
 use futures::{StreamExt, TryStreamExt};
+use std::collections::HashMap;
+
+// 按 MacCMS 格式拼接播放源：vod_play_from 与 vod_play_url 均以 $$$ 分隔
+fn format_play_sources(sources: &[models::PlaySource]) -> (String, String) {
+    let play_from = sources
+        .iter()
+        .map(|s| s.source_name.as_str())
+        .collect::<Vec<_>>()
+        .join(PLAY_SEPARATOR);
+    let play_url = sources
+        .iter()
+        .map(|s| {
+            s.urls
+                .iter()
+                .map(|u| format!("{}${}", u.name, u.url))
+                .collect::<Vec<_>>()
+                .join("#")
+        })
+        .collect::<Vec<_>>()
+        .join(PLAY_SEPARATOR);
+    (play_from, play_url)
+}
+
+// 格式化为 MacCMS 的时间格式，如 2024-01-01 12:00:00
+fn format_vod_time(time: &mongodb::bson::DateTime) -> String {
+    chrono::DateTime::from_timestamp_millis(time.timestamp_millis())
+        .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_default()
+}
+
+// 关键词搜索最多匹配的视频数量
+const MAX_SEARCH_MATCHES: i64 = 1000;
 
 // The main handler for the vod collection API
-pub async fn provide_vod(params: web::Query<ApiParams>, db: web::Data<Database>) -> impl Responder {
+pub async fn provide_vod(
+    params: web::Query<ApiParams>,
+    db: web::Data<Database>,
+    site_data: web::Data<SiteDataManager>,
+) -> impl Responder {
     // Check for the format parameter, default to JSON
     let format = params.at.as_deref().unwrap_or("json");
+    // ac=detail/videolist 返回完整详情，其余按 ac=list 返回精简列表和分类
+    let detail = matches!(params.ac.as_deref(), Some("detail") | Some("videolist"));
 
     // Build the MongoDB filter based on query parameters
-    let mut filter = doc! {};
-    if let Some(wd) = &params.wd {
-        filter.insert("vod_name", doc! { "$regex": wd, "$options": "i" });
+    let mut filter = doc! { "vod_status": 1 };
+    if let Some(ids) = &params.ids {
+        let vod_ids: Vec<i64> = ids
+            .split(',')
+            .filter_map(|id| id.trim().parse().ok())
+            .collect();
+        filter.insert("vod_id", doc! { "$in": vod_ids });
+    }
+    if let Some(wd) = params.wd.as_deref().map(str::trim).filter(|wd| !wd.is_empty()) {
+        // 关键词走搜索索引（分词和拼音），命中结果仍按更新时间分页返回
+        let vod_ids = match crate::search_index::matching_ids(&db, wd, "vod", MAX_SEARCH_MATCHES).await {
+            Ok(ids) => ids,
+            Err(_) => return HttpResponse::InternalServerError().body("Failed to search videos"),
+        };
+        filter.insert("_id", doc! { "$in": vod_ids });
     }
     if let Some(t) = params.t {
         filter.insert("type_id", t);
     }
-    // The 'h' parameter logic is temporarily removed due to a dependency issue.
-    /*
-    if let Some(h) = params.h {
-        // Assuming h is in hours, calculate time in the past
-        let now = mongodb::bson::DateTime::now();
-        let past_time = now.to_chrono() - Duration::hours(h as i64);
-        filter.insert("vod_pubdate", doc! { "$gte": past_time });
+    if let Some(h) = params.h.filter(|h| *h > 0) {
+        // h 为小时数，只返回最近 h 小时内更新的视频
+        let since = mongodb::bson::DateTime::now().timestamp_millis() - (h as i64) * 3600 * 1000;
+        filter.insert(
+            "vod_pubdate",
+            doc! { "$gte": mongodb::bson::DateTime::from_millis(since) },
+        );
     }
-    */
 
     // --- Pagination --- 
-    let page = params.pg.unwrap_or(1);
-    let limit = params.pagesize.unwrap_or(20); // Default page size
-    let skip = if page > 0 { (page - 1) * limit } else { 0 };
+    let page = params.pg.unwrap_or(1).max(1);
+    let limit = params.pagesize.unwrap_or(20).clamp(1, 100); // Default page size
+    let skip = (page - 1) * limit;
 
     let find_options = FindOptions::builder()
         .skip(Some(skip))
@@ -45,7 +96,7 @@ pub async fn provide_vod(params: web::Query<ApiParams>, db: web::Data<Database>)
         Err(_) => return HttpResponse::InternalServerError().body("Failed to count documents"),
     };
 
-    let pagecount = if total > 0 { (total as f64 / limit as f64).ceil() as u64 } else { 0 };
+    let pagecount = total.div_ceil(limit);
 
     let cursor = match vod_collection.find(filter, find_options).await {
         Ok(cursor) => cursor,
@@ -58,16 +109,22 @@ pub async fn provide_vod(params: web::Query<ApiParams>, db: web::Data<Database>)
     };
 
     // --- Data Transformation --- 
-    // In a real app, you'd query the Type collection. For now, we'll use a placeholder.
+    let all_categories = site_data.get_all_categories().await;
+    let type_names: HashMap<i32, String> = all_categories
+        .iter()
+        .map(|t| (t.type_id, t.type_name.clone()))
+        .collect();
+
     let list: Vec<VodApiListEntry> = vod_docs.into_iter().map(|vod| {
+        let (vod_play_from, vod_play_url) = format_play_sources(&vod.vod_play_urls);
         VodApiListEntry {
-            vod_id: VodId::Number(vod.id.unwrap().timestamp().to_string().parse().unwrap_or(0)),
+            vod_id: VodId::Number(vod.vod_id.unwrap_or_default()),
             vod_name: vod.vod_name,
             type_id: vod.type_id,
-            type_name: Some("N/A".to_string()),
-            vod_time: vod.vod_pubdate.to_string(),
+            type_name: type_names.get(&vod.type_id).cloned(),
+            vod_time: format_vod_time(&vod.vod_pubdate),
             vod_remarks: vod.vod_remarks.unwrap_or_default(),
-            vod_play_from: vod.vod_play_urls.into_iter().map(|s| s.source_name).collect::<Vec<_>>().join(","),
+            vod_play_from,
             vod_status: Some(vod.vod_status),
             vod_letter: None,
             vod_color: None,
@@ -104,16 +161,16 @@ pub async fn provide_vod(params: web::Query<ApiParams>, db: web::Data<Database>)
             vod_points: None,
             vod_points_play: None,
             vod_points_down: None,
-            vod_hits: None,
-            vod_hits_day: None,
-            vod_hits_week: None,
-            vod_hits_month: None,
+            vod_hits: vod.vod_hits,
+            vod_hits_day: vod.vod_hits_day,
+            vod_hits_week: vod.vod_hits_week,
+            vod_hits_month: vod.vod_hits_month,
             vod_duration: None,
             vod_up: None,
             vod_down: None,
-            vod_score: None,
-            vod_score_all: None,
-            vod_score_num: None,
+            vod_score: vod.vod_score,
+            vod_score_all: vod.vod_score_all,
+            vod_score_num: vod.vod_score_num,
             vod_time_add: None,
             vod_time_hits: None,
             vod_time_make: None,
@@ -132,7 +189,7 @@ pub async fn provide_vod(params: web::Query<ApiParams>, db: web::Data<Database>)
             vod_content: vod.vod_content,
             vod_play_server: None,
             vod_play_note: None,
-            vod_play_url: None,
+            vod_play_url: Some(vod_play_url),
             vod_down_from: None,
             vod_down_server: None,
             vod_down_note: None,
@@ -141,25 +198,54 @@ pub async fn provide_vod(params: web::Query<ApiParams>, db: web::Data<Database>)
     }).collect();
 
     // --- Category List --- 
-    // Placeholder for category list. A real implementation would query the 'types' collection.
-    let categories: Vec<Category> = vec![];
+    // 列表模式附带启用的分类列表，详情模式不输出
+    let categories: Vec<Category> = if detail {
+        vec![]
+    } else {
+        all_categories
+            .into_iter()
+            .filter(|t| t.type_status == 1)
+            .map(|t| Category {
+                type_id: t.type_id,
+                type_name: t.type_name,
+                type_pid: t.type_pid,
+            })
+            .collect()
+    };
 
     // --- Response Formatting --- 
+    let response = JsonResponse {
+        code: 1,
+        msg: if detail { "数据列表" } else { "列表" }.to_string(),
+        page,
+        pagecount,
+        limit,
+        total,
+        list,
+        categories,
+    };
+
     if format == "xml" {
-        // TODO: Implement XML serialization using quick-xml
-        HttpResponse::Ok().content_type("application/xml").body("<rss><list><video><name>XML support coming soon</name></video></list></rss>")
-    } else {
-        let response = JsonResponse {
-            code: 1,
-            msg: "success".to_string(),
-            page,
-            pagecount,
-            limit,
-            total,
-            list,
-            categories,
-        };
+        match maccms_xml::render_vod_xml(&response, detail) {
+            Ok(xml) => HttpResponse::Ok().content_type("text/xml; charset=utf-8").body(xml),
+            Err(e) => {
+                eprintln!("Failed to render XML: {}", e);
+                HttpResponse::InternalServerError().body("Failed to render XML")
+            }
+        }
+    } else if detail {
         HttpResponse::Ok().json(response)
+    } else {
+        HttpResponse::Ok().json(JsonResponse {
+            code: response.code,
+            msg: response.msg,
+            page: response.page,
+            pagecount: response.pagecount,
+            limit: response.limit,
+            total: response.total,
+            list: response.list.iter().map(VodApiBriefEntry::from).collect(),
+            categories: response.categories,
+        })
     }
 }
 
@@ -221,6 +307,35 @@ pub async fn get_videos_by_type(
     }))
 }
 
+// API endpoint to get rankings (hot today / this week / this month / all time / score)
+pub async fn get_rankings(
+    query: web::Query<RankParams>,
+    site_data: web::Data<SiteDataManager>,
+) -> impl Responder {
+    let board = query.board.as_deref().unwrap_or("day");
+    let Some((board, board_name)) = RANK_BOARDS.iter().find(|(key, _)| *key == board) else {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "code": 0,
+            "msg": format!("Unknown board: {}", board)
+        }));
+    };
+    let type_id = query.type_id.unwrap_or(0);
+    let limit = query.limit.unwrap_or(RANK_LIMIT).clamp(1, RANK_LIMIT);
+
+    let mut list = site_data.get_ranking(board, type_id).await;
+    list.truncate(limit);
+
+    HttpResponse::Ok().json(serde_json::json!({
+        "code": 1,
+        "msg": "success",
+        "board": board,
+        "board_name": board_name,
+        "type_id": type_id,
+        "total": list.len(),
+        "list": list
+    }))
+}
+
 // API endpoint to get category hierarchy
 pub async fn get_category_hierarchy(db: web::Data<Database>) -> impl Responder {
     let type_collection = db.collection::<models::Type>("types");
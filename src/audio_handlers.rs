@@ -0,0 +1,421 @@
+use crate::dto::ApiResponse;
+use crate::dto::CreateProcessingJobRequest;
+use crate::jwt_auth::{AuthenticatedUser, OptionalAuthenticatedUser};
+use crate::models::{Audio, ImageItem};
+use crate::processing_service::ProcessingService;
+use crate::site_data::SiteDataManager;
+use actix_web::{HttpRequest, HttpResponse, Result, web};
+use futures::stream::TryStreamExt;
+use mongodb::Database;
+use mongodb::bson::{Document, doc, oid::ObjectId};
+use mongodb::options::FindOptions;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+pub const AUDIO_COLLECTION: &str = "audios";
+// 前台列表每页专辑数
+pub const AUDIOS_PER_PAGE: u64 = 24;
+
+/// 前台可见的专辑：已发布，或管理员创建的没有 status 字段的旧数据
+pub fn published_filter() -> Document {
+    doc! { "status": { "$in": ["published", null] } }
+}
+
+// 曲目名为空时按序号显示
+pub fn track_name(audio: &Audio, index: usize) -> String {
+    audio
+        .track_names
+        .get(index)
+        .filter(|name| !name.is_empty())
+        .cloned()
+        .unwrap_or_else(|| format!("第{}首", index + 1))
+}
+
+fn processing_service(db: &Database) -> ProcessingService {
+    ProcessingService::new(
+        db.collection::<crate::models::ProcessingJob>("processing_jobs"),
+        db.collection::<crate::models::BatchProcessingJob>("batch_processing_jobs"),
+        db.collection::<crate::models::ProcessingServerConfig>("processing_servers"),
+        db.collection::<crate::models::WebhookNotification>("webhook_notifications"),
+    )
+}
+
+fn webhook_secret() -> String {
+    std::env::var("WEBHOOK_SECRET").unwrap_or_else(|_| "your-webhook-secret".to_string())
+}
+
+// 音频投稿请求数据
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AudioSubmissionRequest {
+    pub title: String,
+    pub en_title: Option<String>,
+    pub description: Option<String>,
+    pub category: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub need_vip: i32,
+    pub upload_id: String,
+    pub server_id: String,
+}
+
+// 音频投稿响应数据
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AudioSubmissionResponse {
+    pub audio_id: String,
+    pub title: String,
+    pub status: String,
+    pub message: String,
+}
+
+// 处理音频投稿，创建 audio-convert 转码任务，完成后由 webhook 回填音轨
+pub async fn submit_audio(
+    user: AuthenticatedUser,
+    db: web::Data<Database>,
+    request: web::Json<AudioSubmissionRequest>,
+) -> Result<HttpResponse> {
+    let submission = request.into_inner();
+    let Some(uploader) = user.user.id else {
+        return Ok(HttpResponse::Unauthorized()
+            .json(ApiResponse::<()>::error("用户信息无效".to_string())));
+    };
+
+    if submission.title.trim().is_empty() {
+        return Ok(
+            HttpResponse::BadRequest().json(ApiResponse::<()>::error("标题不能为空".to_string()))
+        );
+    }
+    if submission.category.trim().is_empty() {
+        return Ok(
+            HttpResponse::BadRequest().json(ApiResponse::<()>::error("分类不能为空".to_string()))
+        );
+    }
+    if submission.upload_id.is_empty() || submission.server_id.is_empty() {
+        return Ok(
+            HttpResponse::BadRequest().json(ApiResponse::<()>::error("上传信息不完整".to_string()))
+        );
+    }
+    if !(0..=5).contains(&submission.need_vip) {
+        return Ok(HttpResponse::BadRequest()
+            .json(ApiResponse::<()>::error("VIP等级必须在 0 到 5 之间".to_string())));
+    }
+
+    // 创建专辑记录（处理中状态，不在前台显示）
+    let now = mongodb::bson::DateTime::now();
+    let audio_id = ObjectId::new();
+    let audio = Audio {
+        id: Some(audio_id),
+        title: submission.title.trim().to_string(),
+        en_title: submission.en_title.clone(),
+        description: submission.description.clone(),
+        tags: submission.tags.clone(),
+        category: submission.category.trim().to_string(),
+        cover: ImageItem {
+            url: String::new(),
+            width: 0,
+            height: 0,
+        },
+        audios: Vec::new(),
+        track_names: Vec::new(),
+        need_vip: submission.need_vip,
+        uploader: Some(uploader),
+        status: "processing".to_string(),
+        created_at: now,
+        updated_at: now,
+    };
+
+    if let Err(e) = db
+        .collection::<Audio>(AUDIO_COLLECTION)
+        .insert_one(&audio, None)
+        .await
+    {
+        eprintln!("保存音频记录失败: {}", e);
+        return Ok(HttpResponse::InternalServerError()
+            .json(ApiResponse::<()>::error("保存音频记录失败".to_string())));
+    }
+
+    let processing_request = CreateProcessingJobRequest {
+        job_type: "audio-convert".to_string(),
+        file_id: submission.upload_id.clone(),
+        parameters: json!({
+            "audio_id": audio_id.to_hex(),
+            "title": submission.title,
+            "category": submission.category,
+            "need_vip": submission.need_vip,
+            "uploader": uploader.to_hex(),
+        }),
+        webhook_url: Some(format!(
+            "{}/api/webhook/audio-processing",
+            std::env::var("HOST").unwrap_or_else(|_| "http://localhost:8080".to_string())
+        )),
+        webhook_secret: Some(webhook_secret()),
+        cms_id: "maccms".to_string(),
+    };
+
+    let (status, message) = match processing_service(&db)
+        .create_processing_job(processing_request)
+        .await
+    {
+        Ok(_) => ("processing", "投稿成功，音频正在转码中"),
+        Err(e) => {
+            // 转码任务创建失败，但专辑记录已保存
+            eprintln!("创建音频转码任务失败: {}", e);
+            ("pending", "投稿成功，但转码任务创建失败，请联系管理员")
+        }
+    };
+
+    Ok(HttpResponse::Ok().json(ApiResponse::success(AudioSubmissionResponse {
+        audio_id: audio_id.to_hex(),
+        title: submission.title,
+        status: status.to_string(),
+        message: message.to_string(),
+    })))
+}
+
+// 用户投稿列表查询参数
+#[derive(Debug, Deserialize)]
+pub struct UserAudioQuery {
+    pub page: Option<u64>,
+    pub limit: Option<u64>,
+    pub status: Option<String>,
+}
+
+// 获取当前用户投稿的音频专辑
+pub async fn get_user_audios(
+    user: AuthenticatedUser,
+    db: web::Data<Database>,
+    query: web::Query<UserAudioQuery>,
+) -> Result<HttpResponse> {
+    let Some(uploader) = user.user.id else {
+        return Ok(HttpResponse::Unauthorized()
+            .json(ApiResponse::<()>::error("用户信息无效".to_string())));
+    };
+
+    let mut filter = doc! { "uploader": uploader };
+    if let Some(status) = query.status.as_deref().filter(|s| !s.is_empty()) {
+        filter.insert("status", status);
+    }
+
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query.limit.unwrap_or(20).clamp(1, 100);
+    let collection = db.collection::<Audio>(AUDIO_COLLECTION);
+
+    let total = match collection.count_documents(filter.clone(), None).await {
+        Ok(count) => count,
+        Err(e) => {
+            eprintln!("查询音频总数失败: {}", e);
+            return Ok(HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error("查询失败".to_string())));
+        }
+    };
+
+    let options = FindOptions::builder()
+        .sort(doc! { "created_at": -1 })
+        .skip((page - 1) * limit)
+        .limit(limit as i64)
+        .build();
+    let audios: Vec<Audio> = match collection.find(filter, options).await {
+        Ok(cursor) => cursor.try_collect().await.unwrap_or_default(),
+        Err(e) => {
+            eprintln!("查询音频列表失败: {}", e);
+            return Ok(HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error("查询失败".to_string())));
+        }
+    };
+
+    Ok(HttpResponse::Ok().json(ApiResponse::success(json!({
+        "audios": audios,
+        "page": page,
+        "limit": limit,
+        "total": total,
+        "total_pages": total.div_ceil(limit)
+    }))))
+}
+
+// 获取专辑音轨，VIP 专辑需要满足 need_vip 要求才返回播放地址
+pub async fn get_audio_tracks(
+    db: web::Data<Database>,
+    site_data_manager: web::Data<SiteDataManager>,
+    path: web::Path<String>,
+    user: OptionalAuthenticatedUser,
+) -> Result<HttpResponse> {
+    if !crate::web_handlers::audio_enabled(&site_data_manager).await {
+        return Ok(HttpResponse::NotFound()
+            .json(json!({"success": false, "has_access": false, "message": "音频功能未开启"})));
+    }
+    let Ok(audio_id) = ObjectId::parse_str(path.into_inner()) else {
+        return Ok(HttpResponse::BadRequest()
+            .json(json!({"success": false, "has_access": false, "message": "无效的音频ID"})));
+    };
+
+    let mut filter = published_filter();
+    filter.insert("_id", audio_id);
+    let audio = match db
+        .collection::<Audio>(AUDIO_COLLECTION)
+        .find_one(filter, None)
+        .await
+    {
+        Ok(Some(audio)) => audio,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound()
+                .json(json!({"success": false, "has_access": false, "message": "音频不存在"})));
+        }
+        Err(e) => {
+            eprintln!("查询音频失败: {}", e);
+            return Ok(HttpResponse::InternalServerError()
+                .json(json!({"success": false, "has_access": false, "message": "服务器错误"})));
+        }
+    };
+
+    let (has_access, message) =
+        crate::web_handlers::check_media_vip_access(&db, user.0.as_ref(), audio.need_vip).await;
+    if !has_access {
+        return Ok(HttpResponse::Ok().json(json!({
+            "success": true,
+            "has_access": false,
+            "need_vip": audio.need_vip,
+            "message": message
+        })));
+    }
+
+    let tracks: Vec<serde_json::Value> = audio
+        .audios
+        .iter()
+        .enumerate()
+        .map(|(index, url)| json!({ "index": index, "name": track_name(&audio, index), "url": url }))
+        .collect();
+
+    Ok(HttpResponse::Ok().json(json!({
+        "success": true,
+        "has_access": true,
+        "message": message,
+        "tracks": tracks
+    })))
+}
+
+// 处理音频转码完成的 webhook
+pub async fn handle_audio_processing_webhook(
+    db: web::Data<Database>,
+    payload: web::Json<serde_json::Value>,
+    req: HttpRequest,
+) -> Result<HttpResponse> {
+    let payload = payload.into_inner();
+
+    // 使用投稿时下发的 webhook_secret 校验签名
+    let signature = req
+        .headers()
+        .get("X-Webhook-Signature")
+        .and_then(|h| h.to_str().ok())
+        .unwrap_or("");
+    let payload_str = serde_json::to_string(&payload).unwrap_or_default();
+    match processing_service(&db).verify_webhook_signature(&webhook_secret(), signature, &payload_str) {
+        Ok(true) => {}
+        Ok(false) => {
+            return Ok(HttpResponse::Unauthorized()
+                .json(ApiResponse::<()>::error("Invalid webhook signature".to_string())));
+        }
+        Err(e) => return Ok(HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e))),
+    }
+
+    let audio_id = match payload
+        .get("audio_id")
+        .and_then(|v| v.as_str())
+        .map(ObjectId::parse_str)
+    {
+        Some(Ok(id)) => id,
+        Some(Err(_)) => {
+            return Ok(HttpResponse::BadRequest()
+                .json(ApiResponse::<()>::error("无效的音频ID".to_string())));
+        }
+        None => {
+            return Ok(
+                HttpResponse::BadRequest().json(ApiResponse::<()>::error("缺少音频ID".to_string()))
+            );
+        }
+    };
+
+    let status = payload
+        .get("status")
+        .and_then(|v| v.as_str())
+        .unwrap_or("failed");
+
+    let update_doc = if status == "completed" {
+        let Some(results) = payload.get("results").and_then(|v| v.as_array()) else {
+            return Ok(HttpResponse::BadRequest()
+                .json(ApiResponse::<()>::error("处理结果格式错误".to_string())));
+        };
+
+        let mut audios = Vec::new();
+        let mut track_names = Vec::new();
+        for result in results {
+            if let Some(url) = result.get("url").and_then(|v| v.as_str()) {
+                audios.push(url.to_string());
+                track_names.push(
+                    result
+                        .get("name")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or_default()
+                        .to_string(),
+                );
+            }
+        }
+        if audios.is_empty() {
+            return Ok(HttpResponse::BadRequest()
+                .json(ApiResponse::<()>::error("处理结果中没有音轨".to_string())));
+        }
+
+        let mut set_doc = doc! {
+            "audios": audios,
+            "track_names": track_names,
+            "status": "published",
+            "updated_at": mongodb::bson::DateTime::now(),
+        };
+        // 转码服务从音频元数据中提取到封面时一并更新
+        if let Some(cover) = payload.get("cover")
+            && let Some(url) = cover.get("url").and_then(|v| v.as_str())
+        {
+            set_doc.insert(
+                "cover",
+                doc! {
+                    "url": url,
+                    "width": cover.get("width").and_then(|v| v.as_i64()).unwrap_or(0) as i32,
+                    "height": cover.get("height").and_then(|v| v.as_i64()).unwrap_or(0) as i32,
+                },
+            );
+        }
+        doc! { "$set": set_doc }
+    } else {
+        let error_message = payload
+            .get("error")
+            .and_then(|v| v.as_str())
+            .unwrap_or("处理失败");
+        doc! {
+            "$set": {
+                "status": "failed",
+                "error_message": error_message,
+                "updated_at": mongodb::bson::DateTime::now(),
+            }
+        }
+    };
+
+    match db
+        .collection::<Audio>(AUDIO_COLLECTION)
+        .update_one(doc! { "_id": audio_id }, update_doc, None)
+        .await
+    {
+        Ok(result) if result.matched_count > 0 => {
+            crate::search_index::refresh_entries(&db, "audio", &[audio_id]).await;
+            Ok(HttpResponse::Ok().json(ApiResponse::success(
+                if status == "completed" { "音频处理完成" } else { "已记录处理失败状态" },
+            )))
+        }
+        Ok(_) => {
+            Ok(HttpResponse::NotFound().json(ApiResponse::<()>::error("音频不存在".to_string())))
+        }
+        Err(e) => {
+            eprintln!("更新音频失败: {}", e);
+            Ok(HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error("更新音频失败".to_string())))
+        }
+    }
+}
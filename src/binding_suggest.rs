@@ -0,0 +1,211 @@
+use crate::dto::Category;
+use crate::models::{Binding, Collection, Type};
+use crate::vod_match::normalize_vod_name;
+use futures::stream::TryStreamExt;
+use mongodb::{bson::doc, Database};
+use serde::Serialize;
+use std::collections::HashMap;
+
+// 推荐的最低置信度，低于该值不给出建议
+const MIN_CONFIDENCE: f64 = 0.5;
+
+// 分类名称常见后缀，比较时忽略
+const NAME_SUFFIXES: &[&str] = &["片", "剧", "类", "频道", "专区", "视频"];
+
+// 常见的同义分类名称
+const NAME_SYNONYMS: &[(&str, &str)] = &[
+    ("连续剧", "电视剧"),
+    ("剧集", "电视剧"),
+    ("影片", "电影"),
+    ("卡通", "动漫"),
+    ("动画", "动漫"),
+    ("真人秀", "综艺"),
+    ("记录", "纪录"),
+];
+
+#[derive(Debug, Serialize)]
+pub struct BindingSuggestion {
+    pub external_id: String,
+    pub external_name: String,
+    pub local_type_id: Option<i32>,
+    pub local_type_name: Option<String>,
+    pub confidence: f64,
+    pub reason: String,
+}
+
+// 规范化分类名称：繁简、大小写、符号，同义词替换并去掉常见后缀
+fn normalize_type_name(name: &str) -> String {
+    let mut name = normalize_vod_name(name);
+    for (from, to) in NAME_SYNONYMS {
+        name = name.replace(from, to);
+    }
+    for suffix in NAME_SUFFIXES {
+        if name.chars().count() > suffix.chars().count() + 1
+            && let Some(stripped) = name.strip_suffix(suffix)
+        {
+            name = stripped.to_string();
+            break;
+        }
+    }
+    name
+}
+
+// 字符二元组的 Dice 相似度
+fn bigram_similarity(a: &str, b: &str) -> f64 {
+    let bigrams = |s: &str| -> Vec<(char, char)> {
+        let chars: Vec<char> = s.chars().collect();
+        chars.windows(2).map(|w| (w[0], w[1])).collect()
+    };
+    let (a_grams, b_grams) = (bigrams(a), bigrams(b));
+    if a_grams.is_empty() || b_grams.is_empty() {
+        return 0.0;
+    }
+
+    let mut remaining = b_grams.clone();
+    let mut common = 0;
+    for gram in &a_grams {
+        if let Some(pos) = remaining.iter().position(|g| g == gram) {
+            remaining.swap_remove(pos);
+            common += 1;
+        }
+    }
+    2.0 * common as f64 / (a_grams.len() + b_grams.len()) as f64
+}
+
+// 两个分类名称的相似度（0-1）
+fn name_similarity(external: &str, local: &str) -> f64 {
+    if normalize_vod_name(external) == normalize_vod_name(local) {
+        return 1.0;
+    }
+
+    let (a, b) = (normalize_type_name(external), normalize_type_name(local));
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    if a == b {
+        return 0.9;
+    }
+    if a.contains(&b) || b.contains(&a) {
+        return 0.7;
+    }
+    bigram_similarity(&a, &b)
+}
+
+// 为采集源中未绑定的外部分类推荐本地分类：
+// 优先参考其他采集源中同名外部分类的绑定，其次按名称相似度匹配本地分类
+pub async fn suggest_bindings(
+    db: &Database,
+    collection: &Collection,
+    categories: &[Category],
+) -> Result<Vec<BindingSuggestion>, mongodb::error::Error> {
+    let bindings_collection = db.collection::<Binding>("bindings");
+    let mut bindings: Vec<Binding> = bindings_collection
+        .find(doc! {}, None)
+        .await?
+        .try_collect()
+        .await?;
+
+    // 旧绑定没有外部名称，无法参与投票：用本源的分类列表补齐
+    for binding in bindings.iter_mut().filter(|b| {
+        b.source_flag == collection.collect_name
+            && b.external_name.as_deref().is_none_or(str::is_empty)
+    }) {
+        let Some(category) = categories
+            .iter()
+            .find(|c| c.type_id.to_string() == binding.external_id)
+        else {
+            continue;
+        };
+        bindings_collection
+            .update_one(
+                doc! {"_id": &binding.id},
+                doc! {"$set": {"external_name": &category.type_name}},
+                None,
+            )
+            .await?;
+        binding.external_name = Some(category.type_name.clone());
+    }
+    let types: Vec<Type> = db
+        .collection::<Type>("types")
+        .find(doc! {}, None)
+        .await?
+        .try_collect()
+        .await?;
+    let type_names: HashMap<i32, &str> = types
+        .iter()
+        .map(|t| (t.type_id, t.type_name.as_str()))
+        .collect();
+
+    let mut suggestions = Vec::new();
+    for category in categories {
+        let external_id = category.type_id.to_string();
+        let already_bound = bindings
+            .iter()
+            .any(|b| b.source_flag == collection.collect_name && b.external_id == external_id);
+        if already_bound {
+            continue;
+        }
+
+        // 其他采集源中同名分类的绑定投票
+        let key = normalize_type_name(&category.type_name);
+        let mut votes: HashMap<i32, u32> = HashMap::new();
+        for binding in &bindings {
+            let same_name = binding
+                .external_name
+                .as_deref()
+                .is_some_and(|name| normalize_type_name(name) == key);
+            if binding.source_flag != collection.collect_name
+                && same_name
+                && type_names.contains_key(&binding.local_type_id)
+            {
+                *votes.entry(binding.local_type_id).or_default() += 1;
+            }
+        }
+        let total_votes: u32 = votes.values().sum();
+        let voted = votes
+            .into_iter()
+            .max_by_key(|(type_id, count)| (*count, -type_id))
+            .map(|(type_id, count)| (type_id, 0.6 + 0.35 * count as f64 / total_votes as f64));
+
+        // 名称相似度，启用的分类优先
+        let similar = types
+            .iter()
+            .map(|t| {
+                let score = name_similarity(&category.type_name, &t.type_name);
+                (t, if t.type_status == 1 { score } else { score * 0.9 })
+            })
+            .filter(|(_, score)| *score >= MIN_CONFIDENCE)
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+
+        let name_reason = |score: f64| if score >= 0.9 { "名称一致" } else { "名称相似" };
+        let best = match (voted, similar) {
+            (Some((_, vote_score)), Some((t, score))) if score > vote_score => {
+                Some((t.type_id, score, name_reason(score)))
+            }
+            (Some((type_id, vote_score)), _) => Some((type_id, vote_score, "其他采集源的绑定")),
+            (None, Some((t, score))) => Some((t.type_id, score, name_reason(score))),
+            (None, None) => None,
+        };
+
+        suggestions.push(match best {
+            Some((type_id, confidence, reason)) => BindingSuggestion {
+                external_id,
+                external_name: category.type_name.clone(),
+                local_type_id: Some(type_id),
+                local_type_name: type_names.get(&type_id).map(|name| name.to_string()),
+                confidence: (confidence * 100.0).round() / 100.0,
+                reason: reason.to_string(),
+            },
+            None => BindingSuggestion {
+                external_id,
+                external_name: category.type_name.clone(),
+                local_type_id: None,
+                local_type_name: None,
+                confidence: 0.0,
+                reason: "没有匹配的本地分类".to_string(),
+            },
+        });
+    }
+
+    Ok(suggestions)
+}
@@ -1,8 +1,16 @@
-use crate::dto::{Category, JsonResponse, VideoListResponse, VodApiListEntry};
-use crate::models::{Binding, Collection, PlaySource, PlayUrl, Vod};
+use crate::dto::{ArtApiListEntry, Category, JsonResponse, VodApiListEntry};
+use crate::maccms_xml::{self, play_from_separator, PLAY_SEPARATOR};
+use crate::collect_rules::CollectRules;
+use crate::jobs::{self, Job, JobProgress};
+use crate::search_index;
+use crate::vod_match;
+use crate::models::{
+    Art, Binding, CollectDryRunReport, CollectTask, Collection, DryRunItem, DryRunMissingBinding,
+    PlaySource, PlayUrl, Vod,
+};
 use actix_web::{web, HttpResponse, Responder};
 use chrono::Timelike;
-use mongodb::bson::{doc, oid::ObjectId, DateTime};
+use mongodb::bson::{doc, oid::ObjectId, Bson, DateTime};
 use mongodb::Database;
 use reqwest;
 use serde::{Deserialize, Serialize};
@@ -12,9 +20,19 @@ use std::time::{SystemTime, UNIX_EPOCH};
 fn parse_play_urls(vod_play_from: &str, vod_play_url: &Option<String>) -> Vec<PlaySource> {
     let mut play_sources = Vec::new();
 
+    // MacCMS 标准格式：多个播放源及其地址均以 $$$ 分隔，一一对应
+    if let Some(play_url) = vod_play_url.as_deref().filter(|_| play_from_separator(vod_play_from) == PLAY_SEPARATOR) {
+        let urls: Vec<&str> = play_url.split(PLAY_SEPARATOR).collect();
+        for (i, source_name) in vod_play_from.split(PLAY_SEPARATOR).enumerate() {
+            let source_url = urls.get(i).copied().unwrap_or_default();
+            play_sources.extend(parse_play_urls(source_name, &Some(source_url.to_string())));
+        }
+        return play_sources;
+    }
+
     if let Some(play_url) = vod_play_url {
         // 按,符号分割播放源
-        let sources: Vec<&str> = vod_play_from.split(',').collect();
+        let sources: Vec<&str> = vod_play_from.split(play_from_separator(vod_play_from)).collect();
 
         // 如果play_url包含#号，说明是多集内容
         if play_url.contains('#') {
@@ -84,11 +102,13 @@ fn parse_play_urls(vod_play_from: &str, vod_play_url: &Option<String>) -> Vec<Pl
 #[derive(Deserialize)]
 pub struct CollectCategoriesQuery {
     url: String,
+    format: Option<i32>,
 }
 
 #[derive(Deserialize)]
 pub struct CollectVideosQuery {
     url: String,
+    format: Option<i32>,
     page: Option<u32>,
     limit: Option<u32>,
     #[serde(rename = "type")]
@@ -105,6 +125,8 @@ pub struct CollectStartRequest {
     collect_type: String,
     video_ids: Option<Vec<String>>,
     hours: Option<u32>,
+    #[serde(default)]
+    dry_run: bool, // 仅预览，不写入视频
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -130,122 +152,169 @@ impl Default for CollectProgress {
     }
 }
 
-#[derive(Serialize)]
-pub struct CollectProgressResponse {
-    success: bool,
-    progress: CollectProgress,
-}
+// 采集源数据格式
+pub const COLLECT_FORMAT_AUTO: i32 = 0;
+pub const COLLECT_FORMAT_JSON: i32 = 1;
+pub const COLLECT_FORMAT_XML: i32 = 2;
 
-// 类型别名简化复杂类型
-type TaskProgressMap = std::collections::HashMap<
-    String,
-    (CollectProgress, String, Option<tokio::task::JoinHandle<()>>),
->;
-type TaskProgressStore = tokio::sync::RwLock<TaskProgressMap>;
+// 文章采集源（Collection.collect_type，1 为视频）
+pub const COLLECT_TYPE_ART: i32 = 2;
 
-// 全局任务进度存储
-static TASK_PROGRESS: std::sync::OnceLock<TaskProgressStore> = std::sync::OnceLock::new();
+// 构建采集API地址，XML格式的采集源追加 at=xml
+fn build_api_url(base_url: &str, ac: &str, format: i32) -> String {
+    let mut api_url = base_url.to_string();
+    if api_url.contains('?') {
+        // 如果URL已包含?，检查是否以?结尾或已有参数
+        if !api_url.ends_with('?') && !api_url.ends_with('&') {
+            api_url.push('&');
+        }
+    } else {
+        api_url.push('?');
+    }
+    api_url.push_str("ac=");
+    api_url.push_str(ac);
 
-// 初始化任务进度存储
-fn get_task_progress_store() -> &'static TaskProgressStore {
-    TASK_PROGRESS.get_or_init(|| tokio::sync::RwLock::new(std::collections::HashMap::new()))
-}
+    if format == COLLECT_FORMAT_XML && !base_url.contains("at=xml") && !base_url.contains("/at/xml")
+    {
+        api_url.push_str("&at=xml");
+    }
 
-// 获取任务进度
-pub async fn get_task_progress(task_id: &str) -> Option<CollectProgress> {
-    let store = get_task_progress_store();
-    let progress_map = store.read().await;
-    progress_map
-        .get(task_id)
-        .map(|(progress, _, _)| progress.clone())
+    api_url
 }
 
-// 更新任务进度
-async fn update_task_progress(task_id: &str, progress: CollectProgress, collection_name: String) {
-    let store = get_task_progress_store();
-    let mut progress_map = store.write().await;
-    if let Some((current_progress, current_name, handle)) = progress_map.get_mut(task_id) {
-        *current_progress = progress;
-        *current_name = collection_name;
-        // 保持原有的handle不变，不需要克隆
+// 按采集源格式解析响应，自动模式下根据内容判断 JSON 或 XML
+pub fn parse_collect_response(
+    response_text: &str,
+    format: i32,
+) -> Result<JsonResponse<VodApiListEntry>, Box<dyn std::error::Error + Send + Sync>> {
+    let is_xml = match format {
+        COLLECT_FORMAT_JSON => false,
+        COLLECT_FORMAT_XML => true,
+        _ => response_text
+            .trim_start_matches('\u{feff}')
+            .trim_start()
+            .starts_with('<'),
+    };
+
+    if is_xml {
+        maccms_xml::parse_vod_xml(response_text)
     } else {
-        progress_map.insert(task_id.to_string(), (progress, collection_name, None));
+        Ok(serde_json::from_str(response_text)?)
     }
 }
 
-// 停止任务
-pub async fn stop_task(task_id: &str) -> bool {
-    let store = get_task_progress_store();
-    let mut progress_map = store.write().await;
-
-    if let Some((mut progress, collection_name, handle)) = progress_map.remove(task_id) {
-        // 取消任务
-        if let Some(task_handle) = handle {
-            task_handle.abort();
+impl From<&CollectProgress> for JobProgress {
+    fn from(progress: &CollectProgress) -> Self {
+        Self {
+            current: progress.current_page as u64,
+            total: progress.total_pages as u64,
+            success: progress.success as u64,
+            failed: progress.failed as u64,
         }
+    }
+}
 
-        // 标记任务为已停止
-        progress.status = "stopped".to_string();
-        progress.log = "任务已手动停止".to_string();
-
-        // 将任务重新插入，但状态为已停止且清除句柄
-        progress_map.insert(task_id.to_string(), (progress, collection_name, None));
-
-        true
-    } else {
-        false
+impl From<&Job> for CollectProgress {
+    fn from(job: &Job) -> Self {
+        // 已中断的任务对采集页面而言等同于已停止，可从断点继续
+        let status = if job.status == jobs::JOB_STATUS_INTERRUPTED {
+            jobs::JOB_STATUS_STOPPED
+        } else {
+            job.status.as_str()
+        };
+        Self {
+            status: status.to_string(),
+            current_page: job.progress.current as u32,
+            total_pages: job.progress.total as u32,
+            success: job.progress.success as u32,
+            failed: job.progress.failed as u32,
+            log: job.log.clone(),
+        }
     }
 }
 
-// 获取所有运行中的任务
-pub async fn get_all_running_tasks() -> Vec<serde_json::Value> {
-    let store = get_task_progress_store();
-    let progress_map = store.read().await;
+// 获取任务进度（来自后台任务记录，任意实例均可查询）
+pub async fn get_task_progress(db: &Database, task_id: &str) -> Option<CollectProgress> {
+    jobs::get_job(db, task_id)
+        .await
+        .filter(|job| job.job_type == jobs::JOB_TYPE_COLLECT)
+        .map(|job| CollectProgress::from(&job))
+}
 
-    let mut tasks = Vec::new();
-    let now = chrono::Utc::now();
+// 更新任务进度
+async fn update_task_progress(db: &Database, task_id: &str, progress: &CollectProgress) {
+    if let Err(e) = jobs::update_job(db, task_id, &progress.into(), &progress.log).await {
+        eprintln!("更新采集任务进度失败 {}: {}", task_id, e);
+    }
+}
 
-    for (task_id, (progress, collection_name, _)) in progress_map.iter() {
-        // 只返回运行中的任务
-        let should_include = progress.status == "running";
+// 停止任务，由执行任务的实例在下一个检查点结束采集
+pub async fn stop_task(db: &Database, task_id: &str) -> bool {
+    jobs::request_cancel(db, task_id).await.unwrap_or(false)
+}
 
-        if should_include {
-            tasks.push(serde_json::json!({
-                "task_id": task_id,
-                "collection_name": collection_name,
+// 获取所有运行中的任务
+pub async fn get_all_running_tasks(db: &Database) -> Vec<serde_json::Value> {
+    let running = jobs::running_jobs(db, doc! { "job_type": jobs::JOB_TYPE_COLLECT })
+        .await
+        .unwrap_or_default();
+
+    running
+        .iter()
+        .map(|job| {
+            let progress = CollectProgress::from(job);
+            let start_time = job
+                .started_at
+                .map(|t| {
+                    let local = chrono::DateTime::<chrono::Utc>::from(t.to_system_time())
+                        .with_timezone(&chrono::Local);
+                    format!("{:02}:{:02}:{:02}", local.hour(), local.minute(), local.second())
+                })
+                .unwrap_or_default();
+            serde_json::json!({
+                "task_id": &job.job_id,
+                "collection_name": &job.job_name,
                 "status": progress.status,
                 "current_page": progress.current_page,
                 "total_pages": progress.total_pages,
                 "success": progress.success,
                 "failed": progress.failed,
                 "log": progress.log,
-                "start_time": format!("{:02}:{:02}:{:02}", now.hour(), now.minute(), now.second())
-            }));
-        }
-    }
+                "start_time": start_time
+            })
+        })
+        .collect()
+}
 
-    tasks
+// 拉取采集源的分类列表
+pub async fn fetch_collect_categories(
+    collection: &Collection,
+) -> Result<Vec<Category>, Box<dyn std::error::Error + Send + Sync>> {
+    let api_url = build_api_url(&collection.collect_url, "list", collection.collect_format);
+    let response_text = reqwest::get(&api_url).await?.text().await?;
+    let api_response = parse_collect_response(&response_text, collection.collect_format)?;
+    if api_response.code != 1 {
+        return Err(format!("API返回错误: {}", api_response.msg).into());
+    }
+    Ok(api_response.categories)
 }
 
 // 获取采集源分类列表
 pub async fn get_collect_categories(query: web::Query<CollectCategoriesQuery>) -> impl Responder {
-    let mut api_url = query.url.clone();
-    if api_url.contains('?') {
-        // 如果URL已包含?，检查是否以?结尾或已有参数
-        if api_url.ends_with('?') {
-            api_url.push_str("ac=list");
-        } else {
-            api_url.push_str("&ac=list");
-        }
-    } else {
-        api_url.push_str("?ac=list");
-    }
+    let api_url = build_api_url(
+        &query.url,
+        "list",
+        query.format.unwrap_or(COLLECT_FORMAT_AUTO),
+    );
 
     match reqwest::get(&api_url).await {
         Ok(response) => match response.text().await {
             Ok(response_text) => {
                 // eprintln!("API Response: {}", response_text);
-                match serde_json::from_str::<JsonResponse<Category>>(&response_text) {
+                match parse_collect_response(
+                    &response_text,
+                    query.format.unwrap_or(COLLECT_FORMAT_AUTO),
+                ) {
                     Ok(api_response) => {
                         if api_response.code == 1 {
                             HttpResponse::Ok().json(serde_json::json!({
@@ -288,7 +357,8 @@ pub async fn get_collect_categories(query: web::Query<CollectCategoriesQuery>) -
 
 // 获取采集源视频列表
 pub async fn get_collect_videos(query: web::Query<CollectVideosQuery>) -> impl Responder {
-    let mut api_url = format!("{}?ac=detail", query.url);
+    let format = query.format.unwrap_or(COLLECT_FORMAT_AUTO);
+    let mut api_url = build_api_url(&query.url, "detail", format);
 
     // 添加查询参数
     let mut params = Vec::new();
@@ -312,7 +382,7 @@ pub async fn get_collect_videos(query: web::Query<CollectVideosQuery>) -> impl R
 
     match reqwest::get(&api_url).await {
         Ok(response) => match response.text().await {
-            Ok(response_text) => match serde_json::from_str::<VideoListResponse>(&response_text) {
+            Ok(response_text) => match parse_collect_response(&response_text, format) {
                 Ok(api_response) => {
                     if api_response.code == 1 {
                         let limit = query.limit.unwrap_or(20) as usize;
@@ -390,62 +460,25 @@ pub async fn start_collect_task(
         }
     };
 
-    // 初始化任务进度
-    let initial_progress = CollectProgress {
-        status: "running".to_string(),
-        current_page: 0,
-        total_pages: 1,
-        success: 0,
-        failed: 0,
-        log: "正在启动采集任务...".to_string(),
-    };
-    update_task_progress(
-        &task_id,
-        initial_progress.clone(),
-        collection.collect_name.clone(),
-    )
-    .await;
-
-    // 启动后台采集任务
+    // 启动后台采集任务，进度与结果由后台任务记录维护
     let db_clone = db.clone();
     let task_id_clone = task_id.clone();
-    let collection_name_clone = collection.collect_name.clone();
-    let handle = tokio::spawn(async move {
+    tokio::spawn(async move {
         let hours = request.hours.map(|h| h.to_string());
-        let task_id_for_closure = task_id_clone.clone();
-        match start_batch_collect(&db_clone, collection.clone(), hours, task_id_clone).await {
-            Ok(_) => {
-                // 任务正常完成
-                let mut progress = get_task_progress(&task_id_for_closure)
-                    .await
-                    .unwrap_or_default();
-                progress.status = "completed".to_string();
-                progress.log = format!(
-                    "采集完成，成功: {}，失败: {}",
-                    progress.success, progress.failed
-                );
-                update_task_progress(&task_id_for_closure, progress, collection_name_clone).await;
-            }
-            Err(e) => {
-                // 任务失败
-                let mut progress = get_task_progress(&task_id_for_closure)
-                    .await
-                    .unwrap_or_default();
-                progress.status = "failed".to_string();
-                progress.log = format!("采集失败: {}", e);
-                update_task_progress(&task_id_for_closure, progress, collection_name_clone).await;
-            }
+        if let Err(e) = start_batch_collect(
+            &db_clone,
+            collection,
+            hours,
+            task_id_clone,
+            request.dry_run,
+            jobs::JOB_TRIGGER_MANUAL,
+        )
+        .await
+        {
+            eprintln!("Batch collect failed: {}", e);
         }
     });
 
-    // 存储任务句柄
-    let store = get_task_progress_store();
-    let mut progress_map = store.write().await;
-    if let Some((progress, collection_name, _)) = progress_map.get_mut(&task_id) {
-        *progress_map.get_mut(&task_id).unwrap() =
-            (progress.clone(), collection_name.clone(), Some(handle));
-    }
-
     HttpResponse::Ok().json(serde_json::json!({
         "success": true,
         "task_id": task_id,
@@ -453,30 +486,6 @@ pub async fn start_collect_task(
     }))
 }
 
-// 获取采集进度
-pub async fn get_collect_progress(path: web::Path<String>) -> impl Responder {
-    let task_id = path.into_inner();
-
-    if let Some(progress) = get_task_progress(&task_id).await {
-        HttpResponse::Ok().json(CollectProgressResponse {
-            success: true,
-            progress,
-        })
-    } else {
-        HttpResponse::Ok().json(CollectProgressResponse {
-            success: false,
-            progress: CollectProgress {
-                status: "not_found".to_string(),
-                current_page: 0,
-                total_pages: 0,
-                success: 0,
-                failed: 0,
-                log: "任务不存在".to_string(),
-            },
-        })
-    }
-}
-
 // 带超时的HTTP请求
 async fn fetch_with_timeout(
     url: &str,
@@ -494,9 +503,25 @@ async fn fetch_with_timeout(
     }
 }
 
+// 解析分页信息，返回 (code, msg, total, limit)
+fn parse_page_info(
+    response_text: &str,
+    format: i32,
+    collect_type: i32,
+) -> Result<(i32, String, u64, u64), Box<dyn std::error::Error + Send + Sync>> {
+    if collect_type == COLLECT_TYPE_ART {
+        let api_response: JsonResponse<ArtApiListEntry> = serde_json::from_str(response_text)?;
+        return Ok((api_response.code, api_response.msg, api_response.total, api_response.limit));
+    }
+    let api_response = parse_collect_response(response_text, format)?;
+    Ok((api_response.code, api_response.msg, api_response.total, api_response.limit))
+}
+
 // 带重试的获取总页数函数
 async fn get_total_pages_with_retry(
     api_url: &str,
+    format: i32,
+    collect_type: i32,
     max_retries: usize,
     timeout_secs: u64,
 ) -> Result<u32, Box<dyn std::error::Error + Send + Sync>> {
@@ -508,15 +533,14 @@ async fn get_total_pages_with_retry(
         println!("🔄 获取总页数 (尝试 {}/{})", attempt, max_retries);
 
         match fetch_with_timeout(&first_page_url, timeout_secs).await {
-            Ok(response_text) => match serde_json::from_str::<VideoListResponse>(&response_text) {
-                Ok(api_response) => {
-                    if api_response.code == 1 {
-                        let total_pages =
-                            (api_response.total as f64 / api_response.limit as f64).ceil() as u32;
+            Ok(response_text) => match parse_page_info(&response_text, format, collect_type) {
+                Ok((code, msg, total, limit)) => {
+                    if code == 1 {
+                        let total_pages = (total as f64 / limit as f64).ceil() as u32;
                         println!("✅ 获取总页数成功: {} 页", total_pages);
                         return Ok(total_pages);
                     } else {
-                        let error = format!("API返回错误: {:?}", api_response);
+                        let error = format!("API返回错误: code={}, msg={}", code, msg);
                         println!("❌ {}", error);
                         last_error = Some(error.into());
                     }
@@ -545,41 +569,310 @@ async fn get_total_pages_with_retry(
     Err(last_error.unwrap_or_else(|| "未知错误".into()))
 }
 
-// 批量采集主函数
+// 采集任务状态（每次运行在 collect_tasks 集合中保留一条记录，task_id 即后台任务ID）
+pub const TASK_STATUS_RUNNING: i32 = 1;
+pub const TASK_STATUS_COMPLETED: i32 = 2;
+pub const TASK_STATUS_FAILED: i32 = 3;
+pub const TASK_STATUS_STOPPED: i32 = 4;
+
+fn task_status_code(job_status: &str) -> i32 {
+    match job_status {
+        jobs::JOB_STATUS_RUNNING => TASK_STATUS_RUNNING,
+        jobs::JOB_STATUS_COMPLETED => TASK_STATUS_COMPLETED,
+        jobs::JOB_STATUS_FAILED => TASK_STATUS_FAILED,
+        _ => TASK_STATUS_STOPPED,
+    }
+}
+
+// 创建或恢复采集任务记录
+async fn save_collect_task_started(
+    db: &Database,
+    task_id: &str,
+    collection: &Collection,
+    hours: &Option<String>,
+    dry_run: bool,
+) -> Result<(), mongodb::error::Error> {
+    let now = DateTime::now();
+    let options = mongodb::options::UpdateOptions::builder()
+        .upsert(true)
+        .build();
+
+    db.collection::<CollectTask>("collect_tasks")
+        .update_one(
+            doc! { "task_id": task_id },
+            doc! {
+                "$set": {
+                    "task_status": TASK_STATUS_RUNNING,
+                    "task_log": "正在获取总页数...",
+                    "updated_at": now,
+                },
+                "$setOnInsert": {
+                    "task_name": &collection.collect_name,
+                    "collect_id": collection.id,
+                    "task_hours": hours,
+                    "dry_run": dry_run,
+                    "task_progress": 0,
+                    "task_total": 0,
+                    "task_success": 0,
+                    "task_failed": 0,
+                    "current_page": 0,
+                    "created_at": now,
+                }
+            },
+            options,
+        )
+        .await?;
+
+    Ok(())
+}
+
+// 同步采集任务记录的进度，finished_page 为已完整采集的页码，None 表示只更新状态和计数
+async fn save_collect_task_progress(
+    db: &Database,
+    task_id: &str,
+    task_status: i32,
+    progress: &CollectProgress,
+    finished_page: Option<u32>,
+) -> Result<(), mongodb::error::Error> {
+    let mut update = doc! {
+        "task_status": task_status,
+        "task_total": progress.total_pages as i32,
+        "task_success": progress.success as i32,
+        "task_failed": progress.failed as i32,
+        "task_log": &progress.log,
+        "updated_at": DateTime::now(),
+    };
+    if let Some(page) = finished_page {
+        let task_progress = (page * 100).checked_div(progress.total_pages).unwrap_or(0) as i32;
+        update.insert("current_page", page as i32);
+        update.insert("task_progress", task_progress);
+    }
+
+    db.collection::<CollectTask>("collect_tasks")
+        .update_one(doc! { "task_id": task_id }, doc! { "$set": update }, None)
+        .await?;
+
+    Ok(())
+}
+
+// 所在实例退出而中断的采集任务同步标记为已中断，便于之后恢复
+pub async fn mark_interrupted_collect_tasks(
+    db: &Database,
+    task_ids: &[String],
+) -> Result<u64, mongodb::error::Error> {
+    if task_ids.is_empty() {
+        return Ok(0);
+    }
+
+    let result = db
+        .collection::<CollectTask>("collect_tasks")
+        .update_many(
+            doc! { "task_id": { "$in": task_ids }, "task_status": TASK_STATUS_RUNNING },
+            doc! {
+                "$set": {
+                    "task_status": TASK_STATUS_STOPPED,
+                    "task_log": "任务所在实例已退出，任务已中断，可从断点继续采集",
+                    "updated_at": DateTime::now(),
+                }
+            },
+            None,
+        )
+        .await?;
+
+    Ok(result.modified_count)
+}
+
+// 采集检查点，保存在后台任务的 params.checkpoint 中，恢复时从 page 的下一页继续
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct CollectCheckpoint {
+    pub page: u32, // 已完整采集的页码
+    pub success: u32,
+    pub failed: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dry_run_report: Option<CollectDryRunReport>, // 预览报告与检查点一同保存，恢复时继续累计
+}
+
+impl CollectCheckpoint {
+    fn from_job(job: &Job) -> Option<Self> {
+        let checkpoint = job.params.get_document("checkpoint").ok()?;
+        mongodb::bson::from_document(checkpoint.clone()).ok()
+    }
+}
+
+// 保存采集进度检查点，finished_page 为已完整采集的页码
+async fn save_collect_checkpoint(
+    db: &Database,
+    task_id: &str,
+    progress: &CollectProgress,
+    finished_page: u32,
+    report: Option<&CollectDryRunReport>,
+) -> Result<(), mongodb::error::Error> {
+    let checkpoint = CollectCheckpoint {
+        page: finished_page,
+        success: progress.success,
+        failed: progress.failed,
+        dry_run_report: report.cloned(),
+    };
+    jobs::save_checkpoint(db, task_id, mongodb::bson::to_document(&checkpoint)?).await?;
+    save_collect_task_progress(db, task_id, TASK_STATUS_RUNNING, progress, Some(finished_page)).await
+}
+
+// 读取预览任务的报告（最近一次检查点）
+pub async fn get_dry_run_report(db: &Database, task_id: &str) -> Option<CollectDryRunReport> {
+    jobs::get_job(db, task_id)
+        .await
+        .filter(|job| job.job_type == jobs::JOB_TYPE_COLLECT && job.params.get_bool("dry_run").unwrap_or(false))
+        .and_then(|job| CollectCheckpoint::from_job(&job))?
+        .dry_run_report
+}
+
+// 从断点恢复采集任务，沿用原任务ID；返回 false 表示任务已被重新开始
+pub async fn resume_collect_task(
+    db: &Database,
+    task_id: &str,
+) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+    let job = jobs::get_job(db, task_id)
+        .await
+        .filter(|job| job.job_type == jobs::JOB_TYPE_COLLECT)
+        .ok_or("任务不存在")?;
+    if !job.is_finished() {
+        return Err("任务正在运行中".into());
+    }
+    jobs::retry_job(db, task_id).await
+}
+
+// 继续执行已由 jobs::retry_job 标记为运行中的采集任务，从检查点的下一页开始
+pub async fn resume_claimed_collect(
+    db: &Database,
+    job: &Job,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let collection_id = job.params.get_object_id("collection_id").map_err(|_| "任务参数缺少采集源")?;
+    let collection = db
+        .collection::<Collection>("collections")
+        .find_one(doc! { "_id": collection_id }, None)
+        .await?
+        .ok_or("采集源不存在")?;
+
+    let hours = job.params.get_str("hours").ok().map(|h| h.to_string());
+    let dry_run = job.params.get_bool("dry_run").unwrap_or(false);
+    let checkpoint = CollectCheckpoint::from_job(job).unwrap_or_default();
+
+    let db = db.clone();
+    let task_id = job.job_id.clone();
+    tokio::spawn(async move {
+        if let Err(e) = run_batch_collect(&db, collection, hours, task_id, dry_run, Some(checkpoint)).await {
+            eprintln!("Resume collect failed: {}", e);
+        }
+    });
+
+    Ok(())
+}
+
+// 批量采集主函数，trigger 为任务的触发方式（手动、定时）
 pub async fn start_batch_collect(
     db: &Database,
     collection: Collection,
     hours: Option<String>,
     task_id: String,
+    dry_run: bool,
+    trigger: &str,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    // 初始化任务进度
-    let initial_progress = CollectProgress {
-        status: "running".to_string(),
-        current_page: 0,
-        total_pages: 1,
-        success: 0,
-        failed: 0,
-        log: "正在获取总页数...".to_string(),
+    let params = doc! {
+        "collection_id": collection.id,
+        "hours": &hours,
+        "dry_run": dry_run,
+        "trigger": trigger,
     };
-    update_task_progress(
+    let max_attempts = if trigger == jobs::JOB_TRIGGER_SCHEDULE { 3 } else { 1 };
+    jobs::start_job(
+        db,
         &task_id,
-        initial_progress.clone(),
-        collection.collect_name.clone(),
+        jobs::JOB_TYPE_COLLECT,
+        &collection.collect_name,
+        params,
+        max_attempts,
     )
-    .await;
+    .await?;
 
-    // 构建API URL
-    let mut api_url = collection.collect_url.clone();
-    if api_url.contains('?') {
-        // 如果URL已包含?，检查是否以?结尾或已有参数
-        if api_url.ends_with('?') {
-            api_url.push_str("ac=detail");
-        } else {
-            api_url.push_str("&ac=detail");
+    run_batch_collect(db, collection, hours, task_id, dry_run, None).await
+}
+
+// 执行已标记为运行中的批量采集任务，负责心跳、采集任务记录与结束状态
+// 定时触发的任务失败或中断后由后台任务维护自动重试
+async fn run_batch_collect(
+    db: &Database,
+    collection: Collection,
+    hours: Option<String>,
+    task_id: String,
+    dry_run: bool,
+    resume: Option<CollectCheckpoint>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let _heartbeat = jobs::spawn_heartbeat(db, &task_id);
+    if let Err(e) = save_collect_task_started(db, &task_id, &collection, &hours, dry_run).await {
+        eprintln!("保存采集任务记录失败 {}: {}", task_id, e);
+    }
+
+    let (start_page, success, failed) = resume
+        .as_ref()
+        .map(|c| (c.page + 1, c.success, c.failed))
+        .unwrap_or((1, 0, 0));
+    let mut progress = CollectProgress {
+        status: jobs::JOB_STATUS_RUNNING.to_string(),
+        current_page: start_page - 1,
+        total_pages: 1,
+        success,
+        failed,
+        log: "正在获取总页数...".to_string(),
+    };
+
+    let result = collect_pages(db, &collection, hours, &task_id, dry_run, resume, &mut progress).await;
+    let status = match &result {
+        Ok(status) => *status,
+        Err(e) => {
+            progress.log = format!("采集失败: {}", e);
+            jobs::JOB_STATUS_FAILED
         }
-    } else {
-        api_url.push_str("?ac=detail");
+    };
+    progress.status = status.to_string();
+    if let Err(e) = jobs::finish_job(db, &task_id, status, Some(&(&progress).into()), &progress.log).await {
+        eprintln!("保存采集任务状态失败 {}: {}", task_id, e);
     }
+    if let Err(e) = save_collect_task_progress(db, &task_id, task_status_code(status), &progress, None).await {
+        eprintln!("保存采集任务记录失败 {}: {}", task_id, e);
+    }
+
+    result.map(|_| ())
+}
+
+// 逐页执行批量采集，resume 不为空时从其检查点的下一页继续，返回任务的结束状态
+// dry_run 时完整执行采集流程但不写入视频、不下载图片，只生成预览报告
+async fn collect_pages(
+    db: &Database,
+    collection: &Collection,
+    hours: Option<String>,
+    task_id: &str,
+    dry_run: bool,
+    resume: Option<CollectCheckpoint>,
+    progress: &mut CollectProgress,
+) -> Result<&'static str, Box<dyn std::error::Error + Send + Sync>> {
+    let start_page = progress.current_page + 1;
+    // 规则在保存采集源时已校验，每次运行只解析一次
+    let rules = CollectRules::from_collection(collection)?;
+    let mut report = dry_run.then(|| {
+        resume
+            .and_then(|c| c.dry_run_report)
+            .unwrap_or_default()
+    });
+
+    update_task_progress(db, task_id, progress).await;
+
+    // 构建API URL，文章采集源只支持JSON格式
+    let format = if collection.collect_type == COLLECT_TYPE_ART {
+        COLLECT_FORMAT_JSON
+    } else {
+        collection.collect_format
+    };
+    let mut api_url = build_api_url(&collection.collect_url, "detail", format);
 
     // 添加hours参数
     if let Some(h) = hours {
@@ -587,39 +880,55 @@ pub async fn start_batch_collect(
     }
 
     // 获取总页数（带重试机制）
-    let total_pages = match get_total_pages_with_retry(&api_url, 3, 30).await {
+    let total_pages = match get_total_pages_with_retry(&api_url, format, collection.collect_type, 3, 30).await {
         Ok(pages) => pages,
         Err(e) => {
             eprintln!("❌ 获取总页数失败，已重试3次: {}", e);
+            progress.status = jobs::JOB_STATUS_FAILED.to_string();
+            progress.log = format!("获取总页数失败: {}", e);
             return Err(format!("获取总页数失败: {}", e).into());
         }
     };
 
     // 更新进度信息
-    let mut progress = initial_progress;
     progress.total_pages = total_pages;
-    progress.log = format!("开始采集，总页数: {}", total_pages);
-    update_task_progress(&task_id, progress.clone(), collection.collect_name.clone()).await;
+    progress.log = if start_page > 1 {
+        format!("从第 {} 页继续采集，总页数: {}", start_page, total_pages)
+    } else {
+        format!("开始采集，总页数: {}", total_pages)
+    };
+    update_task_progress(db, task_id, progress).await;
 
     // 逐页采集
-    for page in 1..=total_pages {
+    for page in start_page..=total_pages {
         // 检查任务是否被停止
-        if let Some(current_progress) = get_task_progress(&task_id).await {
-            if current_progress.status == "stopped" {
-                return Ok(()); // 任务已被停止，直接返回
-            }
+        if is_task_stopped(db, task_id).await {
+            progress.log = format!("任务已手动停止，已完成 {} 页", page - 1);
+            return Ok(jobs::JOB_STATUS_STOPPED);
         }
 
         progress.current_page = page;
         progress.log = format!("正在采集第 {}/{} 页", page, total_pages);
-        update_task_progress(&task_id, progress.clone(), collection.collect_name.clone()).await;
+        update_task_progress(db, task_id, progress).await;
 
         let page_url = format!("{}&pg={}", api_url, page);
-        if let Err(e) = collect_page(db, &collection, &page_url, &mut progress, &task_id).await {
+        let page_result = if collection.collect_type == COLLECT_TYPE_ART {
+            collect_art_page(db, collection, &page_url, progress, task_id, report.as_mut()).await
+        } else {
+            collect_page(db, collection, &rules, &page_url, progress, task_id, report.as_mut()).await
+        };
+        if let Err(e) = page_result {
             progress.failed += 1;
             progress.log = format!("第 {} 页采集失败: {}", page, e);
-            update_task_progress(&task_id, progress.clone(), collection.collect_name.clone()).await;
-            continue;
+            update_task_progress(db, task_id, progress).await;
+        }
+
+        // 本页中途被停止时不记录检查点，恢复时重新采集本页
+        if !is_task_stopped(db, task_id).await
+            && let Err(e) =
+                save_collect_checkpoint(db, task_id, progress, page, report.as_ref()).await
+        {
+            eprintln!("保存采集检查点失败 {}: {}", task_id, e);
         }
 
         // 添加延时避免请求过快
@@ -627,26 +936,42 @@ pub async fn start_batch_collect(
     }
 
     // 完成采集
-    progress.status = "completed".to_string();
-    progress.log = format!(
-        "采集完成，成功: {}，失败: {}",
-        progress.success, progress.failed
-    );
-    update_task_progress(&task_id, progress, collection.collect_name).await;
+    progress.status = jobs::JOB_STATUS_COMPLETED.to_string();
+    progress.log = match &report {
+        Some(report) => format!(
+            "预览完成，将新增: {}，将更新: {}，过滤: {}，缺少分类绑定: {}，失败: {}",
+            report.created, report.updated, report.skipped, report.missing_binding, progress.failed
+        ),
+        None => format!(
+            "采集完成，成功: {}，失败: {}",
+            progress.success, progress.failed
+        ),
+    };
+    if let Some(report) = &report
+        && let Err(e) = save_collect_checkpoint(db, task_id, progress, total_pages, Some(report)).await
+    {
+        eprintln!("保存预览报告失败 {}: {}", task_id, e);
+    }
 
-    Ok(())
+    Ok(jobs::JOB_STATUS_COMPLETED)
+}
+
+async fn is_task_stopped(db: &Database, task_id: &str) -> bool {
+    jobs::is_cancel_requested(db, task_id).await
 }
 
 // 采集单页数据（带超时）
 async fn collect_page(
     db: &Database,
     collection: &Collection,
+    rules: &CollectRules,
     page_url: &str,
     progress: &mut CollectProgress,
     task_id: &str,
+    mut report: Option<&mut CollectDryRunReport>,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let response_text = fetch_with_timeout(page_url, 30).await?;
-    let api_response: VideoListResponse = serde_json::from_str(&response_text)?;
+    let api_response = parse_collect_response(&response_text, collection.collect_format)?;
 
     if api_response.code != 1 {
         return Err(format!("API返回错误: {:?}", api_response).into());
@@ -654,17 +979,19 @@ async fn collect_page(
 
     let mut page_success = 0;
     let mut page_failed = 0;
+    let mut page_skipped = 0;
 
     for vod_data in api_response.list {
         // 检查任务是否被停止
-        if let Some(current_progress) = get_task_progress(task_id).await {
-            if current_progress.status == "stopped" {
-                return Ok(()); // 任务已被停止，直接返回
-            }
+        if is_task_stopped(db, task_id).await {
+            return Ok(()); // 任务已被停止，直接返回
         }
 
-        match collect_single_video(db, collection, &vod_data).await {
-            Ok(_) => page_success += 1,
+        match collect_single_video(db, collection, rules, &vod_data, task_id, report.as_deref_mut())
+            .await
+        {
+            Ok(true) => page_success += 1,
+            Ok(false) => page_skipped += 1,
             Err(e) => {
                 eprintln!("采集视频失败 {}: {}", vod_data.vod_name, e);
                 page_failed += 1;
@@ -675,20 +1002,45 @@ async fn collect_page(
     progress.success += page_success;
     progress.failed += page_failed;
     progress.log = format!(
-        "本页采集完成，成功: {}，失败: {}",
-        page_success, page_failed
+        "本页采集完成，成功: {}，失败: {}，过滤: {}",
+        page_success, page_failed, page_skipped
     );
-    update_task_progress(task_id, progress.clone(), collection.collect_name.clone()).await;
+    update_task_progress(db, task_id, progress).await;
 
     Ok(())
 }
 
-// 采集单个视频
+// 采集单个视频，被采集规则过滤时返回 Ok(false)
 pub async fn collect_single_video(
     db: &Database,
     collection: &Collection,
+    rules: &CollectRules,
     vod_data: &VodApiListEntry,
+    task_id: &str,
+    report: Option<&mut CollectDryRunReport>,
 ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+    let mut vod_data = vod_data.clone();
+    let outcome = rules.apply(&mut vod_data);
+    if !outcome.accepted {
+        println!(
+            "跳过视频 {}: {}",
+            vod_data.vod_name,
+            outcome.reason.as_deref().unwrap_or_default()
+        );
+        if let Some(report) = report {
+            report.skipped += 1;
+            add_dry_run_item(
+                report,
+                vod_data.vod_id.to_string(),
+                &vod_data.vod_name,
+                "skip",
+                outcome.reason,
+            );
+        }
+        return Ok(false);
+    }
+    let vod_data = &vod_data;
+
     // 查找分类绑定
     let bindings_collection = db.collection::<Binding>("bindings");
     let binding = bindings_collection
@@ -704,6 +1056,16 @@ pub async fn collect_single_video(
     let local_type_id = match binding {
         Some(b) => b.local_type_id,
         None => {
+            if let Some(report) = report {
+                add_missing_binding(
+                    report,
+                    vod_data.vod_id.to_string(),
+                    &vod_data.vod_name,
+                    vod_data.type_id,
+                    vod_data.type_name.clone(),
+                );
+                return Ok(false);
+            }
             eprintln!(
                 "未找到分类绑定: source_flag={}, external_id={}",
                 collection.collect_name, vod_data.type_id
@@ -712,28 +1074,36 @@ pub async fn collect_single_video(
         }
     };
 
-    // 检查视频是否已存在（基于vod_name和vod_year）
+    // 按采集源配置的判定方式检查视频是否已存在
     let vods_collection = db.collection::<Vod>("vods");
-    let existing_vod = if let Some(ref year) = vod_data.vod_year {
-        vods_collection
-            .find_one(
-                doc! {
-                    "vod_name": &vod_data.vod_name,
-                    "vod_year": year
-                },
-                None,
-            )
-            .await?
-    } else {
-        vods_collection
-            .find_one(
-                doc! {
-                    "vod_name": &vod_data.vod_name
-                },
-                None,
-            )
-            .await?
-    };
+    let existing_vod = vod_match::find_existing_vod(db, collection, vod_data).await?;
+
+    // 预览模式只记录将执行的操作
+    if let Some(report) = report {
+        match &existing_vod {
+            Some(existing) => {
+                report.updated += 1;
+                add_dry_run_item(
+                    report,
+                    vod_data.vod_id.to_string(),
+                    &vod_data.vod_name,
+                    "update",
+                    Some(existing.vod_name.clone()),
+                );
+            }
+            None => {
+                report.created += 1;
+                add_dry_run_item(
+                    report,
+                    vod_data.vod_id.to_string(),
+                    &vod_data.vod_name,
+                    "create",
+                    None,
+                );
+            }
+        }
+        return Ok(true);
+    }
 
     let current_time = DateTime::from_millis(
         SystemTime::now()
@@ -743,33 +1113,68 @@ pub async fn collect_single_video(
     );
 
     if let Some(mut existing) = existing_vod {
-        // 更新现有视频 - 处理播放源替换
-        let new_play_sources = parse_play_urls(&vod_data.vod_play_from, &vod_data.vod_play_url);
+        let original = existing.clone();
+        if let Err(e) = vod_match::record_merge(db, task_id, collection, vod_data, &existing).await {
+            eprintln!("记录合并报告失败: {}", e);
+        }
 
-        // 根据source_name匹配更新播放源
-        let mut updated = false;
-        for new_source in new_play_sources {
-            if let Some(pos) = existing
-                .vod_play_urls
-                .iter()
-                .position(|s| s.source_name == new_source.source_name)
-            {
-                // 替换现有播放源
-                existing.vod_play_urls[pos] = new_source;
-                updated = true;
-            } else {
-                // 添加新播放源
-                existing.vod_play_urls.push(new_source);
+        // 补齐用于后续重复判定的字段
+        if existing.vod_name_key.is_none() {
+            existing.vod_name_key = Some(vod_match::normalize_vod_name(&existing.vod_name));
+        }
+        if existing.vod_douban_id.is_none() {
+            existing.vod_douban_id = vod_data.vod_douban_id.filter(|id| *id > 0);
+        }
+
+        // 按采集源的字段更新策略更新现有视频
+        let mut updated = apply_update_policy(collection, &mut existing, vod_data, local_type_id).await;
+
+        // 更新播放源：覆盖时按source_name替换或追加，补齐时仅在没有播放源时写入
+        let new_play_sources = parse_play_urls(&vod_data.vod_play_from, &vod_data.vod_play_url);
+        match update_policy(collection, "vod_play_urls") {
+            UPDATE_OVERWRITE => {
+                for new_source in new_play_sources {
+                    if let Some(pos) = existing
+                        .vod_play_urls
+                        .iter()
+                        .position(|s| s.source_name == new_source.source_name)
+                    {
+                        // 替换现有播放源
+                        existing.vod_play_urls[pos] = new_source;
+                    } else {
+                        // 添加新播放源
+                        existing.vod_play_urls.push(new_source);
+                    }
+                    updated = true;
+                }
+            }
+            UPDATE_FILL if existing.vod_play_urls.is_empty() && !new_play_sources.is_empty() => {
+                existing.vod_play_urls = new_play_sources;
                 updated = true;
             }
+            _ => {}
         }
 
         if updated {
             existing.vod_pubdate = current_time;
-            existing.vod_remarks = Some(vod_data.vod_remarks.clone());
+            existing.vod_name_key = Some(vod_match::normalize_vod_name(&existing.vod_name));
+            let changes = changed_fields(&original, &existing, &VOD_COUNTER_FIELDS)?;
             vods_collection
-                .replace_one(doc! { "_id": existing.id }, &existing, None)
+                .update_one(doc! { "_id": existing.id }, doc! { "$set": changes }, None)
                 .await?;
+            // 采集期间可能有用户评分，写入前再次确认没有站内评分
+            if existing.vod_score != original.vod_score {
+                vods_collection
+                    .update_one(
+                        doc! { "_id": existing.id, "vod_score_num": { "$in": [0, Bson::Null] } },
+                        doc! { "$set": { "vod_score": &existing.vod_score } },
+                        None,
+                    )
+                    .await?;
+            }
+            if let Some(id) = existing.id {
+                search_index::refresh_entries(db, "vod", &[id]).await;
+            }
         }
 
         Ok(true)
@@ -777,7 +1182,11 @@ pub async fn collect_single_video(
         // 创建新视频
         let new_vod = Vod {
             id: None,
+            vod_id: Some(crate::db::next_sequence(db, "vod_id").await?),
             vod_name: vod_data.vod_name.clone(),
+            vod_name_key: Some(vod_match::normalize_vod_name(&vod_data.vod_name)),
+            vod_douban_id: vod_data.vod_douban_id.filter(|id| *id > 0),
+            vod_pic_source: None,
             type_id: local_type_id,
             vod_status: vod_data.vod_status.unwrap_or(1),
             vod_class: vod_data.vod_class.clone(),
@@ -795,6 +1204,8 @@ pub async fn collect_single_video(
             vod_hits_week: Some(0),
             vod_hits_month: Some(0),
             vod_score: Some("0.0".to_string()),
+            vod_score_all: Some(0),
+            vod_score_num: Some(0),
             need_vip: 0,
             vod_play_urls: parse_play_urls(&vod_data.vod_play_from, &vod_data.vod_play_url),
         };
@@ -817,13 +1228,417 @@ pub async fn collect_single_video(
         };
 
         let mut final_vod = new_vod;
+        if final_vod_pic != vod_data.vod_pic {
+            final_vod.vod_pic_source = vod_data.vod_pic.clone();
+        }
         final_vod.vod_pic = final_vod_pic;
 
-        vods_collection.insert_one(&final_vod, None).await?;
+        let result = vods_collection.insert_one(&final_vod, None).await?;
+        if let Some(id) = result.inserted_id.as_object_id() {
+            search_index::refresh_entries(db, "vod", &[id]).await;
+        }
         Ok(true)
     }
 }
 
+// --- 文章采集 ---
+
+// 采集单页文章（带超时）
+async fn collect_art_page(
+    db: &Database,
+    collection: &Collection,
+    page_url: &str,
+    progress: &mut CollectProgress,
+    task_id: &str,
+    mut report: Option<&mut CollectDryRunReport>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let response_text = fetch_with_timeout(page_url, 30).await?;
+    let api_response: JsonResponse<ArtApiListEntry> = serde_json::from_str(&response_text)?;
+
+    if api_response.code != 1 {
+        return Err(format!("API返回错误: {}", api_response.msg).into());
+    }
+
+    let mut page_success = 0;
+    let mut page_failed = 0;
+    let mut page_skipped = 0;
+
+    for art_data in api_response.list {
+        if is_task_stopped(db, task_id).await {
+            return Ok(());
+        }
+
+        match collect_single_art(db, collection, &art_data, report.as_deref_mut()).await {
+            Ok(true) => page_success += 1,
+            Ok(false) => page_skipped += 1,
+            Err(e) => {
+                eprintln!("采集文章失败 {}: {}", art_data.art_name, e);
+                page_failed += 1;
+            }
+        }
+    }
+
+    progress.success += page_success;
+    progress.failed += page_failed;
+    progress.log = format!(
+        "本页采集完成，成功: {}，失败: {}，跳过: {}",
+        page_success, page_failed, page_skipped
+    );
+    update_task_progress(db, task_id, progress).await;
+
+    Ok(())
+}
+
+// 采集单篇文章，按名称判断是否已存在
+// 文章沿用采集源的字段更新策略：备注(vod_remarks)、内容(vod_content)、图片(vod_pic)、分类(type_id)
+async fn collect_single_art(
+    db: &Database,
+    collection: &Collection,
+    art_data: &ArtApiListEntry,
+    report: Option<&mut CollectDryRunReport>,
+) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+    let binding = db
+        .collection::<Binding>("bindings")
+        .find_one(
+            doc! {
+                "source_flag": &collection.collect_name,
+                "external_id": art_data.type_id.to_string()
+            },
+            None,
+        )
+        .await?;
+
+    let local_type_id = match binding {
+        Some(b) => b.local_type_id,
+        None => {
+            if let Some(report) = report {
+                add_missing_binding(
+                    report,
+                    art_data.art_id.to_string(),
+                    &art_data.art_name,
+                    art_data.type_id,
+                    art_data.type_name.clone(),
+                );
+                return Ok(false);
+            }
+            eprintln!(
+                "未找到分类绑定: source_flag={}, external_id={}",
+                collection.collect_name, art_data.type_id
+            );
+            return Err("未找到分类绑定".into());
+        }
+    };
+
+    let arts_collection = db.collection::<Art>("arts");
+    let existing_art = arts_collection
+        .find_one(doc! { "art_name": &art_data.art_name }, None)
+        .await?;
+
+    if let Some(report) = report {
+        let source_id = art_data.art_id.to_string();
+        match &existing_art {
+            Some(existing) => {
+                report.updated += 1;
+                let detail = Some(existing.art_name.clone());
+                add_dry_run_item(report, source_id, &art_data.art_name, "update", detail);
+            }
+            None => {
+                report.created += 1;
+                add_dry_run_item(report, source_id, &art_data.art_name, "create", None);
+            }
+        }
+        return Ok(true);
+    }
+
+    // 多页文章的内容以 $$$ 分隔
+    let content = art_data
+        .art_content
+        .as_deref()
+        .map(|c| c.split(PLAY_SEPARATOR).collect::<Vec<_>>().join("\n"));
+
+    if let Some(mut existing) = existing_art {
+        let original = existing.clone();
+        let mut updated = false;
+        updated |= apply_field(
+            update_policy(collection, "vod_remarks"),
+            &mut existing.art_remarks,
+            art_data.art_remarks.as_deref(),
+        );
+        updated |= apply_field(
+            update_policy(collection, "vod_content"),
+            &mut existing.art_content,
+            content.as_deref(),
+        );
+        updated |= apply_field(
+            update_policy(collection, "vod_content"),
+            &mut existing.art_blurb,
+            art_data.art_blurb.as_deref(),
+        );
+        if update_policy(collection, "type_id") == UPDATE_OVERWRITE && existing.type_id != local_type_id {
+            existing.type_id = local_type_id;
+            updated = true;
+        }
+
+        let pic_policy = update_policy(collection, "vod_pic");
+        if let Some(pic_url) = art_data.art_pic.as_deref().map(str::trim).filter(|p| !p.is_empty()) {
+            let pic_empty = existing.art_pic.as_deref().is_none_or(|p| p.trim().is_empty());
+            let pic_changed = existing
+                .art_pic_source
+                .as_deref()
+                .unwrap_or(existing.art_pic.as_deref().unwrap_or_default())
+                != pic_url;
+            if (pic_policy == UPDATE_OVERWRITE && pic_changed) || (pic_policy == UPDATE_FILL && pic_empty) {
+                let (pic, source) = collect_art_pic(collection, pic_url).await;
+                existing.art_pic = Some(pic);
+                existing.art_pic_source = source;
+                updated = true;
+            }
+        }
+
+        if updated {
+            existing.art_pubdate = DateTime::now();
+            let changes = changed_fields(&original, &existing, &ART_COUNTER_FIELDS)?;
+            arts_collection
+                .update_one(doc! { "_id": existing.id }, doc! { "$set": changes }, None)
+                .await?;
+            if let Some(id) = existing.id {
+                search_index::refresh_entries(db, "art", &[id]).await;
+            }
+        }
+        return Ok(true);
+    }
+
+    let (art_pic, art_pic_source) = match art_data.art_pic.as_deref().map(str::trim) {
+        Some(pic_url) if !pic_url.is_empty() => {
+            let (pic, source) = collect_art_pic(collection, pic_url).await;
+            (Some(pic), source)
+        }
+        _ => (None, None),
+    };
+
+    let new_art = Art {
+        id: None,
+        art_name: art_data.art_name.clone(),
+        type_id: local_type_id,
+        art_status: 1,
+        art_pic,
+        art_author: art_data.art_author.clone(),
+        art_from: art_data.art_from.clone(),
+        art_remarks: art_data.art_remarks.clone(),
+        art_pubdate: DateTime::now(),
+        art_content: content,
+        art_blurb: art_data.art_blurb.clone(),
+        art_tag: art_data.art_tag.clone(),
+        art_hits: Some(0),
+        art_pic_source,
+    };
+    let result = arts_collection.insert_one(&new_art, None).await?;
+    if let Some(id) = result.inserted_id.as_object_id() {
+        search_index::refresh_entries(db, "art", &[id]).await;
+    }
+    Ok(true)
+}
+
+// 点击数由 HitCounter 用 $inc 写回并定期重置，采集时不能用读取时的旧值覆盖；
+// 评分汇总由评分接口用 $inc 维护，vod_score 只在没有站内评分时按资源站更新
+const VOD_COUNTER_FIELDS: [&str; 7] = [
+    "vod_hits",
+    "vod_hits_day",
+    "vod_hits_week",
+    "vod_hits_month",
+    "vod_score",
+    "vod_score_all",
+    "vod_score_num",
+];
+const ART_COUNTER_FIELDS: [&str; 1] = ["art_hits"];
+
+// 对比更新前后的文档，只返回发生变化且不在 skipped 中的字段，用于 $set
+fn changed_fields<T: Serialize>(
+    before: &T,
+    after: &T,
+    skipped: &[&str],
+) -> Result<mongodb::bson::Document, mongodb::bson::ser::Error> {
+    let before = mongodb::bson::to_document(before)?;
+    let after = mongodb::bson::to_document(after)?;
+    let mut changes = mongodb::bson::Document::new();
+    for (key, value) in after {
+        if key == "_id" || skipped.contains(&key.as_str()) || before.get(&key) == Some(&value) {
+            continue;
+        }
+        changes.insert(key, value);
+    }
+    Ok(changes)
+}
+
+// 开启图片本地化时下载文章图片，返回 (图片地址, 原始地址)
+async fn collect_art_pic(collection: &Collection, pic_url: &str) -> (String, Option<String>) {
+    if collection.collect_sync_pic_opt != 1 {
+        return (pic_url.to_string(), None);
+    }
+    match download_image_to_local_with_config(pic_url, collection).await {
+        Ok(local_path) => (local_path, Some(pic_url.to_string())),
+        Err(e) => {
+            eprintln!("下载图片失败 {}: {}", pic_url, e);
+            (pic_url.to_string(), None)
+        }
+    }
+}
+
+// 预览报告中保留的明细条数上限
+const DRY_RUN_MAX_ITEMS: usize = 2000;
+
+fn add_dry_run_item(
+    report: &mut CollectDryRunReport,
+    source_id: String,
+    name: &str,
+    action: &str,
+    detail: Option<String>,
+) {
+    if report.items.len() < DRY_RUN_MAX_ITEMS {
+        report.items.push(DryRunItem {
+            source_vod_id: source_id,
+            vod_name: name.to_string(),
+            action: action.to_string(),
+            detail,
+        });
+    }
+}
+
+fn add_missing_binding(
+    report: &mut CollectDryRunReport,
+    source_id: String,
+    name: &str,
+    type_id: i32,
+    type_name: Option<String>,
+) {
+    report.missing_binding += 1;
+    match report.missing_bindings.iter_mut().find(|b| b.type_id == type_id) {
+        Some(binding) => binding.count += 1,
+        None => report.missing_bindings.push(DryRunMissingBinding {
+            type_id,
+            type_name: type_name.clone(),
+            count: 1,
+        }),
+    }
+    add_dry_run_item(report, source_id, name, "missing_binding", type_name);
+}
+
+// 重新采集已有视频时的字段更新策略（Collection.collect_update_policy）
+pub const UPDATE_OVERWRITE: &str = "overwrite"; // 使用资源站数据覆盖
+pub const UPDATE_FILL: &str = "fill"; // 仅在本地为空时补齐
+pub const UPDATE_IGNORE: &str = "ignore"; // 不更新
+
+// 可配置的字段及未配置时的默认策略（默认仅更新播放源和备注）
+pub const UPDATE_POLICY_FIELDS: &[(&str, &str)] = &[
+    ("vod_play_urls", UPDATE_OVERWRITE),
+    ("vod_remarks", UPDATE_OVERWRITE),
+    ("vod_pic", UPDATE_IGNORE),
+    ("vod_content", UPDATE_IGNORE),
+    ("vod_actor", UPDATE_IGNORE),
+    ("vod_director", UPDATE_IGNORE),
+    ("vod_class", UPDATE_IGNORE),
+    ("vod_area", UPDATE_IGNORE),
+    ("vod_lang", UPDATE_IGNORE),
+    ("vod_year", UPDATE_IGNORE),
+    ("vod_score", UPDATE_IGNORE),
+    ("type_id", UPDATE_IGNORE),
+];
+
+fn update_policy<'a>(collection: &'a Collection, field: &str) -> &'a str {
+    match collection.collect_update_policy.get(field).map(String::as_str) {
+        Some(policy @ (UPDATE_OVERWRITE | UPDATE_FILL | UPDATE_IGNORE)) => policy,
+        _ => UPDATE_POLICY_FIELDS
+            .iter()
+            .find(|(name, _)| *name == field)
+            .map(|(_, policy)| *policy)
+            .unwrap_or(UPDATE_IGNORE),
+    }
+}
+
+// 按策略更新单个文本字段，资源站数据为空时不做处理
+fn apply_field(policy: &str, current: &mut Option<String>, incoming: Option<&str>) -> bool {
+    let incoming = match incoming.map(str::trim) {
+        Some(value) if !value.is_empty() => value,
+        _ => return false,
+    };
+    let current_empty = current.as_deref().is_none_or(|v| v.trim().is_empty());
+
+    let should_update = match policy {
+        UPDATE_OVERWRITE => current.as_deref() != Some(incoming),
+        UPDATE_FILL => current_empty,
+        _ => false,
+    };
+    if should_update {
+        *current = Some(incoming.to_string());
+    }
+    should_update
+}
+
+// 应用播放源以外字段的更新策略，返回是否有字段变化
+async fn apply_update_policy(
+    collection: &Collection,
+    existing: &mut Vod,
+    vod_data: &VodApiListEntry,
+    local_type_id: i32,
+) -> bool {
+    let mut updated = false;
+    // 已有站内用户评分时以用户评分为准，不再用资源站评分覆盖
+    let has_user_ratings = existing.vod_score_num.unwrap_or(0) > 0;
+
+    let text_fields: [(&str, &mut Option<String>, Option<&str>); 9] = [
+        ("vod_remarks", &mut existing.vod_remarks, Some(vod_data.vod_remarks.as_str())),
+        ("vod_content", &mut existing.vod_content, vod_data.vod_content.as_deref()),
+        ("vod_actor", &mut existing.vod_actor, vod_data.vod_actor.as_deref()),
+        ("vod_director", &mut existing.vod_director, vod_data.vod_director.as_deref()),
+        ("vod_class", &mut existing.vod_class, vod_data.vod_class.as_deref()),
+        ("vod_area", &mut existing.vod_area, vod_data.vod_area.as_deref()),
+        ("vod_lang", &mut existing.vod_lang, vod_data.vod_lang.as_deref()),
+        ("vod_year", &mut existing.vod_year, vod_data.vod_year.as_deref()),
+        ("vod_score", &mut existing.vod_score, vod_data.vod_score.as_deref()),
+    ];
+    for (field, current, incoming) in text_fields {
+        if field == "vod_score" && has_user_ratings {
+            continue;
+        }
+        updated |= apply_field(update_policy(collection, field), current, incoming);
+    }
+
+    if update_policy(collection, "type_id") == UPDATE_OVERWRITE && existing.type_id != local_type_id {
+        existing.type_id = local_type_id;
+        updated = true;
+    }
+
+    // 海报按原始地址判断是否变化，开启图片本地化时重新下载
+    let pic_policy = update_policy(collection, "vod_pic");
+    if let Some(pic_url) = vod_data.vod_pic.as_deref().map(str::trim).filter(|p| !p.is_empty()) {
+        let pic_empty = existing.vod_pic.as_deref().is_none_or(|p| p.trim().is_empty());
+        let pic_changed = existing.vod_pic_source.as_deref().unwrap_or(
+            existing.vod_pic.as_deref().unwrap_or_default(),
+        ) != pic_url;
+
+        if (pic_policy == UPDATE_OVERWRITE && pic_changed) || (pic_policy == UPDATE_FILL && pic_empty) {
+            existing.vod_pic = if collection.collect_sync_pic_opt == 1 {
+                match download_image_to_local_with_config(pic_url, collection).await {
+                    Ok(local_path) => {
+                        existing.vod_pic_source = Some(pic_url.to_string());
+                        Some(local_path)
+                    }
+                    Err(e) => {
+                        eprintln!("下载图片失败 {}: {}", pic_url, e);
+                        Some(pic_url.to_string())
+                    }
+                }
+            } else {
+                existing.vod_pic_source = None;
+                Some(pic_url.to_string())
+            };
+            updated = true;
+        }
+    }
+
+    updated
+}
+
 // 下载图片到本地（带重试机制和webp转换）
 async fn download_image_to_local_with_config(
     image_url: &str,
@@ -971,6 +1786,13 @@ async fn download_image_to_local(
         collect_remove_ad: 1,
         collect_convert_webp: 0,   // 默认不转换webp
         collect_download_retry: 3, // 默认重试3次
+        collect_format: COLLECT_FORMAT_AUTO,
+        collect_match: 0,          // 名称完全一致
+        collect_update_policy: Default::default(),
+        collect_cron: "".to_string(),
+        collect_cron_status: 0,
+        collect_last_run: None,
+        collect_next_run: None,
         collect_status: 1,
         created_at: mongodb::bson::DateTime::now(),
         updated_at: mongodb::bson::DateTime::now(),
@@ -978,139 +1800,3 @@ async fn download_image_to_local(
 
     download_image_to_local_with_config(image_url, &default_collection).await
 }
-
-// 采集单个视频详情（保留原有函数用于兼容性）
-pub async fn collect_video_detail(
-    db: web::Data<Database>,
-    api_url: &str,
-    vod_id: &str,
-    source_flag: &str,
-) -> Result<bool, Box<dyn std::error::Error>> {
-    // 构建详情API URL
-    let detail_url = format!("{}?ac=detail&h=24&ids={}", api_url, vod_id);
-
-    // 获取视频详情
-    let response = reqwest::get(&detail_url).await?;
-    let api_response: JsonResponse<VodApiListEntry> = response.json().await?;
-
-    if api_response.code != 1 || api_response.list.is_empty() {
-        return Err("获取视频详情失败".into());
-    }
-
-    let vod_data = &api_response.list[0];
-
-    // 查找分类绑定
-    let bindings_collection = db.collection::<Binding>("bindings");
-    let binding = bindings_collection
-        .find_one(
-            doc! {
-                "source_flag": source_flag,
-                "external_id": vod_data.type_id.to_string()
-            },
-            None,
-        )
-        .await?;
-
-    let local_type_id = match binding {
-        Some(b) => b.local_type_id,
-        None => {
-            eprintln!(
-                "未找到分类绑定: source_flag={}, external_id={}",
-                source_flag, vod_data.type_id
-            );
-            return Err("未找到分类绑定".into());
-        }
-    };
-
-    // 检查视频是否已存在
-    let vods_collection = db.collection::<Vod>("vods");
-    let existing_vod = vods_collection
-        .find_one(
-            doc! {
-                "vod_name": &vod_data.vod_name
-            },
-            None,
-        )
-        .await?;
-
-    let current_time = DateTime::from_millis(
-        SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_millis() as i64,
-    );
-
-    if let Some(mut existing) = existing_vod {
-        // 更新现有视频 - 使用VodApiListEntry中的所有可用字段
-        existing.vod_name = vod_data.vod_name.clone();
-        existing.type_id = local_type_id;
-        existing.vod_status = 1; // 默认状态
-                                 // 更新所有可用字段
-        existing.vod_remarks = Some(vod_data.vod_remarks.clone());
-        if let Some(ref pubdate) = vod_data.vod_pubdate {
-            existing.vod_pubdate = current_time;
-        }
-        if let Some(ref class) = vod_data.vod_class {
-            existing.vod_class = Some(class.clone());
-        }
-        if let Some(ref pic) = vod_data.vod_pic {
-            existing.vod_pic = Some(pic.clone());
-        }
-        if let Some(ref actor) = vod_data.vod_actor {
-            existing.vod_actor = Some(actor.clone());
-        }
-        if let Some(ref director) = vod_data.vod_director {
-            existing.vod_director = Some(director.clone());
-        }
-        if let Some(ref area) = vod_data.vod_area {
-            existing.vod_area = Some(area.clone());
-        }
-        if let Some(ref lang) = vod_data.vod_lang {
-            existing.vod_lang = Some(lang.clone());
-        }
-        if let Some(ref year) = vod_data.vod_year {
-            existing.vod_year = Some(year.clone());
-        }
-        if let Some(ref content) = vod_data.vod_content {
-            existing.vod_content = Some(content.clone());
-        }
-        // 解析播放地址
-        if !vod_data.vod_play_from.is_empty() {
-            existing.vod_play_urls =
-                parse_play_urls(&vod_data.vod_play_from, &vod_data.vod_play_url);
-        }
-
-        vods_collection
-            .replace_one(doc! { "_id": existing.id }, &existing, None)
-            .await?;
-    } else {
-        // 创建新视频 - 只使用VodApiListEntry中实际存在的字段
-        let new_vod = Vod {
-            id: None,
-            vod_name: vod_data.vod_name.clone(),
-            type_id: local_type_id,
-            vod_status: vod_data.vod_status.unwrap_or(1),
-            vod_class: vod_data.vod_class.clone(),
-            vod_pic: vod_data.vod_pic.clone(),
-            vod_actor: vod_data.vod_actor.clone(),
-            vod_director: vod_data.vod_director.clone(),
-            vod_remarks: Some(vod_data.vod_remarks.clone()),
-            vod_pubdate: current_time.clone(),
-            vod_area: vod_data.vod_area.clone(),
-            vod_lang: vod_data.vod_lang.clone(),
-            vod_year: vod_data.vod_year.clone(),
-            vod_content: vod_data.vod_content.clone(),
-            vod_hits: Some(0),
-            vod_hits_day: Some(0),
-            vod_hits_week: Some(0),
-            vod_hits_month: Some(0),
-            vod_score: Some("0.0".to_string()),
-            need_vip: 0,
-            vod_play_urls: parse_play_urls(&vod_data.vod_play_from, &vod_data.vod_play_url),
-        };
-
-        vods_collection.insert_one(&new_vod, None).await?;
-    }
-
-    Ok(true)
-}
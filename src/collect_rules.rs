@@ -0,0 +1,381 @@
+use crate::dto::VodApiListEntry;
+use crate::maccms_xml::{play_from_separator, PLAY_SEPARATOR};
+use crate::models::Collection;
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::Serialize;
+
+// 采集规则引擎
+//
+// collect_filter 一行一条规则，格式为 [字段:]模式：
+//   预告              名称包含“预告”时跳过（未写字段时默认匹配名称）
+//   class:伦理        扩展分类包含“伦理”时跳过，字段可为 name / class / content
+//   name:/^\d+$/      以 / 包围的模式按正则匹配
+//   ad:加微信         移除广告时从简介中删除该文本，同样支持 /正则/
+//   # 注释            以 # 开头的行忽略
+//
+// collect_filter_from 为逗号分隔的播放源白名单，flag=名称 表示同时重命名，
+// * 表示允许其余播放源，例如: ffm3u8=非凡线路,lzm3u8,*
+//
+// collect_remove_ad=1 时清理简介中的HTML标签、网址以及 ad: 规则匹配的内容
+
+lazy_static! {
+    static ref HTML_TAG_RE: Regex = Regex::new(r"(?s)<[^>]*>").unwrap();
+    static ref URL_RE: Regex =
+        Regex::new(r"(?i)(https?://|www\.)[a-z0-9\-._~:/?#\[\]@!$&'()*+,;=%]+").unwrap();
+    static ref BLANK_RE: Regex = Regex::new(r"[ \t\u{3000}]+").unwrap();
+}
+
+#[derive(Debug, Clone)]
+enum Matcher {
+    Keyword(String),
+    Regex(Regex),
+}
+
+impl Matcher {
+    fn parse(pattern: &str) -> Result<Self, regex::Error> {
+        if pattern.len() > 2 && pattern.starts_with('/') && pattern.ends_with('/') {
+            Ok(Matcher::Regex(Regex::new(&pattern[1..pattern.len() - 1])?))
+        } else {
+            Ok(Matcher::Keyword(pattern.to_string()))
+        }
+    }
+
+    fn is_match(&self, text: &str) -> bool {
+        match self {
+            Matcher::Keyword(keyword) => text.contains(keyword.as_str()),
+            Matcher::Regex(re) => re.is_match(text),
+        }
+    }
+
+    fn remove_from(&self, text: &str) -> String {
+        match self {
+            Matcher::Keyword(keyword) => text.replace(keyword.as_str(), ""),
+            Matcher::Regex(re) => re.replace_all(text, "").into_owned(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RuleField {
+    Name,
+    Class,
+    Content,
+}
+
+impl RuleField {
+    fn label(self) -> &'static str {
+        match self {
+            RuleField::Name => "名称",
+            RuleField::Class => "分类",
+            RuleField::Content => "简介",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct BlacklistRule {
+    field: RuleField,
+    rule: String,
+    matcher: Matcher,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CollectRules {
+    blacklist: Vec<BlacklistRule>,
+    ad_patterns: Vec<Matcher>,
+    source_whitelist: Vec<(String, Option<String>)>,
+    allow_other_sources: bool,
+    remove_ad: bool,
+}
+
+// 单条数据的规则处理结果
+#[derive(Debug, Serialize)]
+pub struct RuleOutcome {
+    pub accepted: bool,
+    pub reason: Option<String>,
+}
+
+impl CollectRules {
+    pub fn from_collection(collection: &Collection) -> Result<Self, String> {
+        Self::parse(
+            &collection.collect_filter,
+            &collection.collect_filter_from,
+            collection.collect_remove_ad,
+        )
+    }
+
+    pub fn parse(filter: &str, filter_from: &str, remove_ad: i32) -> Result<Self, String> {
+        let mut rules = CollectRules {
+            remove_ad: remove_ad == 1,
+            ..Default::default()
+        };
+
+        for (index, line) in filter.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (prefix, pattern) = match line.split_once(':') {
+                Some((prefix, pattern))
+                    if matches!(prefix.trim(), "name" | "class" | "content" | "ad") =>
+                {
+                    (prefix.trim(), pattern.trim())
+                }
+                _ => ("name", line),
+            };
+            if pattern.is_empty() {
+                continue;
+            }
+
+            let matcher = Matcher::parse(pattern)
+                .map_err(|e| format!("第 {} 行规则的正则无效: {}", index + 1, e))?;
+
+            let field = match prefix {
+                "ad" => {
+                    rules.ad_patterns.push(matcher);
+                    continue;
+                }
+                "class" => RuleField::Class,
+                "content" => RuleField::Content,
+                _ => RuleField::Name,
+            };
+            rules.blacklist.push(BlacklistRule {
+                field,
+                rule: line.to_string(),
+                matcher,
+            });
+        }
+
+        for item in filter_from.split([',', '，', '\n']) {
+            let item = item.trim();
+            if item.is_empty() {
+                continue;
+            }
+            if item == "*" {
+                rules.allow_other_sources = true;
+                continue;
+            }
+            match item.split_once('=') {
+                Some((flag, name)) if !name.trim().is_empty() => rules
+                    .source_whitelist
+                    .push((flag.trim().to_string(), Some(name.trim().to_string()))),
+                _ => rules
+                    .source_whitelist
+                    .push((item.trim_end_matches('=').trim().to_string(), None)),
+            }
+        }
+
+        Ok(rules)
+    }
+
+    // 应用全部规则，被过滤时返回原因，通过时原地修改播放源和简介
+    pub fn apply(&self, vod: &mut VodApiListEntry) -> RuleOutcome {
+        if let Some(reason) = self.check_blacklist(vod) {
+            return RuleOutcome {
+                accepted: false,
+                reason: Some(reason),
+            };
+        }
+
+        if !vod.vod_play_from.is_empty() && !self.filter_play_sources(vod) {
+            return RuleOutcome {
+                accepted: false,
+                reason: Some("没有白名单内的播放源".to_string()),
+            };
+        }
+
+        if self.remove_ad {
+            vod.vod_content = vod.vod_content.as_deref().map(|content| self.clean_content(content));
+        }
+
+        RuleOutcome {
+            accepted: true,
+            reason: None,
+        }
+    }
+
+    fn check_blacklist(&self, vod: &VodApiListEntry) -> Option<String> {
+        self.blacklist.iter().find_map(|rule| {
+            let text = match rule.field {
+                RuleField::Name => vod.vod_name.as_str(),
+                RuleField::Class => vod.vod_class.as_deref().unwrap_or_default(),
+                RuleField::Content => vod.vod_content.as_deref().unwrap_or_default(),
+            };
+            rule.matcher
+                .is_match(text)
+                .then(|| format!("{}命中过滤规则: {}", rule.field.label(), rule.rule))
+        })
+    }
+
+    // 按白名单筛选并重命名播放源，返回是否仍有可用播放源
+    // 播放源的拆分方式与入库时解析播放地址一致：$$$ 格式地址随播放源一起筛选，逗号格式共用同一组地址
+    fn filter_play_sources(&self, vod: &mut VodApiListEntry) -> bool {
+        if self.source_whitelist.is_empty() {
+            return true;
+        }
+
+        let separator = play_from_separator(&vod.vod_play_from);
+        let play_url = vod.vod_play_url.clone().unwrap_or_default();
+        let urls: Vec<&str> = play_url.split(PLAY_SEPARATOR).collect();
+
+        let mut froms = Vec::new();
+        let mut kept_urls = Vec::new();
+        for (i, flag) in vod.vod_play_from.split(separator).enumerate() {
+            let flag = flag.trim();
+            let display = match self.source_whitelist.iter().find(|(f, _)| f == flag) {
+                Some((_, rename)) => rename.clone().unwrap_or_else(|| flag.to_string()),
+                None if self.allow_other_sources => flag.to_string(),
+                None => continue,
+            };
+            froms.push(display);
+            kept_urls.push(urls.get(i).copied().unwrap_or_default());
+        }
+
+        vod.vod_play_from = froms.join(separator);
+        if separator == PLAY_SEPARATOR {
+            vod.vod_play_url = Some(kept_urls.join(PLAY_SEPARATOR));
+        }
+        !froms.is_empty()
+    }
+
+    // 去除HTML标签、网址和广告文本
+    fn clean_content(&self, content: &str) -> String {
+        let text = content
+            .replace("<br>", "\n")
+            .replace("<br/>", "\n")
+            .replace("<br />", "\n")
+            .replace("</p>", "\n");
+        let text = HTML_TAG_RE.replace_all(&text, "");
+        let text = html_escape::decode_html_entities(&text);
+        let mut text = URL_RE.replace_all(&text, "").into_owned();
+
+        for pattern in &self.ad_patterns {
+            text = pattern.remove_from(&text);
+        }
+
+        text.lines()
+            .map(|line| BLANK_RE.replace_all(line.trim(), " ").into_owned())
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vod(name: &str, class: &str, play_from: &str, play_url: &str, content: &str) -> VodApiListEntry {
+        serde_json::from_value(serde_json::json!({
+            "vod_id": 1,
+            "vod_name": name,
+            "type_id": 1,
+            "vod_time": "",
+            "vod_remarks": "",
+            "vod_class": class,
+            "vod_play_from": play_from,
+            "vod_play_url": play_url,
+            "vod_content": content,
+        }))
+        .unwrap()
+    }
+
+    fn rules(filter: &str, filter_from: &str, remove_ad: i32) -> CollectRules {
+        CollectRules::parse(filter, filter_from, remove_ad).unwrap()
+    }
+
+    #[test]
+    fn parse_rejects_invalid_regex_with_line_number() {
+        let err = CollectRules::parse("预告\n# 注释\nname:/[/", "", 0).unwrap_err();
+        assert!(err.contains("第 3 行"), "{}", err);
+    }
+
+    #[test]
+    fn blacklist_matches_keyword_field_and_regex() {
+        let rules = rules("# 注释\n预告\nclass:伦理\nname:/^\\d+$/", "", 0);
+
+        let outcome = rules.apply(&mut vod("庆余年 预告片", "", "", "", ""));
+        assert!(!outcome.accepted);
+        assert_eq!(outcome.reason.as_deref(), Some("名称命中过滤规则: 预告"));
+
+        let outcome = rules.apply(&mut vod("某片", "剧情,伦理", "", "", ""));
+        assert_eq!(outcome.reason.as_deref(), Some("分类命中过滤规则: class:伦理"));
+
+        assert!(!rules.apply(&mut vod("12345", "", "", "", "")).accepted);
+        assert!(rules.apply(&mut vod("庆余年", "剧情", "", "", "")).accepted);
+    }
+
+    #[test]
+    fn unknown_prefix_is_part_of_name_rule() {
+        let rules = rules("http://", "", 0);
+        assert!(!rules.apply(&mut vod("http://example.com", "", "", "", "")).accepted);
+    }
+
+    #[test]
+    fn whitelist_filters_and_renames_standard_sources() {
+        let rules = rules("", "ffm3u8=非凡线路,lzm3u8", 0);
+        let mut entry = vod("庆余年", "", "ffm3u8$$$bad$$$lzm3u8", "a$1$$$b$2$$$c$3", "");
+        assert!(rules.apply(&mut entry).accepted);
+        assert_eq!(entry.vod_play_from, "非凡线路$$$lzm3u8");
+        assert_eq!(entry.vod_play_url.as_deref(), Some("a$1$$$c$3"));
+    }
+
+    #[test]
+    fn whitelist_wildcard_keeps_other_sources() {
+        let rules = rules("", "ffm3u8=非凡线路,*", 0);
+        let mut entry = vod("庆余年", "", "ffm3u8$$$other", "a$1$$$b$2", "");
+        assert!(rules.apply(&mut entry).accepted);
+        assert_eq!(entry.vod_play_from, "非凡线路$$$other");
+        assert_eq!(entry.vod_play_url.as_deref(), Some("a$1$$$b$2"));
+    }
+
+
+    #[test]
+    fn whitelist_rejects_when_no_source_left() {
+        let rules = rules("", "lzm3u8", 0);
+        let outcome = rules.apply(&mut vod("庆余年", "", "ffm3u8$$$bad", "a$1$$$b$2", ""));
+        assert!(!outcome.accepted);
+        assert_eq!(outcome.reason.as_deref(), Some("没有白名单内的播放源"));
+    }
+
+    #[test]
+    fn remove_ad_strips_html_urls_and_ad_rules() {
+        let rules = rules("ad:加微信abc\nad:/QQ群\\d+/", "", 1);
+        let mut entry = vod(
+            "庆余年",
+            "",
+            "",
+            "",
+            "<p>剧情&amp;简介</p><br>更多请访问 https://ads.example.com/x 加微信abc QQ群12345",
+        );
+        assert!(rules.apply(&mut entry).accepted);
+        assert_eq!(entry.vod_content.as_deref(), Some("剧情&简介\n更多请访问"));
+    }
+
+    #[test]
+    fn ad_rules_are_ignored_without_remove_ad() {
+        let rules = rules("ad:加微信", "", 0);
+        let mut entry = vod("庆余年", "", "", "", "简介 加微信");
+        assert!(rules.apply(&mut entry).accepted);
+        assert_eq!(entry.vod_content.as_deref(), Some("简介 加微信"));
+    }
+
+    #[test]
+    fn whitelist_splits_comma_sources_like_the_parser() {
+        let rules = rules("", "lzm3u8", 0);
+        let mut entry = vod("庆余年", "", "ffm3u8,lzm3u8", "第1集$https://a/1.m3u8", "");
+        assert!(rules.apply(&mut entry).accepted);
+        assert_eq!(entry.vod_play_from, "lzm3u8");
+        // 逗号格式的播放源共用同一组地址，地址保持不变
+        assert_eq!(entry.vod_play_url.as_deref(), Some("第1集$https://a/1.m3u8"));
+    }
+
+    #[test]
+    fn play_from_separator_matches_format() {
+        assert_eq!(play_from_separator("ffm3u8$$$lzm3u8"), PLAY_SEPARATOR);
+        assert_eq!(play_from_separator("ffm3u8,lzm3u8"), ",");
+        assert_eq!(play_from_separator("ffm3u8"), ",");
+    }
+}
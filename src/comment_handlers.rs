@@ -0,0 +1,516 @@
+use crate::history_handlers::{media_type_bson, resolve_media};
+use crate::jwt_auth::AuthenticatedUser;
+use crate::models::{
+    Comment, CommentLike, Config, MediaType, User, COMMENT_STATUS_APPROVED,
+    COMMENT_STATUS_PENDING,
+};
+use crate::template::TERA;
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use futures::stream::TryStreamExt;
+use mongodb::bson::{doc, oid::ObjectId, DateTime};
+use mongodb::options::{FindOneOptions, FindOptions};
+use mongodb::{Collection, Database};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+pub const COMMENT_COLLECTION: &str = "comments";
+pub const COMMENT_LIKE_COLLECTION: &str = "comment_likes";
+// 评论配置项
+pub const CONFIG_COMMENT_AUDIT: &str = "comment_audit";
+pub const CONFIG_COMMENT_SENSITIVE_WORDS: &str = "comment_sensitive_words";
+
+const COMMENTS_PER_PAGE: u64 = 10;
+const MAX_COMMENT_CHARS: usize = 500;
+// 同一用户两次发表评论的最小间隔
+const COMMENT_INTERVAL_SECS: i64 = 10;
+
+// 评论配置
+pub struct CommentSettings {
+    pub audit: bool,
+    pub sensitive_words: Vec<String>,
+}
+
+/// 读取评论配置，直接查库以便后台修改后立即生效
+pub async fn load_comment_settings(db: &Database) -> CommentSettings {
+    let configs: Vec<Config> = match db
+        .collection::<Config>("configs")
+        .find(
+            doc! { "config_key": { "$in": [CONFIG_COMMENT_AUDIT, CONFIG_COMMENT_SENSITIVE_WORDS] } },
+            None,
+        )
+        .await
+    {
+        Ok(cursor) => cursor.try_collect().await.unwrap_or_default(),
+        Err(_) => vec![],
+    };
+
+    let value = |key: &str| {
+        configs
+            .iter()
+            .find(|c| c.config_key == key)
+            .map(|c| c.config_value.clone())
+    };
+    CommentSettings {
+        // 未配置时默认先审后发
+        audit: value(CONFIG_COMMENT_AUDIT).is_none_or(|v| v.trim() != "关闭"),
+        sensitive_words: value(CONFIG_COMMENT_SENSITIVE_WORDS)
+            .unwrap_or_default()
+            .split([',', '，', '\n', '|'])
+            .map(str::trim)
+            .filter(|w| !w.is_empty())
+            .map(str::to_string)
+            .collect(),
+    }
+}
+
+/// 将敏感词替换为等长的 *，返回过滤后的内容及是否命中
+pub fn filter_sensitive_words(content: &str, words: &[String]) -> (String, bool) {
+    let mut filtered = content.to_string();
+    let mut hit = false;
+    for word in words {
+        if filtered.contains(word.as_str()) {
+            hit = true;
+            filtered = filtered.replace(word.as_str(), &"*".repeat(word.chars().count()));
+        }
+    }
+    (filtered, hit)
+}
+
+// 评论展示数据
+#[derive(Debug, Serialize)]
+pub struct CommentView {
+    pub id: String,
+    pub user_name: String,
+    pub reply_to_user: Option<String>,
+    pub content: String,
+    pub likes: i32,
+    pub created_at: String,
+    pub replies: Vec<CommentView>,
+}
+
+impl From<&Comment> for CommentView {
+    fn from(comment: &Comment) -> Self {
+        let created_at = chrono::DateTime::from_timestamp_millis(comment.created_at.timestamp_millis())
+            .map(|t| t.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_default();
+        Self {
+            id: comment.id.map(|id| id.to_hex()).unwrap_or_default(),
+            user_name: comment.user_name.clone(),
+            reply_to_user: comment.reply_to_user.clone(),
+            content: comment.content.clone(),
+            likes: comment.likes,
+            created_at,
+            replies: Vec::new(),
+        }
+    }
+}
+
+// 一页评论，供 Tera 模板渲染
+#[derive(Debug, Serialize)]
+pub struct CommentPage {
+    pub media_type: String,
+    pub media_id: String,
+    pub items: Vec<CommentView>,
+    pub page: u64,
+    pub total: u64,
+    pub total_pages: u64,
+}
+
+/// 分页加载已通过审核的顶级评论及其回复
+pub async fn load_comment_page(
+    db: &Database,
+    media_type: &MediaType,
+    media_id: ObjectId,
+    page: u64,
+) -> Result<CommentPage, mongodb::error::Error> {
+    let collection = db.collection::<Comment>(COMMENT_COLLECTION);
+    let page = page.max(1);
+    let filter = doc! {
+        "media_type": media_type_bson(media_type),
+        "media_id": media_id,
+        "parent_id": null,
+        "status": COMMENT_STATUS_APPROVED,
+    };
+    let total = collection.count_documents(filter.clone(), None).await?;
+
+    let options = FindOptions::builder()
+        .sort(doc! { "created_at": -1 })
+        .skip((page - 1) * COMMENTS_PER_PAGE)
+        .limit(COMMENTS_PER_PAGE as i64)
+        .build();
+    let comments: Vec<Comment> = collection.find(filter, options).await?.try_collect().await?;
+
+    let parent_ids: Vec<ObjectId> = comments.iter().filter_map(|c| c.id).collect();
+    let replies: Vec<Comment> = if parent_ids.is_empty() {
+        vec![]
+    } else {
+        let options = FindOptions::builder().sort(doc! { "created_at": 1 }).build();
+        collection
+            .find(
+                doc! { "parent_id": { "$in": &parent_ids }, "status": COMMENT_STATUS_APPROVED },
+                options,
+            )
+            .await?
+            .try_collect()
+            .await?
+    };
+
+    let items = comments
+        .iter()
+        .map(|comment| {
+            let mut view = CommentView::from(comment);
+            view.replies = replies
+                .iter()
+                .filter(|reply| reply.parent_id == comment.id)
+                .map(CommentView::from)
+                .collect();
+            view
+        })
+        .collect();
+
+    Ok(CommentPage {
+        media_type: media_type.slug().to_string(),
+        media_id: media_id.to_hex(),
+        items,
+        page,
+        total,
+        total_pages: total.div_ceil(COMMENTS_PER_PAGE),
+    })
+}
+
+// 评论分页参数
+#[derive(Debug, Deserialize)]
+pub struct CommentPageQuery {
+    pub page: Option<u64>,
+}
+
+// 渲染评论列表片段，详情页翻页时通过 AJAX 加载
+pub async fn comment_list_fragment(
+    path: web::Path<(String, String)>,
+    query: web::Query<CommentPageQuery>,
+    db: web::Data<Database>,
+) -> impl Responder {
+    let (media_type, media_id) = path.into_inner();
+    let Some(media_type) = MediaType::from_slug(&media_type) else {
+        return HttpResponse::NotFound().body("Unknown media type");
+    };
+    let Ok(media_id) = ObjectId::parse_str(&media_id) else {
+        return HttpResponse::BadRequest().body("Invalid media ID");
+    };
+
+    let comment_page =
+        match load_comment_page(&db, &media_type, media_id, query.page.unwrap_or(1)).await {
+            Ok(page) => page,
+            Err(e) => {
+                eprintln!("加载评论失败: {}", e);
+                return HttpResponse::InternalServerError().body("Failed to load comments");
+            }
+        };
+
+    let mut context = tera::Context::new();
+    context.insert("comment_page", &comment_page);
+    match TERA.render("partials/comment_list.html", &context) {
+        Ok(html) => HttpResponse::Ok().content_type("text/html").body(html),
+        Err(e) => {
+            crate::web_handlers::handle_template_rendering_error(
+                "partials/comment_list.html",
+                &e,
+                Some("Comment list fragment"),
+                None,
+            );
+            HttpResponse::InternalServerError().body("Template error")
+        }
+    }
+}
+
+fn default_media_type() -> MediaType {
+    MediaType::Vod
+}
+
+// 发表评论请求
+#[derive(Debug, Deserialize)]
+pub struct CreateCommentRequest {
+    #[serde(default = "default_media_type")]
+    pub media_type: MediaType,
+    pub media_id: String,
+    pub content: String,
+    pub parent_id: Option<String>,
+}
+
+// 发表评论或回复
+pub async fn create_comment(
+    user: AuthenticatedUser,
+    http_req: HttpRequest,
+    db: web::Data<Database>,
+    req: web::Json<CreateCommentRequest>,
+) -> impl Responder {
+    let Some(user_id) = user.user.id else {
+        return HttpResponse::Unauthorized().json(json!({"success": false, "message": "用户信息无效"}));
+    };
+
+    // 令牌中的用户状态可能已过期，以数据库为准判断是否被禁言
+    match db
+        .collection::<User>("users")
+        .find_one(doc! { "_id": user_id }, None)
+        .await
+    {
+        Ok(Some(u)) if u.user_status == 1 => {}
+        Ok(_) => {
+            return HttpResponse::Forbidden().json(json!({"success": false, "message": "账号已被禁用，无法评论"}));
+        }
+        Err(e) => {
+            eprintln!("查询用户失败: {}", e);
+            return HttpResponse::InternalServerError().json(json!({"success": false, "message": "评论失败"}));
+        }
+    }
+
+    let content = req.content.trim();
+    if content.is_empty() {
+        return HttpResponse::BadRequest().json(json!({"success": false, "message": "评论内容不能为空"}));
+    }
+    if content.chars().count() > MAX_COMMENT_CHARS {
+        return HttpResponse::BadRequest().json(json!({
+            "success": false,
+            "message": format!("评论内容不能超过 {} 个字", MAX_COMMENT_CHARS)
+        }));
+    }
+    let media_id = match ObjectId::parse_str(&req.media_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return HttpResponse::BadRequest().json(json!({"success": false, "message": "无效的媒体ID"}));
+        }
+    };
+
+    let collection = db.collection::<Comment>(COMMENT_COLLECTION);
+
+    // 限制发表频率
+    let since = DateTime::from_millis(DateTime::now().timestamp_millis() - COMMENT_INTERVAL_SECS * 1000);
+    match collection
+        .count_documents(doc! { "user_id": user_id, "created_at": { "$gt": since } }, None)
+        .await
+    {
+        Ok(0) => {}
+        Ok(_) => {
+            return HttpResponse::TooManyRequests().json(json!({"success": false, "message": "评论太频繁，请稍后再试"}));
+        }
+        Err(e) => {
+            eprintln!("查询评论频率失败: {}", e);
+            return HttpResponse::InternalServerError().json(json!({"success": false, "message": "评论失败"}));
+        }
+    }
+
+    let media = match resolve_media(&db, &req.media_type, media_id).await {
+        Ok(Some(media)) => media,
+        Ok(None) => {
+            return HttpResponse::NotFound().json(json!({"success": false, "message": "媒体不存在"}));
+        }
+        Err(e) => {
+            eprintln!("查询媒体信息失败: {}", e);
+            return HttpResponse::InternalServerError().json(json!({"success": false, "message": "评论失败"}));
+        }
+    };
+
+    // 回复统一挂到顶级评论下，并记录被回复的用户
+    let (parent_id, reply_to_user) = match req.parent_id.as_deref().filter(|id| !id.is_empty()) {
+        Some(parent_id) => {
+            let Ok(parent_id) = ObjectId::parse_str(parent_id) else {
+                return HttpResponse::BadRequest().json(json!({"success": false, "message": "无效的回复对象"}));
+            };
+            let parent = match collection
+                .find_one(
+                    doc! {
+                        "_id": parent_id,
+                        "media_type": media_type_bson(&req.media_type),
+                        "media_id": media_id,
+                        "status": COMMENT_STATUS_APPROVED,
+                    },
+                    None,
+                )
+                .await
+            {
+                Ok(Some(parent)) => parent,
+                Ok(None) => {
+                    return HttpResponse::NotFound().json(json!({"success": false, "message": "回复的评论不存在"}));
+                }
+                Err(e) => {
+                    eprintln!("查询回复对象失败: {}", e);
+                    return HttpResponse::InternalServerError().json(json!({"success": false, "message": "评论失败"}));
+                }
+            };
+            (parent.parent_id.or(parent.id), Some(parent.user_name))
+        }
+        None => (None, None),
+    };
+
+    let settings = load_comment_settings(&db).await;
+    let (content, sensitive_hit) = filter_sensitive_words(content, &settings.sensitive_words);
+    // 开启审核或命中敏感词的评论进入审核队列
+    let status = if settings.audit || sensitive_hit {
+        COMMENT_STATUS_PENDING
+    } else {
+        COMMENT_STATUS_APPROVED
+    };
+
+    let now = DateTime::now();
+    let comment = Comment {
+        id: None,
+        media_type: req.media_type.clone(),
+        media_id,
+        media_name: media.name,
+        user_id,
+        user_name: user.user.user_name.clone(),
+        parent_id,
+        reply_to_user,
+        content,
+        status,
+        sensitive_hit,
+        likes: 0,
+        ip: http_req.connection_info().realip_remote_addr().map(str::to_string),
+        created_at: now,
+        updated_at: now,
+    };
+
+    match collection.insert_one(&comment, None).await {
+        Ok(_) => HttpResponse::Ok().json(json!({
+            "success": true,
+            "pending": status == COMMENT_STATUS_PENDING,
+            "message": if status == COMMENT_STATUS_PENDING { "评论已提交，审核通过后显示" } else { "评论成功" }
+        })),
+        Err(e) => {
+            eprintln!("保存评论失败: {}", e);
+            HttpResponse::InternalServerError().json(json!({"success": false, "message": "评论失败"}))
+        }
+    }
+}
+
+// 点赞或取消点赞评论
+pub async fn toggle_comment_like(
+    user: AuthenticatedUser,
+    db: web::Data<Database>,
+    path: web::Path<String>,
+) -> impl Responder {
+    let Some(user_id) = user.user.id else {
+        return HttpResponse::Unauthorized().json(json!({"success": false, "message": "用户信息无效"}));
+    };
+    let Ok(comment_id) = ObjectId::parse_str(path.into_inner()) else {
+        return HttpResponse::BadRequest().json(json!({"success": false, "message": "无效的评论ID"}));
+    };
+
+    let comments = db.collection::<Comment>(COMMENT_COLLECTION);
+    match comments
+        .count_documents(doc! { "_id": comment_id, "status": COMMENT_STATUS_APPROVED }, None)
+        .await
+    {
+        Ok(0) => {
+            return HttpResponse::NotFound().json(json!({"success": false, "message": "评论不存在"}));
+        }
+        Ok(_) => {}
+        Err(e) => {
+            eprintln!("查询评论失败: {}", e);
+            return HttpResponse::InternalServerError().json(json!({"success": false, "message": "操作失败"}));
+        }
+    }
+
+    // 依赖 comment_id + user_id 唯一索引，插入重复即表示已点赞，改为取消
+    let likes = db.collection::<CommentLike>(COMMENT_LIKE_COLLECTION);
+    let liked = match crate::db::toggle_unique(&likes, doc! { "comment_id": comment_id, "user_id": user_id }).await {
+        Ok(Some(liked)) => liked,
+        // 已被并发请求取消
+        Ok(None) => {
+            return HttpResponse::Conflict().json(json!({"success": false, "message": "操作过于频繁"}));
+        }
+        Err(e) => {
+            eprintln!("切换点赞失败: {}", e);
+            return HttpResponse::InternalServerError().json(json!({"success": false, "message": "操作失败"}));
+        }
+    };
+
+    let delta = if liked { 1 } else { -1 };
+    if let Err(e) = comments
+        .update_one(doc! { "_id": comment_id }, doc! { "$inc": { "likes": delta } }, None)
+        .await
+    {
+        eprintln!("更新点赞数失败: {}", e);
+    }
+    let options = FindOneOptions::builder().projection(doc! { "likes": 1 }).build();
+    let count = match db
+        .collection::<mongodb::bson::Document>(COMMENT_COLLECTION)
+        .find_one(doc! { "_id": comment_id }, options)
+        .await
+    {
+        Ok(Some(doc)) => doc.get_i32("likes").unwrap_or(0),
+        _ => 0,
+    };
+
+    HttpResponse::Ok().json(json!({"success": true, "liked": liked, "likes": count}))
+}
+
+// 删除自己的评论，删除顶级评论时一并删除其回复
+pub async fn delete_own_comment(
+    user: AuthenticatedUser,
+    db: web::Data<Database>,
+    path: web::Path<String>,
+) -> impl Responder {
+    let Some(user_id) = user.user.id else {
+        return HttpResponse::Unauthorized().json(json!({"success": false, "message": "用户信息无效"}));
+    };
+    let Ok(comment_id) = ObjectId::parse_str(path.into_inner()) else {
+        return HttpResponse::BadRequest().json(json!({"success": false, "message": "无效的评论ID"}));
+    };
+
+    match delete_comments(&db, &[comment_id], Some(user_id)).await {
+        Ok(0) => HttpResponse::NotFound().json(json!({"success": false, "message": "评论不存在"})),
+        Ok(_) => HttpResponse::Ok().json(json!({"success": true, "message": "评论已删除"})),
+        Err(e) => {
+            eprintln!("删除评论失败: {}", e);
+            HttpResponse::InternalServerError().json(json!({"success": false, "message": "删除失败"}))
+        }
+    }
+}
+
+/// 删除评论及其回复和点赞记录，指定 user_id 时只删除该用户的评论，返回删除的评论数
+pub async fn delete_comments(
+    db: &Database,
+    ids: &[ObjectId],
+    user_id: Option<ObjectId>,
+) -> Result<u64, mongodb::error::Error> {
+    let comments = db.collection::<Comment>(COMMENT_COLLECTION);
+    let mut filter = doc! { "_id": { "$in": ids } };
+    if let Some(user_id) = user_id {
+        filter.insert("user_id", user_id);
+    }
+    let owned = find_comment_ids(&comments, filter).await?;
+    if owned.is_empty() {
+        return Ok(0);
+    }
+
+    // 回复的点赞也要一并删除，先查出包含回复在内的全部评论ID
+    let removed = find_comment_ids(
+        &comments,
+        doc! { "$or": [{ "_id": { "$in": &owned } }, { "parent_id": { "$in": &owned } }] },
+    )
+    .await?;
+    let result = comments
+        .delete_many(doc! { "_id": { "$in": &removed } }, None)
+        .await?;
+    db.collection::<CommentLike>(COMMENT_LIKE_COLLECTION)
+        .delete_many(doc! { "comment_id": { "$in": &removed } }, None)
+        .await?;
+    Ok(result.deleted_count)
+}
+
+async fn find_comment_ids(
+    comments: &Collection<Comment>,
+    filter: mongodb::bson::Document,
+) -> Result<Vec<ObjectId>, mongodb::error::Error> {
+    let options = FindOptions::builder().projection(doc! { "_id": 1 }).build();
+    Ok(comments
+        .clone_with_type::<mongodb::bson::Document>()
+        .find(filter, options)
+        .await?
+        .try_collect::<Vec<_>>()
+        .await?
+        .iter()
+        .filter_map(|d| d.get_object_id("_id").ok())
+        .collect())
+}
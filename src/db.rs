@@ -1,4 +1,8 @@
-use mongodb::{Client, Database, options::ClientOptions};
+use futures::TryStreamExt;
+use mongodb::bson::{DateTime, Document, doc};
+use mongodb::error::{ErrorKind, WriteFailure};
+use mongodb::options::{FindOneAndUpdateOptions, FindOptions, ReturnDocument};
+use mongodb::{Client, Collection, Database, options::ClientOptions};
 use std::env;
 use std::time::Duration;
 use dotenv::dotenv;
@@ -17,3 +21,136 @@ pub async fn init() -> Result<Database, mongodb::error::Error> {
     let database_name = env::var("DATABASE_NAME").expect("DATABASE_NAME must be set");
     Ok(client.database(&database_name))
 }
+
+// 依赖唯一索引切换一条关系记录（点赞、追更等）：插入成功即开启，唯一键冲突说明已存在，改为删除即关闭。
+// 返回 Some(true) 表示开启、Some(false) 表示关闭；None 表示记录已被并发请求删除，本次没有产生变更
+pub async fn toggle_unique<T>(collection: &Collection<T>, key: Document) -> Result<Option<bool>, mongodb::error::Error> {
+    let collection = collection.clone_with_type::<Document>();
+    let mut record = key.clone();
+    record.insert("created_at", DateTime::now());
+    match collection.insert_one(record, None).await {
+        Ok(_) => Ok(Some(true)),
+        Err(e) => match *e.kind {
+            ErrorKind::Write(WriteFailure::WriteError(ref we)) if we.code == 11000 => {
+                let result = collection.delete_one(key, None).await?;
+                Ok((result.deleted_count > 0).then_some(false))
+            }
+            _ => Err(e),
+        },
+    }
+}
+
+// 获取自增序列的下一个值（counters 集合，用于生成稳定的数字ID）
+pub async fn next_sequence(db: &Database, name: &str) -> Result<i64, mongodb::error::Error> {
+    let options = FindOneAndUpdateOptions::builder()
+        .upsert(true)
+        .return_document(ReturnDocument::After)
+        .build();
+
+    let counter = db
+        .collection::<Document>("counters")
+        .find_one_and_update(doc! { "_id": name }, doc! { "$inc": { "seq": 1_i64 } }, options)
+        .await?;
+
+    Ok(counter
+        .and_then(|c| c.get_i64("seq").ok())
+        .unwrap_or(1))
+}
+
+// 为缺少 vod_id 的历史视频补齐数字ID
+pub async fn backfill_vod_ids(db: &Database) -> Result<u64, mongodb::error::Error> {
+    let collection = db.collection::<Document>("vods");
+    let options = FindOptions::builder()
+        .sort(doc! { "_id": 1 })
+        .projection(doc! { "_id": 1 })
+        .build();
+
+    let mut cursor = collection
+        .find(doc! { "vod_id": { "$exists": false } }, options)
+        .await?;
+
+    let mut count = 0;
+    while let Some(vod) = cursor.try_next().await? {
+        if let Ok(id) = vod.get_object_id("_id") {
+            let vod_id = next_sequence(db, "vod_id").await?;
+            collection
+                .update_one(doc! { "_id": id }, doc! { "$set": { "vod_id": vod_id } }, None)
+                .await?;
+            count += 1;
+        }
+    }
+
+    Ok(count)
+}
+
+// 为缺少规范化名称的视频补齐 vod_name_key（用于重复判定）
+pub async fn backfill_vod_name_keys(db: &Database) -> Result<u64, mongodb::error::Error> {
+    let collection = db.collection::<Document>("vods");
+    let options = FindOptions::builder()
+        .projection(doc! { "_id": 1, "vod_name": 1 })
+        .build();
+
+    let mut cursor = collection
+        .find(doc! { "vod_name_key": { "$exists": false } }, options)
+        .await?;
+
+    let mut count = 0;
+    while let Some(vod) = cursor.try_next().await? {
+        if let (Ok(id), Ok(name)) = (vod.get_object_id("_id"), vod.get_str("vod_name")) {
+            let key = crate::vod_match::normalize_vod_name(name);
+            collection
+                .update_one(doc! { "_id": id }, doc! { "$set": { "vod_name_key": key } }, None)
+                .await?;
+            count += 1;
+        }
+    }
+
+    Ok(count)
+}
+
+// 为引入审核前的图集补齐审核状态：已发布的视为已通过，其余进入待审核
+pub async fn backfill_image_review_status(db: &Database) -> Result<u64, mongodb::error::Error> {
+    use crate::models::{IMAGE_REVIEW_APPROVED, IMAGE_REVIEW_PENDING};
+
+    let collection = db.collection::<Document>(crate::image_handlers::IMAGE_COLLECTION);
+    let approved = collection
+        .update_many(
+            doc! { "review_status": { "$exists": false }, "status": "published" },
+            doc! { "$set": { "review_status": IMAGE_REVIEW_APPROVED } },
+            None,
+        )
+        .await?;
+    let pending = collection
+        .update_many(
+            doc! { "review_status": { "$exists": false } },
+            doc! { "$set": { "review_status": IMAGE_REVIEW_PENDING } },
+            None,
+        )
+        .await?;
+
+    Ok(approved.modified_count + pending.modified_count)
+}
+
+// 为引入连载审核前的连载补齐审核状态：已有审核通过章节的视为已通过，其余进入待审核
+pub async fn backfill_series_review_status(db: &Database) -> Result<u64, mongodb::error::Error> {
+    use crate::models::{IMAGE_REVIEW_APPROVED, IMAGE_REVIEW_PENDING};
+
+    let collection = db.collection::<Document>(crate::series_handlers::SERIES_COLLECTION);
+    let visible = crate::series_handlers::visible_series_ids(db).await?;
+    let approved = collection
+        .update_many(
+            doc! { "review_status": { "$exists": false }, "_id": { "$in": visible } },
+            doc! { "$set": { "review_status": IMAGE_REVIEW_APPROVED } },
+            None,
+        )
+        .await?;
+    let pending = collection
+        .update_many(
+            doc! { "review_status": { "$exists": false } },
+            doc! { "$set": { "review_status": IMAGE_REVIEW_PENDING } },
+            None,
+        )
+        .await?;
+
+    Ok(approved.modified_count + pending.modified_count)
+}
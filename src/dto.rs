@@ -133,22 +133,6 @@ pub struct JsonResponse<T> {
     pub categories: Vec<Category>,
 }
 
-// Struct for video list responses (without categories field)
-#[derive(Debug, Serialize, Deserialize)]
-pub struct VideoListResponse {
-    pub code: i32,
-    pub msg: String,
-    #[serde(deserialize_with = "deserialize_string_or_number")]
-    pub page: u64,
-    #[serde(deserialize_with = "deserialize_string_or_number")]
-    pub pagecount: u64,
-    #[serde(deserialize_with = "deserialize_string_or_number")]
-    pub limit: u64,
-    #[serde(deserialize_with = "deserialize_string_or_number")]
-    pub total: u64,
-    pub list: Vec<VodApiListEntry>,
-}
-
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Category {
     pub type_id: i32,
@@ -197,7 +181,7 @@ where
 
 // A simplified Vod structure for the API list response
 // The full detail response might use the main Vod model
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VodApiListEntry {
     pub vod_id: VodId,
     pub vod_name: String,
@@ -277,8 +261,48 @@ pub struct VodApiListEntry {
     pub vod_down_url: Option<String>,
 }
 
-// TODO: Define structs for XML serialization using quick-xml attributes
-// This will be more involved and will be handled in the handler implementation.
+// MacCMS 文章采集接口（ac=detail）返回的条目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArtApiListEntry {
+    pub art_id: VodId,
+    pub art_name: String,
+    pub type_id: i32,
+    pub type_name: Option<String>,
+    pub art_pic: Option<String>,
+    pub art_author: Option<String>,
+    pub art_from: Option<String>,
+    pub art_remarks: Option<String>,
+    pub art_blurb: Option<String>,
+    pub art_tag: Option<String>,
+    pub art_time: Option<String>,
+    pub art_content: Option<String>,
+}
+
+// MacCMS ac=list 返回的精简条目
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VodApiBriefEntry {
+    pub vod_id: VodId,
+    pub vod_name: String,
+    pub type_id: i32,
+    pub type_name: Option<String>,
+    pub vod_time: String,
+    pub vod_remarks: String,
+    pub vod_play_from: String,
+}
+
+impl From<&VodApiListEntry> for VodApiBriefEntry {
+    fn from(entry: &VodApiListEntry) -> Self {
+        Self {
+            vod_id: entry.vod_id.clone(),
+            vod_name: entry.vod_name.clone(),
+            type_id: entry.type_id,
+            type_name: entry.type_name.clone(),
+            vod_time: entry.vod_time.clone(),
+            vod_remarks: entry.vod_remarks.clone(),
+            vod_play_from: entry.vod_play_from.clone(),
+        }
+    }
+}
 
 #[derive(Debug, Deserialize)]
 pub struct ListPageParams {
@@ -299,6 +323,39 @@ pub struct VideoFilterParams {
     pub limit: Option<u64>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct RankParams {
+    pub board: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_empty_string_to_none")]
+    pub type_id: Option<i32>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AudioListParams {
+    pub category: Option<String>,
+    pub pg: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GalleryListParams {
+    pub category: Option<String>,
+    pub tag: Option<String>,
+    pub pg: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GalleryReaderParams {
+    pub p: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SearchParams {
+    pub wd: Option<String>,
+    pub kind: Option<String>,
+    pub pg: Option<u64>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CategoryHierarchy {
     pub category: crate::models::Type,
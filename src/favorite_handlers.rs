@@ -0,0 +1,240 @@
+use crate::history_handlers::{media_type_bson, resolve_media, MediaTypeQuery};
+use crate::jwt_auth::AuthenticatedUser;
+use crate::models::{MediaType, UserFavorite};
+use actix_web::{web, HttpResponse, Responder};
+use futures::stream::TryStreamExt;
+use mongodb::bson::{doc, oid::ObjectId, DateTime};
+use mongodb::error::{ErrorKind, WriteFailure};
+use mongodb::options::FindOptions;
+use mongodb::Database;
+use serde::Deserialize;
+use serde_json::json;
+
+pub const FAVORITE_COLLECTION: &str = "user_favorites";
+
+fn default_media_type() -> MediaType {
+    MediaType::Vod
+}
+
+// 添加收藏请求
+#[derive(Debug, Deserialize)]
+pub struct AddFavoriteRequest {
+    #[serde(default = "default_media_type")]
+    pub media_type: MediaType,
+    pub media_id: String,
+}
+
+// 收藏列表查询参数
+#[derive(Debug, Deserialize)]
+pub struct FavoriteQuery {
+    pub media_type: Option<MediaType>,
+    pub page: Option<u64>,
+    pub limit: Option<u64>,
+}
+
+/// 统计媒体被收藏的次数，供详情页展示
+pub async fn favorite_count(db: &Database, media_type: &MediaType, media_id: ObjectId) -> u64 {
+    db.collection::<UserFavorite>(FAVORITE_COLLECTION)
+        .count_documents(
+            doc! { "media_type": media_type_bson(media_type), "media_id": media_id },
+            None,
+        )
+        .await
+        .unwrap_or(0)
+}
+
+// 添加收藏，重复收藏同一媒体时直接返回已收藏
+pub async fn add_favorite(
+    user: AuthenticatedUser,
+    db: web::Data<Database>,
+    req: web::Json<AddFavoriteRequest>,
+) -> impl Responder {
+    let Some(user_id) = user.user.id else {
+        return HttpResponse::Unauthorized().json(json!({"success": false, "message": "用户信息无效"}));
+    };
+    let media_id = match ObjectId::parse_str(&req.media_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return HttpResponse::BadRequest().json(json!({"success": false, "message": "无效的媒体ID"}));
+        }
+    };
+
+    let media = match resolve_media(&db, &req.media_type, media_id).await {
+        Ok(Some(media)) => media,
+        Ok(None) => {
+            return HttpResponse::NotFound().json(json!({"success": false, "message": "媒体不存在"}));
+        }
+        Err(e) => {
+            eprintln!("查询媒体信息失败: {}", e);
+            return HttpResponse::InternalServerError().json(json!({"success": false, "message": "收藏失败"}));
+        }
+    };
+
+    let now = DateTime::now();
+    let favorite = UserFavorite {
+        id: None,
+        user_id,
+        media_type: req.media_type.clone(),
+        media_id,
+        media_name: media.name,
+        poster: media.poster,
+        category: media.category,
+        remarks: media.remarks,
+        created_at: now,
+        updated_at: now,
+    };
+
+    // 依赖 user_id + media_type + media_id 唯一索引防止重复收藏
+    let message = match db
+        .collection::<UserFavorite>(FAVORITE_COLLECTION)
+        .insert_one(&favorite, None)
+        .await
+    {
+        Ok(_) => "收藏成功",
+        Err(e) => match *e.kind {
+            ErrorKind::Write(WriteFailure::WriteError(ref we)) if we.code == 11000 => "已在收藏中",
+            _ => {
+                eprintln!("添加收藏失败: {}", e);
+                return HttpResponse::InternalServerError().json(json!({"success": false, "message": "收藏失败"}));
+            }
+        },
+    };
+
+    HttpResponse::Ok().json(json!({
+        "success": true,
+        "message": message,
+        "favorited": true,
+        "count": favorite_count(&db, &req.media_type, media_id).await
+    }))
+}
+
+// 取消收藏
+pub async fn remove_favorite(
+    user: AuthenticatedUser,
+    db: web::Data<Database>,
+    path: web::Path<String>,
+    query: web::Query<MediaTypeQuery>,
+) -> impl Responder {
+    let Some(user_id) = user.user.id else {
+        return HttpResponse::Unauthorized().json(json!({"success": false, "message": "用户信息无效"}));
+    };
+    let media_id = match ObjectId::parse_str(path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => {
+            return HttpResponse::BadRequest().json(json!({"success": false, "message": "无效的媒体ID"}));
+        }
+    };
+    let media_type = query.media_type.clone().unwrap_or(MediaType::Vod);
+
+    let filter = doc! {
+        "user_id": user_id,
+        "media_type": media_type_bson(&media_type),
+        "media_id": media_id,
+    };
+    match db
+        .collection::<UserFavorite>(FAVORITE_COLLECTION)
+        .delete_one(filter, None)
+        .await
+    {
+        Ok(result) => HttpResponse::Ok().json(json!({
+            "success": true,
+            "message": if result.deleted_count > 0 { "已取消收藏" } else { "未收藏该内容" },
+            "favorited": false,
+            "count": favorite_count(&db, &media_type, media_id).await
+        })),
+        Err(e) => {
+            eprintln!("取消收藏失败: {}", e);
+            HttpResponse::InternalServerError().json(json!({"success": false, "message": "取消收藏失败"}))
+        }
+    }
+}
+
+// 分页获取收藏列表，最近收藏的在前
+pub async fn get_favorites(
+    user: AuthenticatedUser,
+    db: web::Data<Database>,
+    query: web::Query<FavoriteQuery>,
+) -> impl Responder {
+    let Some(user_id) = user.user.id else {
+        return HttpResponse::Unauthorized().json(json!({"success": false, "message": "用户信息无效"}));
+    };
+
+    let mut filter = doc! { "user_id": user_id };
+    if let Some(media_type) = &query.media_type {
+        filter.insert("media_type", media_type_bson(media_type));
+    }
+
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query.limit.unwrap_or(20).clamp(1, 100);
+
+    let collection = db.collection::<UserFavorite>(FAVORITE_COLLECTION);
+    let total = match collection.count_documents(filter.clone(), None).await {
+        Ok(count) => count,
+        Err(e) => {
+            eprintln!("查询收藏总数失败: {}", e);
+            return HttpResponse::InternalServerError().json(json!({"success": false, "message": "查询失败"}));
+        }
+    };
+
+    let options = FindOptions::builder()
+        .sort(doc! { "created_at": -1 })
+        .skip((page - 1) * limit)
+        .limit(limit as i64)
+        .build();
+    let favorites: Vec<UserFavorite> = match collection.find(filter, options).await {
+        Ok(cursor) => cursor.try_collect().await.unwrap_or_default(),
+        Err(e) => {
+            eprintln!("查询收藏列表失败: {}", e);
+            return HttpResponse::InternalServerError().json(json!({"success": false, "message": "查询失败"}));
+        }
+    };
+
+    HttpResponse::Ok().json(json!({
+        "success": true,
+        "favorites": favorites,
+        "page": page,
+        "limit": limit,
+        "total": total,
+        "total_pages": total.div_ceil(limit)
+    }))
+}
+
+// 检查是否已收藏，同时返回收藏总数
+pub async fn check_favorite(
+    user: AuthenticatedUser,
+    db: web::Data<Database>,
+    path: web::Path<String>,
+    query: web::Query<MediaTypeQuery>,
+) -> impl Responder {
+    let Some(user_id) = user.user.id else {
+        return HttpResponse::Unauthorized().json(json!({"success": false, "message": "用户信息无效"}));
+    };
+    let media_id = match ObjectId::parse_str(path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => {
+            return HttpResponse::BadRequest().json(json!({"success": false, "message": "无效的媒体ID"}));
+        }
+    };
+    let media_type = query.media_type.clone().unwrap_or(MediaType::Vod);
+
+    let filter = doc! {
+        "user_id": user_id,
+        "media_type": media_type_bson(&media_type),
+        "media_id": media_id,
+    };
+    match db
+        .collection::<UserFavorite>(FAVORITE_COLLECTION)
+        .count_documents(filter, None)
+        .await
+    {
+        Ok(count) => HttpResponse::Ok().json(json!({
+            "success": true,
+            "favorited": count > 0,
+            "count": favorite_count(&db, &media_type, media_id).await
+        })),
+        Err(e) => {
+            eprintln!("查询收藏状态失败: {}", e);
+            HttpResponse::InternalServerError().json(json!({"success": false, "message": "查询失败"}))
+        }
+    }
+}
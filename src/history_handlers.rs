@@ -0,0 +1,367 @@
+use crate::audio_handlers::published_filter;
+use crate::image_handlers::published_gallery_filter;
+use crate::jwt_auth::AuthenticatedUser;
+use crate::models::{MediaType, UserHistory};
+use actix_web::{web, HttpResponse, Responder};
+use futures::stream::TryStreamExt;
+use mongodb::bson::{self, doc, oid::ObjectId, DateTime, Document};
+use mongodb::options::{FindOneOptions, FindOptions, UpdateOptions};
+use mongodb::Database;
+use serde::Deserialize;
+use serde_json::json;
+
+pub const HISTORY_COLLECTION: &str = "user_histories";
+// 每个用户最多保留的观看记录数
+const MAX_HISTORY_PER_USER: u64 = 200;
+
+fn default_media_type() -> MediaType {
+    MediaType::Vod
+}
+
+// 播放进度上报请求
+#[derive(Debug, Deserialize)]
+pub struct RecordHistoryRequest {
+    #[serde(default = "default_media_type")]
+    pub media_type: MediaType,
+    pub media_id: String,
+    pub media_url: String,
+    pub episode_name: Option<String>,
+    pub current_time: Option<i64>,
+    pub duration: Option<i64>,
+}
+
+// 观看记录列表查询参数
+#[derive(Debug, Deserialize)]
+pub struct HistoryQuery {
+    pub media_type: Option<MediaType>,
+    pub page: Option<u64>,
+    pub limit: Option<u64>,
+}
+
+// 继续观看/清空记录的媒体类型参数
+#[derive(Debug, Deserialize)]
+pub struct MediaTypeQuery {
+    pub media_type: Option<MediaType>,
+}
+
+fn user_object_id(user: &AuthenticatedUser) -> Option<ObjectId> {
+    user.user.id
+}
+
+pub fn media_type_bson(media_type: &MediaType) -> bson::Bson {
+    bson::to_bson(media_type).unwrap_or_default()
+}
+
+// 将秒数格式化为 mm:ss 或 hh:mm:ss
+fn format_watch_time(seconds: i64) -> String {
+    let seconds = seconds.max(0);
+    let (h, m, s) = (seconds / 3600, seconds % 3600 / 60, seconds % 60);
+    if h > 0 {
+        format!("{:02}:{:02}:{:02}", h, m, s)
+    } else {
+        format!("{:02}:{:02}", m, s)
+    }
+}
+
+// 媒体的展示信息，用于观看记录和收藏
+pub struct MediaSummary {
+    pub name: String,
+    pub poster: String,
+    pub category: Option<String>,
+    pub remarks: Option<String>,
+}
+
+/// 查询媒体名称、封面等展示信息，媒体不存在或未公开时返回 None
+pub async fn resolve_media(
+    db: &Database,
+    media_type: &MediaType,
+    media_id: ObjectId,
+) -> Result<Option<MediaSummary>, mongodb::error::Error> {
+    let (name_field, poster_field, category_field, remarks_field) = match media_type {
+        MediaType::Vod => ("vod_name", "vod_pic", "vod_class", "vod_remarks"),
+        MediaType::Image | MediaType::Audio => ("title", "cover", "category", "description"),
+    };
+    let options = FindOneOptions::builder()
+        .projection(doc! {
+            name_field: 1, poster_field: 1, category_field: 1, remarks_field: 1,
+        })
+        .build();
+    // 与前台展示一致：音频和图集只接受已公开的内容，视频详情页不区分状态；
+    // 所属连载未审核通过的章节在详情页按独立图集展示，这里同样只看章节本身
+    let mut filter = match media_type {
+        MediaType::Vod => Document::new(),
+        MediaType::Image => published_gallery_filter(),
+        MediaType::Audio => published_filter(),
+    };
+    filter.insert("_id", media_id);
+    let Some(media) = db
+        .collection::<Document>(media_type.collection())
+        .find_one(filter, options)
+        .await?
+    else {
+        return Ok(None);
+    };
+
+    let poster = match media.get(poster_field) {
+        Some(bson::Bson::String(url)) => url.clone(),
+        Some(bson::Bson::Document(cover)) => cover.get_str("url").unwrap_or_default().to_string(),
+        _ => String::new(),
+    };
+    Ok(Some(MediaSummary {
+        name: media.get_str(name_field).unwrap_or_default().to_string(),
+        poster,
+        category: media.get_str(category_field).ok().map(str::to_string),
+        remarks: media.get_str(remarks_field).ok().map(str::to_string),
+    }))
+}
+
+// 上报播放进度，每个用户每个媒体只保留一条记录并原地更新
+pub async fn record_history(
+    user: AuthenticatedUser,
+    db: web::Data<Database>,
+    req: web::Json<RecordHistoryRequest>,
+) -> impl Responder {
+    let Some(user_id) = user_object_id(&user) else {
+        return HttpResponse::Unauthorized().json(json!({"success": false, "message": "用户信息无效"}));
+    };
+    let media_id = match ObjectId::parse_str(&req.media_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return HttpResponse::BadRequest().json(json!({"success": false, "message": "无效的媒体ID"}));
+        }
+    };
+
+    let media = match resolve_media(&db, &req.media_type, media_id).await {
+        Ok(Some(media)) => media,
+        Ok(None) => {
+            return HttpResponse::NotFound().json(json!({"success": false, "message": "媒体不存在"}));
+        }
+        Err(e) => {
+            eprintln!("查询媒体信息失败: {}", e);
+            return HttpResponse::InternalServerError().json(json!({"success": false, "message": "记录失败"}));
+        }
+    };
+
+    let now = DateTime::now();
+    let current_time = req.current_time.map(|t| t.max(0));
+    let mut set = doc! {
+        "media_name": media.name,
+        "media_url": &req.media_url,
+        "poster": media.poster,
+        "episode_name": req.episode_name.as_deref(),
+        "current_time": current_time,
+        "watch_time": current_time.map(format_watch_time),
+        "timestamp": now.timestamp_millis() / 1000,
+        "updated_at": now,
+    };
+    if let Some(duration) = req.duration.filter(|d| *d > 0) {
+        set.insert("duration", duration);
+    }
+
+    let collection = db.collection::<UserHistory>(HISTORY_COLLECTION);
+    let filter = doc! {
+        "user_id": user_id,
+        "media_type": media_type_bson(&req.media_type),
+        "media_id": media_id,
+    };
+    let update = doc! {
+        "$set": set,
+        "$setOnInsert": { "created_at": now },
+    };
+    let options = UpdateOptions::builder().upsert(true).build();
+
+    match collection.update_one(filter, update, options).await {
+        Ok(result) => {
+            // 新增记录时清理超出上限的旧记录
+            if result.upserted_id.is_some() {
+                prune_history(&db, user_id).await;
+            }
+            HttpResponse::Ok().json(json!({"success": true, "message": "播放进度已记录"}))
+        }
+        Err(e) => {
+            eprintln!("记录观看历史失败: {}", e);
+            HttpResponse::InternalServerError().json(json!({"success": false, "message": "记录失败"}))
+        }
+    }
+}
+
+// 删除超出上限的最旧观看记录
+async fn prune_history(db: &Database, user_id: ObjectId) {
+    let collection = db.collection::<UserHistory>(HISTORY_COLLECTION);
+    let options = FindOptions::builder()
+        .sort(doc! { "updated_at": -1 })
+        .skip(MAX_HISTORY_PER_USER)
+        .projection(doc! { "_id": 1 })
+        .build();
+    let stale: Vec<ObjectId> = match collection
+        .clone_with_type::<Document>()
+        .find(doc! { "user_id": user_id }, options)
+        .await
+    {
+        Ok(cursor) => cursor
+            .try_collect::<Vec<Document>>()
+            .await
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|d| d.get_object_id("_id").ok())
+            .collect(),
+        Err(e) => {
+            eprintln!("查询过期观看记录失败: {}", e);
+            return;
+        }
+    };
+    if !stale.is_empty()
+        && let Err(e) = collection.delete_many(doc! { "_id": { "$in": stale } }, None).await
+    {
+        eprintln!("清理过期观看记录失败: {}", e);
+    }
+}
+
+// 分页获取观看记录，最近观看的在前
+pub async fn get_history(
+    user: AuthenticatedUser,
+    db: web::Data<Database>,
+    query: web::Query<HistoryQuery>,
+) -> impl Responder {
+    let Some(user_id) = user_object_id(&user) else {
+        return HttpResponse::Unauthorized().json(json!({"success": false, "message": "用户信息无效"}));
+    };
+
+    let mut filter = doc! { "user_id": user_id };
+    if let Some(media_type) = &query.media_type {
+        filter.insert("media_type", media_type_bson(media_type));
+    }
+
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query.limit.unwrap_or(20).clamp(1, 100);
+
+    let collection = db.collection::<UserHistory>(HISTORY_COLLECTION);
+    let total = match collection.count_documents(filter.clone(), None).await {
+        Ok(count) => count,
+        Err(e) => {
+            eprintln!("查询观看记录总数失败: {}", e);
+            return HttpResponse::InternalServerError().json(json!({"success": false, "message": "查询失败"}));
+        }
+    };
+
+    let options = FindOptions::builder()
+        .sort(doc! { "updated_at": -1 })
+        .skip((page - 1) * limit)
+        .limit(limit as i64)
+        .build();
+    let histories: Vec<UserHistory> = match collection.find(filter, options).await {
+        Ok(cursor) => cursor.try_collect().await.unwrap_or_default(),
+        Err(e) => {
+            eprintln!("查询观看记录失败: {}", e);
+            return HttpResponse::InternalServerError().json(json!({"success": false, "message": "查询失败"}));
+        }
+    };
+
+    HttpResponse::Ok().json(json!({
+        "success": true,
+        "histories": histories,
+        "page": page,
+        "limit": limit,
+        "total": total,
+        "total_pages": total.div_ceil(limit)
+    }))
+}
+
+// 获取某个媒体的继续观看记录
+pub async fn get_continue_watching(
+    user: AuthenticatedUser,
+    db: web::Data<Database>,
+    path: web::Path<String>,
+    query: web::Query<MediaTypeQuery>,
+) -> impl Responder {
+    let Some(user_id) = user_object_id(&user) else {
+        return HttpResponse::Unauthorized().json(json!({"success": false, "message": "用户信息无效"}));
+    };
+    let media_id = match ObjectId::parse_str(path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => {
+            return HttpResponse::BadRequest().json(json!({"success": false, "message": "无效的媒体ID"}));
+        }
+    };
+    let media_type = query.media_type.clone().unwrap_or(MediaType::Vod);
+
+    let filter = doc! {
+        "user_id": user_id,
+        "media_type": media_type_bson(&media_type),
+        "media_id": media_id,
+    };
+    match db
+        .collection::<UserHistory>(HISTORY_COLLECTION)
+        .find_one(filter, None)
+        .await
+    {
+        Ok(history) => HttpResponse::Ok().json(json!({"success": true, "history": history})),
+        Err(e) => {
+            eprintln!("查询继续观看记录失败: {}", e);
+            HttpResponse::InternalServerError().json(json!({"success": false, "message": "查询失败"}))
+        }
+    }
+}
+
+// 删除单条观看记录
+pub async fn delete_history(
+    user: AuthenticatedUser,
+    db: web::Data<Database>,
+    path: web::Path<String>,
+) -> impl Responder {
+    let Some(user_id) = user_object_id(&user) else {
+        return HttpResponse::Unauthorized().json(json!({"success": false, "message": "用户信息无效"}));
+    };
+    let history_id = match ObjectId::parse_str(path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => {
+            return HttpResponse::BadRequest().json(json!({"success": false, "message": "无效的记录ID"}));
+        }
+    };
+
+    match db
+        .collection::<UserHistory>(HISTORY_COLLECTION)
+        .delete_one(doc! { "_id": history_id, "user_id": user_id }, None)
+        .await
+    {
+        Ok(result) if result.deleted_count > 0 => {
+            HttpResponse::Ok().json(json!({"success": true, "message": "记录已删除"}))
+        }
+        Ok(_) => HttpResponse::NotFound().json(json!({"success": false, "message": "记录不存在"})),
+        Err(e) => {
+            eprintln!("删除观看记录失败: {}", e);
+            HttpResponse::InternalServerError().json(json!({"success": false, "message": "删除失败"}))
+        }
+    }
+}
+
+// 清空观看记录，可按媒体类型清空
+pub async fn clear_history(
+    user: AuthenticatedUser,
+    db: web::Data<Database>,
+    query: web::Query<MediaTypeQuery>,
+) -> impl Responder {
+    let Some(user_id) = user_object_id(&user) else {
+        return HttpResponse::Unauthorized().json(json!({"success": false, "message": "用户信息无效"}));
+    };
+
+    let mut filter = doc! { "user_id": user_id };
+    if let Some(media_type) = &query.media_type {
+        filter.insert("media_type", media_type_bson(media_type));
+    }
+
+    match db
+        .collection::<UserHistory>(HISTORY_COLLECTION)
+        .delete_many(filter, None)
+        .await
+    {
+        Ok(result) => HttpResponse::Ok().json(json!({
+            "success": true,
+            "message": format!("已清空 {} 条观看记录", result.deleted_count)
+        })),
+        Err(e) => {
+            eprintln!("清空观看记录失败: {}", e);
+            HttpResponse::InternalServerError().json(json!({"success": false, "message": "清空失败"}))
+        }
+    }
+}
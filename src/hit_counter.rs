@@ -0,0 +1,133 @@
+use chrono::Local;
+use mongodb::bson::{doc, oid::ObjectId, Document};
+use mongodb::options::UpdateOptions;
+use mongodb::Database;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::time::{interval, Duration};
+
+// 缓冲的点击数写回数据库的周期
+const FLUSH_INTERVAL_SECS: u64 = 10;
+// 记录各统计周期当前所处区间的文档
+const ROLLOVER_STATE_ID: &str = "vod";
+
+/// 视频点击计数器：请求中只累加内存计数，由后台任务定期合并写入数据库
+#[derive(Clone)]
+pub struct HitCounter {
+    pending: Arc<Mutex<HashMap<ObjectId, i32>>>,
+    // 写回期间持有，停机时的写回会等待进行中的写回完成
+    flushing: Arc<tokio::sync::Mutex<()>>,
+    db: Database,
+}
+
+impl HitCounter {
+    pub fn new(db: Database) -> Self {
+        Self {
+            pending: Arc::new(Mutex::new(HashMap::new())),
+            flushing: Arc::new(tokio::sync::Mutex::new(())),
+            db,
+        }
+    }
+
+    /// 记录一次点击（详情页或播放页访问）
+    pub fn record(&self, vod_id: ObjectId) {
+        *self.pending.lock().unwrap().entry(vod_id).or_insert(0) += 1;
+    }
+
+    /// 启动后台写回与周期重置任务：先重置跨过边界的统计，再写回缓冲的点击数
+    pub fn start(&self) {
+        let counter = self.clone();
+        tokio::spawn(async move {
+            let mut timer = interval(Duration::from_secs(FLUSH_INTERVAL_SECS));
+            loop {
+                timer.tick().await;
+                if let Err(e) = counter.rollover().await {
+                    eprintln!("❌ 重置点击统计失败: {}", e);
+                }
+                counter.flush().await;
+            }
+        });
+    }
+
+    /// 将缓冲的点击数写入数据库，写入失败的计数保留到下一次
+    pub async fn flush(&self) {
+        let _flushing = self.flushing.lock().await;
+        let pending = std::mem::take(&mut *self.pending.lock().unwrap());
+        if pending.is_empty() {
+            return;
+        }
+
+        let vods = self.db.collection::<Document>("vods");
+        let mut failed = Vec::new();
+        for (vod_id, hits) in pending {
+            let update = doc! {
+                "$inc": {
+                    "vod_hits": hits,
+                    "vod_hits_day": hits,
+                    "vod_hits_week": hits,
+                    "vod_hits_month": hits,
+                }
+            };
+            if let Err(e) = vods.update_one(doc! { "_id": vod_id }, update, None).await {
+                eprintln!("❌ 写入点击数失败 {}: {}", vod_id, e);
+                failed.push((vod_id, hits));
+            }
+        }
+
+        if !failed.is_empty() {
+            let mut pending = self.pending.lock().unwrap();
+            for (vod_id, hits) in failed {
+                *pending.entry(vod_id).or_insert(0) += hits;
+            }
+        }
+    }
+
+    /// 跨过日、周、月边界时清零对应的点击数（按服务器本地时区）
+    /// 通过条件更新抢占区间切换，多实例部署时每个边界只会重置一次
+    async fn rollover(&self) -> Result<(), mongodb::error::Error> {
+        let now = Local::now();
+        let periods = [
+            ("day", "vod_hits_day", now.format("%Y-%m-%d").to_string()),
+            ("week", "vod_hits_week", now.format("%G-W%V").to_string()),
+            ("month", "vod_hits_month", now.format("%Y-%m").to_string()),
+        ];
+
+        let state = self.db.collection::<Document>("hit_rollovers");
+
+        // 首次运行只记录当前区间，不重置已有数据
+        let mut initial = Document::new();
+        for (period, _, key) in &periods {
+            initial.insert(*period, key);
+        }
+        state
+            .update_one(
+                doc! { "_id": ROLLOVER_STATE_ID },
+                doc! { "$setOnInsert": initial },
+                UpdateOptions::builder().upsert(true).build(),
+            )
+            .await?;
+
+        for (period, field, key) in &periods {
+            let mut filter = doc! { "_id": ROLLOVER_STATE_ID };
+            filter.insert(*period, doc! { "$ne": key });
+            let mut set = Document::new();
+            set.insert(*period, key);
+            let switched = state.update_one(filter, doc! { "$set": set }, None).await?;
+
+            if switched.modified_count > 0 {
+                let mut filter = Document::new();
+                filter.insert(*field, doc! { "$ne": 0 });
+                let mut reset = Document::new();
+                reset.insert(*field, 0);
+                let result = self
+                    .db
+                    .collection::<Document>("vods")
+                    .update_many(filter, doc! { "$set": reset }, None)
+                    .await?;
+                println!("🔄 点击统计进入新区间 {}，已重置 {} 个视频的 {}", key, result.modified_count, field);
+            }
+        }
+
+        Ok(())
+    }
+}
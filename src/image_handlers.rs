@@ -1,13 +1,26 @@
 use crate::dto::ApiResponse;
 use crate::dto::CreateProcessingJobRequest;
-use crate::jwt_auth::AuthenticatedUser;
-use crate::models::Image;
+use crate::jwt_auth::{AuthenticatedUser, OptionalAuthenticatedUser};
+use crate::models::{IMAGE_REVIEW_APPROVED, IMAGE_REVIEW_PENDING, Image, ImageLike};
 use crate::processing_service::ProcessingService;
+use crate::site_data::SiteDataManager;
 use actix_web::{HttpResponse, Result, web};
 use futures::stream::TryStreamExt;
 use mongodb::Database;
-use mongodb::bson::{doc, oid::ObjectId};
+use mongodb::bson::{DateTime, Document, doc, oid::ObjectId};
+use mongodb::options::FindOneOptions;
 use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+pub const IMAGE_COLLECTION: &str = "images";
+const IMAGE_LIKE_COLLECTION: &str = "image_likes";
+// 前台列表每页图集数
+pub const GALLERIES_PER_PAGE: u64 = 24;
+
+/// 前台可见的图集：处理完成并且审核通过的
+pub fn published_gallery_filter() -> Document {
+    doc! { "status": "published", "review_status": IMAGE_REVIEW_APPROVED }
+}
 
 // 图集投稿请求数据
 #[derive(Debug, Serialize, Deserialize)]
@@ -83,6 +96,12 @@ pub async fn submit_image(
         language: submission_data.language.clone(),
         artists: submission_data.artists.clone(),
         need_vip: submission_data.need_vip,
+        status: "processing".to_string(),
+        review_status: IMAGE_REVIEW_PENDING.to_string(),
+        review_reason: None,
+        reviewed_at: None,
+        series_id: None,
+        chapter_no: None,
         created_at: now,
         updated_at: now,
     };
@@ -90,77 +109,30 @@ pub async fn submit_image(
     // 保存图集记录到数据库
     let collection = db.collection::<Image>("images");
     match collection.insert_one(&image, None).await {
-        Ok(_) => {
-            // 创建处理任务
-            let processing_request = CreateProcessingJobRequest {
-                job_type: "archive-process".to_string(),
-                file_id: submission_data.upload_id.clone(),
-                parameters: serde_json::json!({
-                    "image_id": image_id.to_hex(),
-                    "title": submission_data.title,
-                    "en_title": submission_data.en_title,
-                    "description": submission_data.description,
-                    "category": submission_data.category,
-                    "language": submission_data.language,
-                    "artists": submission_data.artists,
-                    "tags": submission_data.tags,
-                    "need_vip": submission_data.need_vip,
-                    "uploader": user.user.id.unwrap_or_else(|| ObjectId::new()).to_hex(),
-                }),
-                webhook_url: Some(format!(
-                    "{}/api/webhook/image-processing",
-                    std::env::var("HOST").unwrap_or_else(|_| "http://localhost:8080".to_string())
-                )),
-                webhook_secret: Some(
-                    std::env::var("WEBHOOK_SECRET")
-                        .unwrap_or_else(|_| "your-webhook-secret".to_string()),
-                ),
-                cms_id: "maccms".to_string(),
-            };
-
-            let processing_collection =
-                db.collection::<crate::models::ProcessingJob>("processing_jobs");
-            let batch_processing_collection =
-                db.collection::<crate::models::BatchProcessingJob>("batch_processing_jobs");
-            let processing_servers_collection =
-                db.collection::<crate::models::ProcessingServerConfig>("processing_servers");
-            let webhook_notifications_collection =
-                db.collection::<crate::models::WebhookNotification>("webhook_notifications");
-            let processing_service = ProcessingService::new(
-                processing_collection,
-                batch_processing_collection,
-                processing_servers_collection,
-                webhook_notifications_collection,
-            );
-
-            match processing_service
-                .create_processing_job(processing_request)
-                .await
-            {
-                Ok(processing_response) => {
-                    let response_data = ImageSubmissionResponse {
-                        image_id: image_id.to_hex(),
-                        title: submission_data.title,
-                        status: "processing".to_string(),
-                        message: "投稿成功，压缩包正在处理中".to_string(),
-                    };
-
-                    Ok(HttpResponse::Ok().json(ApiResponse::success(response_data)))
-                }
-                Err(e) => {
-                    // 处理任务创建失败，但图集记录已保存
-                    eprintln!("创建处理任务失败: {}", e);
-                    let response_data = ImageSubmissionResponse {
-                        image_id: image_id.to_hex(),
-                        title: submission_data.title,
-                        status: "pending".to_string(),
-                        message: "投稿成功，但处理任务创建失败，请联系管理员".to_string(),
-                    };
-
-                    Ok(HttpResponse::Ok().json(ApiResponse::success(response_data)))
-                }
+        Ok(_) => match create_archive_job(&db, &image, &submission_data.upload_id).await {
+            Ok(()) => {
+                let response_data = ImageSubmissionResponse {
+                    image_id: image_id.to_hex(),
+                    title: submission_data.title,
+                    status: "processing".to_string(),
+                    message: "投稿成功，压缩包正在处理中，审核通过后公开".to_string(),
+                };
+
+                Ok(HttpResponse::Ok().json(ApiResponse::success(response_data)))
             }
-        }
+            Err(e) => {
+                // 处理任务创建失败，但图集记录已保存
+                eprintln!("创建处理任务失败: {}", e);
+                let response_data = ImageSubmissionResponse {
+                    image_id: image_id.to_hex(),
+                    title: submission_data.title,
+                    status: "pending".to_string(),
+                    message: "投稿成功，但处理任务创建失败，请联系管理员".to_string(),
+                };
+
+                Ok(HttpResponse::Ok().json(ApiResponse::success(response_data)))
+            }
+        },
         Err(e) => {
             eprintln!("保存图集记录失败: {}", e);
             Ok(HttpResponse::InternalServerError()
@@ -169,6 +141,48 @@ pub async fn submit_image(
     }
 }
 
+/// 为已保存的图集创建 archive-process 处理任务，处理完成后回调 /api/webhook/image-processing
+pub async fn create_archive_job(db: &Database, image: &Image, file_id: &str) -> Result<(), String> {
+    let image_id = image.id.ok_or("图集ID缺失")?;
+    let processing_request = CreateProcessingJobRequest {
+        job_type: "archive-process".to_string(),
+        file_id: file_id.to_string(),
+        parameters: serde_json::json!({
+            "image_id": image_id.to_hex(),
+            "title": image.title,
+            "en_title": image.en_title,
+            "description": image.description,
+            "category": image.category,
+            "language": image.language,
+            "artists": image.artists,
+            "tags": image.tags,
+            "need_vip": image.need_vip,
+            "uploader": image.uploader.to_hex(),
+            "series_id": image.series_id.map(|id| id.to_hex()),
+            "chapter_no": image.chapter_no,
+        }),
+        webhook_url: Some(format!(
+            "{}/api/webhook/image-processing",
+            std::env::var("HOST").unwrap_or_else(|_| "http://localhost:8080".to_string())
+        )),
+        webhook_secret: Some(
+            std::env::var("WEBHOOK_SECRET").unwrap_or_else(|_| "your-webhook-secret".to_string()),
+        ),
+        cms_id: "maccms".to_string(),
+    };
+
+    let processing_service = ProcessingService::new(
+        db.collection::<crate::models::ProcessingJob>("processing_jobs"),
+        db.collection::<crate::models::BatchProcessingJob>("batch_processing_jobs"),
+        db.collection::<crate::models::ProcessingServerConfig>("processing_servers"),
+        db.collection::<crate::models::WebhookNotification>("webhook_notifications"),
+    );
+    processing_service
+        .create_processing_job(processing_request)
+        .await
+        .map(|_| ())
+}
+
 // 获取用户的图集列表
 pub async fn get_user_images(
     user: AuthenticatedUser,
@@ -193,6 +207,11 @@ pub async fn get_user_images(
         filter.insert("category", category);
     }
 
+    // 添加审核状态筛选
+    if let Some(review_status) = &query.review_status {
+        filter.insert("review_status", review_status);
+    }
+
     // 设置分页
     let page = query.page.unwrap_or(1);
     let limit = query.limit.unwrap_or(20);
@@ -247,6 +266,7 @@ pub struct ImageListQuery {
     pub limit: Option<i64>,
     pub status: Option<String>,
     pub category: Option<String>,
+    pub review_status: Option<String>,
 }
 
 // 图集列表响应数据
@@ -361,8 +381,12 @@ pub async fn update_image(
         set_doc.insert("need_vip", need_vip);
     }
 
+    // 内容有修改时重新进入审核
     if !set_doc.is_empty() {
+        set_doc.insert("review_status", IMAGE_REVIEW_PENDING);
+        set_doc.insert("updated_at", DateTime::now());
         updates.insert("$set", set_doc);
+        updates.insert("$unset", doc! { "review_reason": "", "reviewed_at": "" });
     }
 
     if updates.is_empty() {
@@ -380,7 +404,9 @@ pub async fn update_image(
                     .json(ApiResponse::<()>::error("图集不存在".to_string())));
             }
 
-            Ok(HttpResponse::Ok().json(ApiResponse::success("更新成功")))
+            // 重新进入审核，前台搜索不再展示
+            crate::search_index::refresh_entries(&db, "gallery", &[image_id]).await;
+            Ok(HttpResponse::Ok().json(ApiResponse::success("更新成功，等待重新审核")))
         }
         Err(e) => {
             eprintln!("更新图集失败: {}", e);
@@ -451,6 +477,16 @@ pub async fn delete_image(
                     .json(ApiResponse::<()>::error("图集不存在".to_string())));
             }
 
+            // 清理该图集的点赞记录
+            if let Err(e) = db
+                .collection::<ImageLike>(IMAGE_LIKE_COLLECTION)
+                .delete_many(doc! { "image_id": image_id }, None)
+                .await
+            {
+                eprintln!("删除图集点赞记录失败: {}", e);
+            }
+            crate::search_index::refresh_entries(&db, "gallery", &[image_id]).await;
+
             Ok(HttpResponse::Ok().json(ApiResponse::success("删除成功")))
         }
         Err(e) => {
@@ -461,6 +497,19 @@ pub async fn delete_image(
     }
 }
 
+/// 删除图集及其点赞记录和搜索索引条目，返回删除的图集数量
+pub async fn delete_galleries(db: &Database, ids: &[ObjectId]) -> mongodb::error::Result<u64> {
+    let result = db
+        .collection::<Image>(IMAGE_COLLECTION)
+        .delete_many(doc! { "_id": { "$in": ids } }, None)
+        .await?;
+    db.collection::<ImageLike>(IMAGE_LIKE_COLLECTION)
+        .delete_many(doc! { "image_id": { "$in": ids } }, None)
+        .await?;
+    crate::search_index::refresh_entries(db, "gallery", ids).await;
+    Ok(result.deleted_count)
+}
+
 // 处理图集处理完成的webhook
 pub async fn handle_image_processing_webhook(
     db: web::Data<Database>,
@@ -572,7 +621,12 @@ pub async fn handle_image_processing_webhook(
                 .update_one(mongodb::bson::doc! { "_id": image_id }, update_doc, None)
                 .await
             {
-                Ok(_) => Ok(HttpResponse::Ok().json(ApiResponse::success("图集处理完成"))),
+                Ok(_) => {
+                    // 处理完成前已审核通过的章节，在此为连载补上封面
+                    crate::series_handlers::fill_series_covers(&db, &[image_id]).await;
+                    crate::search_index::refresh_entries(&db, "gallery", &[image_id]).await;
+                    Ok(HttpResponse::Ok().json(ApiResponse::success("图集处理完成")))
+                }
                 Err(e) => {
                     eprintln!("更新图集失败: {}", e);
                     Ok(HttpResponse::InternalServerError()
@@ -612,3 +666,159 @@ pub async fn handle_image_processing_webhook(
         }
     }
 }
+
+// 读取图集当前点赞数
+async fn load_image_likes(db: &Database, image_id: ObjectId) -> i32 {
+    let options = FindOneOptions::builder().projection(doc! { "likes": 1 }).build();
+    match db
+        .collection::<Document>(IMAGE_COLLECTION)
+        .find_one(doc! { "_id": image_id }, options)
+        .await
+    {
+        Ok(Some(doc)) => doc.get_i32("likes").unwrap_or(0),
+        _ => 0,
+    }
+}
+
+// 获取阅读器页面列表，VIP 图集需要满足 need_vip 要求才返回图片地址
+pub async fn get_gallery_pages(
+    db: web::Data<Database>,
+    site_data_manager: web::Data<SiteDataManager>,
+    path: web::Path<String>,
+    user: OptionalAuthenticatedUser,
+) -> Result<HttpResponse> {
+    if !crate::web_handlers::gallery_enabled(&site_data_manager).await {
+        return Ok(HttpResponse::NotFound()
+            .json(json!({"success": false, "has_access": false, "message": "图集功能未开启"})));
+    }
+    let Ok(image_id) = ObjectId::parse_str(path.into_inner()) else {
+        return Ok(HttpResponse::BadRequest()
+            .json(json!({"success": false, "has_access": false, "message": "无效的图集ID"})));
+    };
+
+    let mut filter = published_gallery_filter();
+    filter.insert("_id", image_id);
+    let image = match db
+        .collection::<Image>(IMAGE_COLLECTION)
+        .find_one(filter, None)
+        .await
+    {
+        Ok(Some(image)) => image,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound()
+                .json(json!({"success": false, "has_access": false, "message": "图集不存在"})));
+        }
+        Err(e) => {
+            eprintln!("查询图集失败: {}", e);
+            return Ok(HttpResponse::InternalServerError()
+                .json(json!({"success": false, "has_access": false, "message": "服务器错误"})));
+        }
+    };
+
+    let (has_access, message) =
+        crate::web_handlers::check_media_vip_access(&db, user.0.as_ref(), image.need_vip).await;
+    if !has_access {
+        return Ok(HttpResponse::Ok().json(json!({
+            "success": true,
+            "has_access": false,
+            "need_vip": image.need_vip,
+            "message": message
+        })));
+    }
+
+    Ok(HttpResponse::Ok().json(json!({
+        "success": true,
+        "has_access": true,
+        "message": message,
+        "pages": image.images
+    })))
+}
+
+// 获取当前用户对图集的点赞状态
+pub async fn get_image_like_status(
+    user: AuthenticatedUser,
+    db: web::Data<Database>,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    let Some(user_id) = user.user.id else {
+        return Ok(HttpResponse::Unauthorized().json(json!({"success": false, "message": "用户信息无效"})));
+    };
+    let Ok(image_id) = ObjectId::parse_str(path.into_inner()) else {
+        return Ok(HttpResponse::BadRequest().json(json!({"success": false, "message": "无效的图集ID"})));
+    };
+
+    let liked = match db
+        .collection::<ImageLike>(IMAGE_LIKE_COLLECTION)
+        .count_documents(doc! { "image_id": image_id, "user_id": user_id }, None)
+        .await
+    {
+        Ok(count) => count > 0,
+        Err(e) => {
+            eprintln!("查询点赞状态失败: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(json!({"success": false, "message": "查询失败"})));
+        }
+    };
+
+    Ok(HttpResponse::Ok().json(json!({
+        "success": true,
+        "liked": liked,
+        "likes": load_image_likes(&db, image_id).await
+    })))
+}
+
+// 点赞或取消点赞图集，每个用户对每个图集只记一次
+pub async fn toggle_image_like(
+    user: AuthenticatedUser,
+    db: web::Data<Database>,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    let Some(user_id) = user.user.id else {
+        return Ok(HttpResponse::Unauthorized().json(json!({"success": false, "message": "用户信息无效"})));
+    };
+    let Ok(image_id) = ObjectId::parse_str(path.into_inner()) else {
+        return Ok(HttpResponse::BadRequest().json(json!({"success": false, "message": "无效的图集ID"})));
+    };
+
+    let mut filter = published_gallery_filter();
+    filter.insert("_id", image_id);
+    let images = db.collection::<Image>(IMAGE_COLLECTION);
+    match images.count_documents(filter, None).await {
+        Ok(0) => {
+            return Ok(HttpResponse::NotFound().json(json!({"success": false, "message": "图集不存在"})));
+        }
+        Ok(_) => {}
+        Err(e) => {
+            eprintln!("查询图集失败: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(json!({"success": false, "message": "操作失败"})));
+        }
+    }
+
+    // 依赖 image_id + user_id 唯一索引，插入重复即表示已点赞，改为取消
+    let likes = db.collection::<ImageLike>(IMAGE_LIKE_COLLECTION);
+    let liked = match crate::db::toggle_unique(&likes, doc! { "image_id": image_id, "user_id": user_id }).await {
+        Ok(Some(liked)) => liked,
+        // 已被并发请求取消
+        Ok(None) => {
+            return Ok(HttpResponse::Conflict().json(json!({"success": false, "message": "操作过于频繁"})));
+        }
+        Err(e) => {
+            eprintln!("切换点赞失败: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(json!({"success": false, "message": "操作失败"})));
+        }
+    };
+
+    let delta = if liked { 1 } else { -1 };
+    if let Err(e) = images
+        .update_one(doc! { "_id": image_id }, doc! { "$inc": { "likes": delta } }, None)
+        .await
+    {
+        eprintln!("更新点赞数失败: {}", e);
+    }
+
+    Ok(HttpResponse::Ok().json(json!({
+        "success": true,
+        "liked": liked,
+        "message": if liked { "点赞成功" } else { "已取消点赞" },
+        "likes": load_image_likes(&db, image_id).await
+    })))
+}
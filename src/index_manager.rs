@@ -130,7 +130,423 @@ impl IndexManager {
                 sparse: None,
                 background: Some(true),
             },
+            IndexInfo {
+                collection: "vods".to_string(),
+                keys: {
+                    let mut keys = HashMap::new();
+                    keys.insert("vod_id".to_string(), 1);
+                    keys
+                },
+                name: "vod_id_1".to_string(),
+                unique: Some(true),
+                sparse: Some(true),
+                background: Some(true),
+            },
+            IndexInfo {
+                collection: "vods".to_string(),
+                keys: {
+                    let mut keys = HashMap::new();
+                    keys.insert("vod_hits_day".to_string(), -1);
+                    keys
+                },
+                name: "vod_hits_day_-1".to_string(),
+                unique: None,
+                sparse: None,
+                background: Some(true),
+            },
+            IndexInfo {
+                collection: "vods".to_string(),
+                keys: {
+                    let mut keys = HashMap::new();
+                    keys.insert("vod_hits_week".to_string(), -1);
+                    keys
+                },
+                name: "vod_hits_week_-1".to_string(),
+                unique: None,
+                sparse: None,
+                background: Some(true),
+            },
+            IndexInfo {
+                collection: "vods".to_string(),
+                keys: {
+                    let mut keys = HashMap::new();
+                    keys.insert("vod_hits_month".to_string(), -1);
+                    keys
+                },
+                name: "vod_hits_month_-1".to_string(),
+                unique: None,
+                sparse: None,
+                background: Some(true),
+            },
+            IndexInfo {
+                collection: "vods".to_string(),
+                keys: {
+                    let mut keys = HashMap::new();
+                    keys.insert("vod_hits".to_string(), -1);
+                    keys
+                },
+                name: "vod_hits_-1".to_string(),
+                unique: None,
+                sparse: None,
+                background: Some(true),
+            },
             
+            // user_histories 集合索引
+            IndexInfo {
+                collection: "user_histories".to_string(),
+                keys: {
+                    let mut keys = HashMap::new();
+                    keys.insert("user_id".to_string(), 1);
+                    keys.insert("media_type".to_string(), 1);
+                    keys.insert("media_id".to_string(), 1);
+                    keys
+                },
+                name: "user_id_1_media_type_1_media_id_1".to_string(),
+                unique: Some(true),
+                sparse: None,
+                background: Some(true),
+            },
+            IndexInfo {
+                collection: "user_histories".to_string(),
+                keys: {
+                    let mut keys = HashMap::new();
+                    keys.insert("user_id".to_string(), 1);
+                    keys.insert("updated_at".to_string(), -1);
+                    keys
+                },
+                name: "user_id_1_updated_at_-1".to_string(),
+                unique: None,
+                sparse: None,
+                background: Some(true),
+            },
+
+            // user_favorites 集合索引
+            IndexInfo {
+                collection: "user_favorites".to_string(),
+                keys: {
+                    let mut keys = HashMap::new();
+                    keys.insert("user_id".to_string(), 1);
+                    keys.insert("media_type".to_string(), 1);
+                    keys.insert("media_id".to_string(), 1);
+                    keys
+                },
+                name: "user_id_1_media_type_1_media_id_1".to_string(),
+                unique: Some(true),
+                sparse: None,
+                background: Some(true),
+            },
+            IndexInfo {
+                collection: "user_favorites".to_string(),
+                keys: {
+                    let mut keys = HashMap::new();
+                    keys.insert("media_type".to_string(), 1);
+                    keys.insert("media_id".to_string(), 1);
+                    keys
+                },
+                name: "media_type_1_media_id_1".to_string(),
+                unique: None,
+                sparse: None,
+                background: Some(true),
+            },
+
+            // vod_ratings 集合索引
+            IndexInfo {
+                collection: "vod_ratings".to_string(),
+                keys: {
+                    let mut keys = HashMap::new();
+                    keys.insert("user_id".to_string(), 1);
+                    keys.insert("vod_id".to_string(), 1);
+                    keys
+                },
+                name: "user_id_1_vod_id_1".to_string(),
+                unique: Some(true),
+                sparse: None,
+                background: Some(true),
+            },
+
+            // comments 集合索引
+            IndexInfo {
+                collection: "comments".to_string(),
+                keys: {
+                    let mut keys = HashMap::new();
+                    keys.insert("media_type".to_string(), 1);
+                    keys.insert("media_id".to_string(), 1);
+                    keys.insert("status".to_string(), 1);
+                    keys
+                },
+                name: "media_type_1_media_id_1_status_1".to_string(),
+                unique: None,
+                sparse: None,
+                background: Some(true),
+            },
+            IndexInfo {
+                collection: "comments".to_string(),
+                keys: {
+                    let mut keys = HashMap::new();
+                    keys.insert("status".to_string(), 1);
+                    keys.insert("created_at".to_string(), -1);
+                    keys
+                },
+                name: "status_1_created_at_-1".to_string(),
+                unique: None,
+                sparse: None,
+                background: Some(true),
+            },
+            IndexInfo {
+                collection: "comments".to_string(),
+                keys: {
+                    let mut keys = HashMap::new();
+                    keys.insert("parent_id".to_string(), 1);
+                    keys
+                },
+                name: "parent_id_1".to_string(),
+                unique: None,
+                sparse: None,
+                background: Some(true),
+            },
+
+            // comment_likes 集合索引
+            IndexInfo {
+                collection: "comment_likes".to_string(),
+                keys: {
+                    let mut keys = HashMap::new();
+                    keys.insert("comment_id".to_string(), 1);
+                    keys.insert("user_id".to_string(), 1);
+                    keys
+                },
+                name: "comment_id_1_user_id_1".to_string(),
+                unique: Some(true),
+                sparse: None,
+                background: Some(true),
+            },
+
+            // audios 集合索引
+            IndexInfo {
+                collection: "audios".to_string(),
+                keys: {
+                    let mut keys = HashMap::new();
+                    keys.insert("status".to_string(), 1);
+                    keys.insert("created_at".to_string(), -1);
+                    keys
+                },
+                name: "status_1_created_at_-1".to_string(),
+                unique: None,
+                sparse: None,
+                background: Some(true),
+            },
+            IndexInfo {
+                collection: "audios".to_string(),
+                keys: {
+                    let mut keys = HashMap::new();
+                    keys.insert("category".to_string(), 1);
+                    keys.insert("created_at".to_string(), -1);
+                    keys
+                },
+                name: "category_1_created_at_-1".to_string(),
+                unique: None,
+                sparse: None,
+                background: Some(true),
+            },
+            IndexInfo {
+                collection: "audios".to_string(),
+                keys: {
+                    let mut keys = HashMap::new();
+                    keys.insert("uploader".to_string(), 1);
+                    keys.insert("created_at".to_string(), -1);
+                    keys
+                },
+                name: "uploader_1_created_at_-1".to_string(),
+                unique: None,
+                sparse: None,
+                background: Some(true),
+            },
+
+            // images 集合索引
+            IndexInfo {
+                collection: "images".to_string(),
+                keys: {
+                    let mut keys = HashMap::new();
+                    keys.insert("status".to_string(), 1);
+                    keys.insert("created_at".to_string(), -1);
+                    keys
+                },
+                name: "status_1_created_at_-1".to_string(),
+                unique: None,
+                sparse: None,
+                background: Some(true),
+            },
+            IndexInfo {
+                collection: "images".to_string(),
+                keys: {
+                    let mut keys = HashMap::new();
+                    keys.insert("category".to_string(), 1);
+                    keys.insert("created_at".to_string(), -1);
+                    keys
+                },
+                name: "category_1_created_at_-1".to_string(),
+                unique: None,
+                sparse: None,
+                background: Some(true),
+            },
+            IndexInfo {
+                collection: "images".to_string(),
+                keys: {
+                    let mut keys = HashMap::new();
+                    keys.insert("tags".to_string(), 1);
+                    keys
+                },
+                name: "tags_1".to_string(),
+                unique: None,
+                sparse: None,
+                background: Some(true),
+            },
+            IndexInfo {
+                collection: "images".to_string(),
+                keys: {
+                    let mut keys = HashMap::new();
+                    keys.insert("review_status".to_string(), 1);
+                    keys.insert("created_at".to_string(), -1);
+                    keys
+                },
+                name: "review_status_1_created_at_-1".to_string(),
+                unique: None,
+                sparse: None,
+                background: Some(true),
+            },
+            IndexInfo {
+                collection: "images".to_string(),
+                keys: {
+                    let mut keys = HashMap::new();
+                    keys.insert("uploader".to_string(), 1);
+                    keys.insert("created_at".to_string(), -1);
+                    keys
+                },
+                name: "uploader_1_created_at_-1".to_string(),
+                unique: None,
+                sparse: None,
+                background: Some(true),
+            },
+
+            // image_likes 集合索引
+            IndexInfo {
+                collection: "image_likes".to_string(),
+                keys: {
+                    let mut keys = HashMap::new();
+                    keys.insert("image_id".to_string(), 1);
+                    keys.insert("user_id".to_string(), 1);
+                    keys
+                },
+                name: "image_id_1_user_id_1".to_string(),
+                unique: Some(true),
+                sparse: None,
+                background: Some(true),
+            },
+            IndexInfo {
+                collection: "images".to_string(),
+                keys: {
+                    let mut keys = HashMap::new();
+                    keys.insert("series_id".to_string(), 1);
+                    keys.insert("chapter_no".to_string(), 1);
+                    keys
+                },
+                name: "series_id_1_chapter_no_1".to_string(),
+                unique: None,
+                sparse: None,
+                background: Some(true),
+            },
+
+            // gallery_series 集合索引
+            IndexInfo {
+                collection: "gallery_series".to_string(),
+                keys: {
+                    let mut keys = HashMap::new();
+                    keys.insert("uploader".to_string(), 1);
+                    keys
+                },
+                name: "uploader_1".to_string(),
+                unique: None,
+                sparse: None,
+                background: Some(true),
+            },
+            IndexInfo {
+                collection: "gallery_series".to_string(),
+                keys: {
+                    let mut keys = HashMap::new();
+                    keys.insert("updated_at".to_string(), -1);
+                    keys
+                },
+                name: "updated_at_-1".to_string(),
+                unique: None,
+                sparse: None,
+                background: Some(true),
+            },
+
+            // series_follows 集合索引
+            IndexInfo {
+                collection: "series_follows".to_string(),
+                keys: {
+                    let mut keys = HashMap::new();
+                    keys.insert("series_id".to_string(), 1);
+                    keys.insert("user_id".to_string(), 1);
+                    keys
+                },
+                name: "series_id_1_user_id_1".to_string(),
+                unique: Some(true),
+                sparse: None,
+                background: Some(true),
+            },
+            IndexInfo {
+                collection: "series_follows".to_string(),
+                keys: {
+                    let mut keys = HashMap::new();
+                    keys.insert("user_id".to_string(), 1);
+                    keys.insert("created_at".to_string(), -1);
+                    keys
+                },
+                name: "user_id_1_created_at_-1".to_string(),
+                unique: None,
+                sparse: None,
+                background: Some(true),
+            },
+
+            // search_entries 集合索引（多键索引，按分词检索）
+            IndexInfo {
+                collection: "search_entries".to_string(),
+                keys: {
+                    let mut keys = HashMap::new();
+                    keys.insert("terms".to_string(), 1);
+                    keys
+                },
+                name: "terms_1".to_string(),
+                unique: None,
+                sparse: None,
+                background: Some(true),
+            },
+            IndexInfo {
+                collection: "search_entries".to_string(),
+                keys: {
+                    let mut keys = HashMap::new();
+                    keys.insert("kind".to_string(), 1);
+                    keys
+                },
+                name: "kind_1".to_string(),
+                unique: None,
+                sparse: None,
+                background: Some(true),
+            },
+            IndexInfo {
+                collection: "search_entries".to_string(),
+                keys: {
+                    let mut keys = HashMap::new();
+                    keys.insert("indexed_at".to_string(), -1);
+                    keys
+                },
+                name: "indexed_at_-1".to_string(),
+                unique: None,
+                sparse: None,
+                background: Some(true),
+            },
+
             // types 集合索引
             IndexInfo {
                 collection: "types".to_string(),
@@ -212,6 +628,160 @@ impl IndexManager {
                 background: Some(true),
             },
             
+            IndexInfo {
+                collection: "vods".to_string(),
+                keys: {
+                    let mut keys = HashMap::new();
+                    keys.insert("vod_name_key".to_string(), 1);
+                    keys
+                },
+                name: "vod_name_key_1".to_string(),
+                unique: None,
+                sparse: Some(true),
+                background: Some(true),
+            },
+            IndexInfo {
+                collection: "vods".to_string(),
+                keys: {
+                    let mut keys = HashMap::new();
+                    keys.insert("vod_douban_id".to_string(), 1);
+                    keys
+                },
+                name: "vod_douban_id_1".to_string(),
+                unique: None,
+                sparse: Some(true),
+                background: Some(true),
+            },
+
+            // arts 集合索引
+            IndexInfo {
+                collection: "arts".to_string(),
+                keys: {
+                    let mut keys = HashMap::new();
+                    keys.insert("art_name".to_string(), 1);
+                    keys
+                },
+                name: "art_name_1".to_string(),
+                unique: None,
+                sparse: None,
+                background: Some(true),
+            },
+            IndexInfo {
+                collection: "arts".to_string(),
+                keys: {
+                    let mut keys = HashMap::new();
+                    keys.insert("type_id".to_string(), 1);
+                    keys
+                },
+                name: "type_id_1".to_string(),
+                unique: None,
+                sparse: None,
+                background: Some(true),
+            },
+            IndexInfo {
+                collection: "arts".to_string(),
+                keys: {
+                    let mut keys = HashMap::new();
+                    keys.insert("art_pubdate".to_string(), -1);
+                    keys
+                },
+                name: "art_pubdate_-1".to_string(),
+                unique: None,
+                sparse: None,
+                background: Some(true),
+            },
+
+            // vod_merge_logs 集合索引
+            IndexInfo {
+                collection: "vod_merge_logs".to_string(),
+                keys: {
+                    let mut keys = HashMap::new();
+                    keys.insert("task_id".to_string(), 1);
+                    keys
+                },
+                name: "task_id_1".to_string(),
+                unique: None,
+                sparse: None,
+                background: Some(true),
+            },
+
+            // collect_tasks 集合索引
+            IndexInfo {
+                collection: "collect_tasks".to_string(),
+                keys: {
+                    let mut keys = HashMap::new();
+                    keys.insert("task_id".to_string(), 1);
+                    keys
+                },
+                name: "task_id_1".to_string(),
+                unique: Some(true),
+                sparse: Some(true),
+                background: Some(true),
+            },
+            IndexInfo {
+                collection: "collect_tasks".to_string(),
+                keys: {
+                    let mut keys = HashMap::new();
+                    keys.insert("created_at".to_string(), -1);
+                    keys
+                },
+                name: "created_at_-1".to_string(),
+                unique: None,
+                sparse: None,
+                background: Some(true),
+            },
+
+            // jobs 集合索引
+            IndexInfo {
+                collection: "jobs".to_string(),
+                keys: {
+                    let mut keys = HashMap::new();
+                    keys.insert("job_id".to_string(), 1);
+                    keys
+                },
+                name: "job_id_1".to_string(),
+                unique: Some(true),
+                sparse: None,
+                background: Some(true),
+            },
+            IndexInfo {
+                collection: "jobs".to_string(),
+                keys: {
+                    let mut keys = HashMap::new();
+                    keys.insert("status".to_string(), 1);
+                    keys.insert("job_type".to_string(), 1);
+                    keys
+                },
+                name: "status_1_job_type_1".to_string(),
+                unique: None,
+                sparse: None,
+                background: Some(true),
+            },
+            IndexInfo {
+                collection: "jobs".to_string(),
+                keys: {
+                    let mut keys = HashMap::new();
+                    keys.insert("created_at".to_string(), -1);
+                    keys
+                },
+                name: "created_at_-1".to_string(),
+                unique: None,
+                sparse: None,
+                background: Some(true),
+            },
+            IndexInfo {
+                collection: "jobs".to_string(),
+                keys: {
+                    let mut keys = HashMap::new();
+                    keys.insert("finished_at".to_string(), 1);
+                    keys
+                },
+                name: "finished_at_1".to_string(),
+                unique: None,
+                sparse: None,
+                background: Some(true),
+            },
+
             // configs 集合索引
             IndexInfo {
                 collection: "configs".to_string(),
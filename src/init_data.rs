@@ -96,6 +96,26 @@ pub async fn init_website_config(db: &Database) -> Result<(), Box<dyn std::error
             config_sort: 8,
             updated_at: DateTime::now(),
         },
+        Config {
+            id: None,
+            config_key: "comment_audit".to_string(),
+            config_value: "开启".to_string(),
+            config_desc: Some("评论是否需要审核后显示".to_string()),
+            config_type: "text".to_string(),
+            config_group: Some("评论设置".to_string()),
+            config_sort: 9,
+            updated_at: DateTime::now(),
+        },
+        Config {
+            id: None,
+            config_key: "comment_sensitive_words".to_string(),
+            config_value: "".to_string(),
+            config_desc: Some("评论敏感词，多个用逗号或换行分隔".to_string()),
+            config_type: "textarea".to_string(),
+            config_group: Some("评论设置".to_string()),
+            config_sort: 10,
+            updated_at: DateTime::now(),
+        },
     ];
 
     let mut created_count = 0;
@@ -945,6 +965,10 @@ pub async fn init_test_videos(db: &Database) -> Result<(), Box<dyn std::error::E
     let videos = vec![
         Vod {
             id: None,
+            vod_id: None, // 启动时由 db::backfill_vod_ids 补齐
+            vod_name_key: None, // 启动时由 db::backfill_vod_name_keys 补齐
+            vod_douban_id: None,
+            vod_pic_source: None,
             vod_name: "复仇者联盟4：终局之战".to_string(),
             type_id: 11, // 动作片
             vod_status: 1,
@@ -965,6 +989,8 @@ pub async fn init_test_videos(db: &Database) -> Result<(), Box<dyn std::error::E
             vod_hits_week: Some(0),
             vod_hits_month: Some(0),
             vod_score: Some("9.2".to_string()),
+            vod_score_all: Some(0),
+            vod_score_num: Some(0),
             need_vip: 0,
             vod_play_urls: vec![PlaySource {
                 source_name: "高清播放".to_string(),
@@ -976,6 +1002,10 @@ pub async fn init_test_videos(db: &Database) -> Result<(), Box<dyn std::error::E
         },
         Vod {
             id: None,
+            vod_id: None, // 启动时由 db::backfill_vod_ids 补齐
+            vod_name_key: None, // 启动时由 db::backfill_vod_name_keys 补齐
+            vod_douban_id: None,
+            vod_pic_source: None,
             vod_name: "流浪地球".to_string(),
             type_id: 13, // 科幻片
             vod_status: 1,
@@ -996,6 +1026,8 @@ pub async fn init_test_videos(db: &Database) -> Result<(), Box<dyn std::error::E
             vod_hits_week: Some(0),
             vod_hits_month: Some(0),
             vod_score: Some("8.8".to_string()),
+            vod_score_all: Some(0),
+            vod_score_num: Some(0),
             need_vip: 0,
             vod_play_urls: vec![PlaySource {
                 source_name: "高清播放".to_string(),
@@ -1007,6 +1039,10 @@ pub async fn init_test_videos(db: &Database) -> Result<(), Box<dyn std::error::E
         },
         Vod {
             id: None,
+            vod_id: None, // 启动时由 db::backfill_vod_ids 补齐
+            vod_name_key: None, // 启动时由 db::backfill_vod_name_keys 补齐
+            vod_douban_id: None,
+            vod_pic_source: None,
             vod_name: "你好,李焕英".to_string(),
             type_id: 12, // 喜剧片
             vod_status: 1,
@@ -1025,6 +1061,8 @@ pub async fn init_test_videos(db: &Database) -> Result<(), Box<dyn std::error::E
             vod_hits_week: Some(0),
             vod_hits_month: Some(0),
             vod_score: Some("8.5".to_string()),
+            vod_score_all: Some(0),
+            vod_score_num: Some(0),
             need_vip: 0,
             vod_play_urls: vec![PlaySource {
                 source_name: "高清播放".to_string(),
@@ -1147,6 +1185,13 @@ pub async fn init_collection_sources(db: &Database) -> Result<(), Box<dyn std::e
             collect_remove_ad: 1,
             collect_convert_webp: 1,   // 启用webp转换
             collect_download_retry: 3, // 重试3次
+            collect_format: 0,         // 自动识别数据格式
+            collect_match: 0,          // 按名称精确判定重复
+            collect_update_policy: Default::default(), // 默认仅更新播放源和备注
+            collect_cron: "".to_string(),
+            collect_cron_status: 0,    // 默认不参与定时采集
+            collect_last_run: None,
+            collect_next_run: None,
             collect_status: 1,
             created_at: DateTime::now(),
             updated_at: DateTime::now(),
@@ -1167,6 +1212,13 @@ pub async fn init_collection_sources(db: &Database) -> Result<(), Box<dyn std::e
             collect_remove_ad: 1,
             collect_convert_webp: 1,   // 启用webp转换
             collect_download_retry: 3, // 重试3次
+            collect_format: 0,         // 自动识别数据格式
+            collect_match: 0,          // 按名称精确判定重复
+            collect_update_policy: Default::default(), // 默认仅更新播放源和备注
+            collect_cron: "".to_string(),
+            collect_cron_status: 0,    // 默认不参与定时采集
+            collect_last_run: None,
+            collect_next_run: None,
             collect_status: 1,
             created_at: DateTime::now(),
             updated_at: DateTime::now(),
@@ -1200,6 +1252,10 @@ pub async fn init_collection_sources(db: &Database) -> Result<(), Box<dyn std::e
                         "collect_remove_ad": collect.collect_remove_ad,
                         "collect_convert_webp": collect.collect_convert_webp,
                         "collect_download_retry": collect.collect_download_retry,
+                        "collect_format": collect.collect_format,
+                        "collect_match": collect.collect_match,
+                        "collect_cron": &collect.collect_cron,
+                        "collect_cron_status": collect.collect_cron_status,
                         "collect_status": collect.collect_status,
                         "created_at": collect.created_at,
                         "updated_at": collect.updated_at,
@@ -1233,6 +1289,10 @@ pub async fn init_collection_sources(db: &Database) -> Result<(), Box<dyn std::e
                         "collect_remove_ad": collect.collect_remove_ad,
                         "collect_convert_webp": collect.collect_convert_webp,
                         "collect_download_retry": collect.collect_download_retry,
+                        "collect_format": collect.collect_format,
+                        "collect_match": collect.collect_match,
+                        "collect_cron": &collect.collect_cron,
+                        "collect_cron_status": collect.collect_cron_status,
                         "collect_status": collect.collect_status,
                         "created_at": collect.created_at,
                         "updated_at": collect.updated_at,
@@ -1277,6 +1337,7 @@ pub async fn init_bindings(db: &Database) -> Result<(), Box<dyn std::error::Erro
             id: "7a4856e7b6a1e1a2580a9b69cdc7233c_5".to_string(), // 模拟PHP中的绑定格式
             source_flag: "7a4856e7b6a1e1a2580a9b69cdc7233c".to_string(), // 采集源标识
             external_id: "5".to_string(),                         // 外部分类ID
+            external_name: None,
             local_type_id: 6,                                     // 本地分类ID
             local_type_name: "动作片".to_string(),
             created_at: now,
@@ -1286,6 +1347,7 @@ pub async fn init_bindings(db: &Database) -> Result<(), Box<dyn std::error::Erro
             id: "test_api_source_1".to_string(),
             source_flag: "test_api_source".to_string(),
             external_id: "1".to_string(),
+            external_name: None,
             local_type_id: 11,
             local_type_name: "动作片".to_string(),
             created_at: now,
@@ -1295,6 +1357,7 @@ pub async fn init_bindings(db: &Database) -> Result<(), Box<dyn std::error::Erro
             id: "test_api_source_2".to_string(),
             source_flag: "test_api_source".to_string(),
             external_id: "2".to_string(),
+            external_name: None,
             local_type_id: 12,
             local_type_name: "喜剧片".to_string(),
             created_at: now,
@@ -0,0 +1,431 @@
+use crate::leader_lock::instance_id;
+use futures::TryStreamExt;
+use mongodb::bson::{doc, oid::ObjectId, DateTime, Document};
+use mongodb::options::{FindOptions, UpdateOptions};
+use mongodb::{Collection as MongoCollection, Database};
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinHandle;
+use tokio::time::{interval, Duration};
+
+// 任务类型
+pub const JOB_TYPE_COLLECT: &str = "collect";
+pub const JOB_TYPE_BATCH_DELETE_SOURCE: &str = "batch_delete_source";
+pub const JOB_TYPE_VOD_BULK: &str = "vod_bulk";
+
+// 任务状态
+pub const JOB_STATUS_RUNNING: &str = "running";
+pub const JOB_STATUS_COMPLETED: &str = "completed";
+pub const JOB_STATUS_FAILED: &str = "failed";
+pub const JOB_STATUS_STOPPED: &str = "stopped";
+pub const JOB_STATUS_INTERRUPTED: &str = "interrupted";
+
+// 采集任务的触发方式（Job.params.trigger）
+pub const JOB_TRIGGER_MANUAL: &str = "manual";
+pub const JOB_TRIGGER_SCHEDULE: &str = "schedule";
+
+// 运行中的任务每隔该时间写一次心跳
+const HEARTBEAT_SECS: u64 = 30;
+// 超过该时间没有心跳的运行中任务视为所在实例已退出
+const STALE_JOB_SECS: i64 = 120;
+// 失败任务自动重试前的等待时间
+const RETRY_DELAY_SECS: i64 = 60;
+// 已结束任务的保留天数，超过后由后台维护清理
+const FINISHED_JOB_RETENTION_DAYS: i64 = 30;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct JobProgress {
+    pub current: u64, // 当前进度，如采集页码、已处理视频数
+    pub total: u64,   // 总量
+    pub success: u64, // 成功数量
+    pub failed: u64,  // 失败数量
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Job {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub job_id: String,   // 对外任务ID，沿用各功能原有的任务ID
+    pub job_type: String, // collect, batch_delete_source, vod_bulk
+    pub job_name: String,
+    pub status: String, // running, completed, failed, stopped, interrupted
+    #[serde(default)]
+    pub progress: JobProgress,
+    #[serde(default)]
+    pub log: String,
+    #[serde(default)]
+    pub params: Document, // 任务参数，重试时据此重新执行；可恢复的任务在 params.checkpoint 中保存检查点
+    #[serde(default)]
+    pub attempts: i32, // 已执行次数
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: i32, // 失败或中断后最多自动执行的次数
+    #[serde(default)]
+    pub cancel_requested: bool, // 已请求停止，执行中的任务在检查点响应
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instance_id: Option<String>, // 执行任务的实例
+    pub created_at: DateTime,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<DateTime>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<DateTime>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub heartbeat_at: Option<DateTime>,
+    pub updated_at: DateTime,
+}
+
+fn default_max_attempts() -> i32 {
+    1
+}
+
+impl Job {
+    pub fn is_finished(&self) -> bool {
+        self.status != JOB_STATUS_RUNNING
+    }
+
+    // 失败、停止或中断的任务可以重试
+    pub fn is_retryable(&self) -> bool {
+        self.status == JOB_STATUS_FAILED
+            || self.status == JOB_STATUS_STOPPED
+            || self.status == JOB_STATUS_INTERRUPTED
+    }
+}
+
+fn jobs(db: &Database) -> MongoCollection<Job> {
+    db.collection::<Job>("jobs")
+}
+
+/// 开始一个新任务；重新执行已有任务由 retry_job 负责
+pub async fn start_job(
+    db: &Database,
+    job_id: &str,
+    job_type: &str,
+    job_name: &str,
+    params: Document,
+    max_attempts: i32,
+) -> Result<(), mongodb::error::Error> {
+    let now = DateTime::now();
+    let options = UpdateOptions::builder().upsert(true).build();
+
+    jobs(db)
+        .update_one(
+            doc! { "job_id": job_id },
+            doc! {
+                "$set": running_fields(now),
+                "$inc": { "attempts": 1 },
+                "$setOnInsert": {
+                    "job_type": job_type,
+                    "job_name": job_name,
+                    "params": params,
+                    "max_attempts": max_attempts,
+                    "progress": mongodb::bson::to_bson(&JobProgress::default())?,
+                    "created_at": now,
+                }
+            },
+            options,
+        )
+        .await?;
+
+    Ok(())
+}
+
+// 任务开始执行时写入的字段
+fn running_fields(now: DateTime) -> Document {
+    doc! {
+        "status": JOB_STATUS_RUNNING,
+        "log": "任务已启动",
+        "cancel_requested": false,
+        "instance_id": instance_id(),
+        "started_at": now,
+        "finished_at": None::<DateTime>,
+        "heartbeat_at": now,
+        "updated_at": now,
+    }
+}
+
+// 原子地把失败、停止或中断的任务重新标记为运行中并累计执行次数
+// 返回 false 表示任务已不处于可重试状态（已被其他请求或实例重新开始）
+async fn claim_retry(db: &Database, job_id: &str) -> Result<bool, mongodb::error::Error> {
+    let result = jobs(db)
+        .update_one(
+            doc! {
+                "job_id": job_id,
+                "status": { "$in": [JOB_STATUS_FAILED, JOB_STATUS_STOPPED, JOB_STATUS_INTERRUPTED] },
+            },
+            doc! { "$set": running_fields(DateTime::now()), "$inc": { "attempts": 1 } },
+            None,
+        )
+        .await?;
+    Ok(result.matched_count > 0)
+}
+
+/// 更新任务进度
+pub async fn update_job(
+    db: &Database,
+    job_id: &str,
+    progress: &JobProgress,
+    log: &str,
+) -> Result<(), mongodb::error::Error> {
+    let now = DateTime::now();
+    jobs(db)
+        .update_one(
+            doc! { "job_id": job_id },
+            doc! {
+                "$set": {
+                    "progress": mongodb::bson::to_bson(progress)?,
+                    "log": log,
+                    "heartbeat_at": now,
+                    "updated_at": now,
+                }
+            },
+            None,
+        )
+        .await?;
+    Ok(())
+}
+
+/// 保存任务检查点，重试时由各任务从 params.checkpoint 恢复
+pub async fn save_checkpoint(
+    db: &Database,
+    job_id: &str,
+    checkpoint: Document,
+) -> Result<(), mongodb::error::Error> {
+    jobs(db)
+        .update_one(
+            doc! { "job_id": job_id },
+            doc! { "$set": { "params.checkpoint": checkpoint, "updated_at": DateTime::now() } },
+            None,
+        )
+        .await?;
+    Ok(())
+}
+
+/// 结束任务
+pub async fn finish_job(
+    db: &Database,
+    job_id: &str,
+    status: &str,
+    progress: Option<&JobProgress>,
+    log: &str,
+) -> Result<(), mongodb::error::Error> {
+    let now = DateTime::now();
+    let mut update = doc! {
+        "status": status,
+        "log": log,
+        "finished_at": now,
+        "updated_at": now,
+    };
+    if let Some(progress) = progress {
+        update.insert("progress", mongodb::bson::to_bson(progress)?);
+    }
+
+    jobs(db)
+        .update_one(doc! { "job_id": job_id }, doc! { "$set": update }, None)
+        .await?;
+    Ok(())
+}
+
+/// 运行期间定时写心跳，返回的句柄在任务结束时中止
+pub fn spawn_heartbeat(db: &Database, job_id: &str) -> JobHeartbeat {
+    let db = db.clone();
+    let job_id = job_id.to_string();
+    let handle = tokio::spawn(async move {
+        let mut timer = interval(Duration::from_secs(HEARTBEAT_SECS));
+        loop {
+            timer.tick().await;
+            if let Err(e) = jobs(&db)
+                .update_one(
+                    doc! { "job_id": &job_id, "status": JOB_STATUS_RUNNING },
+                    doc! { "$set": { "heartbeat_at": DateTime::now() } },
+                    None,
+                )
+                .await
+            {
+                eprintln!("❌ 更新任务心跳失败 {}: {}", job_id, e);
+            }
+        }
+    });
+    JobHeartbeat { handle }
+}
+
+/// 任务心跳句柄，drop 时停止心跳
+pub struct JobHeartbeat {
+    handle: JoinHandle<()>,
+}
+
+impl Drop for JobHeartbeat {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+/// 获取任务
+pub async fn get_job(db: &Database, job_id: &str) -> Option<Job> {
+    jobs(db)
+        .find_one(doc! { "job_id": job_id }, None)
+        .await
+        .ok()?
+}
+
+/// 请求停止运行中的任务，任何实例都可以发起，由执行任务的实例在检查点响应
+pub async fn request_cancel(db: &Database, job_id: &str) -> Result<bool, mongodb::error::Error> {
+    let result = jobs(db)
+        .update_one(
+            doc! { "job_id": job_id, "status": JOB_STATUS_RUNNING },
+            doc! { "$set": { "cancel_requested": true, "log": "正在停止任务...", "updated_at": DateTime::now() } },
+            None,
+        )
+        .await?;
+    Ok(result.matched_count > 0)
+}
+
+/// 任务是否已被请求停止
+pub async fn is_cancel_requested(db: &Database, job_id: &str) -> bool {
+    jobs(db)
+        .find_one(doc! { "job_id": job_id, "cancel_requested": true }, None)
+        .await
+        .ok()
+        .flatten()
+        .is_some()
+}
+
+/// 分页查询任务，按创建时间倒序
+pub async fn list_jobs(
+    db: &Database,
+    filter: Document,
+    page: u64,
+    limit: i64,
+) -> Result<(Vec<Job>, u64), mongodb::error::Error> {
+    let total = jobs(db).count_documents(filter.clone(), None).await?;
+    let find_options = FindOptions::builder()
+        .sort(doc! { "created_at": -1 })
+        .skip((page.max(1) - 1) * limit as u64)
+        .limit(limit)
+        .build();
+    let list: Vec<Job> = jobs(db).find(filter, find_options).await?.try_collect().await?;
+    Ok((list, total))
+}
+
+/// 查询运行中的任务
+pub async fn running_jobs(db: &Database, mut filter: Document) -> Result<Vec<Job>, mongodb::error::Error> {
+    filter.insert("status", JOB_STATUS_RUNNING);
+    let find_options = FindOptions::builder().sort(doc! { "started_at": 1 }).build();
+    jobs(db).find(filter, find_options).await?.try_collect().await
+}
+
+/// 将所在实例已退出的运行中任务标记为已中断，并同步采集任务记录，返回被标记的任务数量
+/// 心跳超时的任务视为实例已退出；服务启动时当前实例（INSTANCE_ID 固定时）遗留的任务也一并标记
+pub async fn recover_stale_jobs(db: &Database, on_startup: bool) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+    let now = DateTime::now();
+    let deadline = DateTime::from_millis(now.timestamp_millis() - STALE_JOB_SECS * 1000);
+    let mut conditions = vec![
+        doc! { "heartbeat_at": { "$lt": deadline } },
+        doc! { "heartbeat_at": { "$exists": false } },
+    ];
+    if on_startup {
+        conditions.push(doc! { "instance_id": instance_id() });
+    }
+    let filter = doc! { "status": JOB_STATUS_RUNNING, "$or": conditions };
+    let stale: Vec<Job> = jobs(db).find(filter, None).await?.try_collect().await?;
+
+    for job in &stale {
+        jobs(db)
+            .update_one(
+                doc! { "job_id": &job.job_id, "status": JOB_STATUS_RUNNING },
+                doc! {
+                    "$set": {
+                        "status": JOB_STATUS_INTERRUPTED,
+                        "log": "任务所在实例已退出，任务已中断，可重试继续执行",
+                        "finished_at": now,
+                        "updated_at": now,
+                    }
+                },
+                None,
+            )
+            .await?;
+    }
+
+    let collect_ids: Vec<String> = stale
+        .iter()
+        .filter(|job| job.job_type == JOB_TYPE_COLLECT)
+        .map(|job| job.job_id.clone())
+        .collect();
+    crate::collect_handlers::mark_interrupted_collect_tasks(db, &collect_ids).await?;
+
+    Ok(stale.len())
+}
+
+/// 重新执行失败、停止或中断的任务，返回 false 表示任务已被重新开始、本次未执行
+/// 任务状态在数据库中原子切换为运行中，管理员重试与自动重试同时发生时只有一方会执行
+pub async fn retry_job(db: &Database, job_id: &str) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+    let job = get_job(db, job_id).await.ok_or("任务不存在")?;
+    if !job.is_retryable() {
+        return Err("只能重试失败、已停止或已中断的任务".into());
+    }
+    if !claim_retry(db, job_id).await? {
+        return Ok(false);
+    }
+
+    let result: Result<(), Box<dyn std::error::Error + Send + Sync>> = match job.job_type.as_str() {
+        JOB_TYPE_COLLECT => crate::collect_handlers::resume_claimed_collect(db, &job).await,
+        JOB_TYPE_BATCH_DELETE_SOURCE => match job.params.get_str("source_name") {
+            Ok(source_name) => crate::admin_handlers::start_batch_delete_source(db, source_name.to_string(), Some(job.job_id.clone()))
+                .await
+                .map(|_| ())
+                .map_err(|e| e.into()),
+            Err(_) => Err("任务参数缺少播放源名称".into()),
+        },
+        JOB_TYPE_VOD_BULK => match mongodb::bson::from_document::<crate::admin_handlers::VodBulkRequest>(job.params.clone()) {
+            Ok(request) => crate::admin_handlers::start_vod_bulk(db, request, Some(job.job_id.clone()))
+                .await
+                .map(|_| ())
+                .map_err(|e| e.into()),
+            Err(e) => Err(e.into()),
+        },
+        other => Err(format!("未知的任务类型: {}", other).into()),
+    };
+
+    // 已标记为运行中但没能启动，恢复为失败状态以便之后重试
+    if let Err(e) = &result {
+        finish_job(db, job_id, JOB_STATUS_FAILED, None, &format!("重试启动失败: {}", e)).await?;
+    }
+    result.map(|_| true)
+}
+
+/// 后台维护：标记中断的任务，自动重试未超过执行次数上限的任务，并清理过期的已结束任务
+/// 由定时采集主节点周期调用，保证多实例时只有一个实例执行
+pub async fn maintain_jobs(db: &Database) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let stale = recover_stale_jobs(db, false).await?;
+    if stale > 0 {
+        println!("⚠️ {} 个任务因所在实例退出被标记为已中断", stale);
+    }
+
+    let deadline = DateTime::from_millis(DateTime::now().timestamp_millis() - RETRY_DELAY_SECS * 1000);
+    let filter = doc! {
+        "status": { "$in": [JOB_STATUS_FAILED, JOB_STATUS_INTERRUPTED] },
+        "finished_at": { "$lt": deadline },
+        "$expr": { "$lt": ["$attempts", "$max_attempts"] },
+    };
+    let retryable: Vec<Job> = jobs(db).find(filter, None).await?.try_collect().await?;
+    for job in retryable {
+        println!("🔁 自动重试任务: {} (第 {} 次)", job.job_name, job.attempts + 1);
+        match retry_job(db, &job.job_id).await {
+            Ok(true) => {}
+            Ok(false) => println!("任务已被重新开始，跳过自动重试: {}", job.job_id),
+            Err(e) => eprintln!("❌ 自动重试任务失败 {}: {}", job.job_id, e),
+        }
+    }
+
+    let expired = DateTime::from_millis(
+        DateTime::now().timestamp_millis() - FINISHED_JOB_RETENTION_DAYS * 24 * 3600 * 1000,
+    );
+    let result = jobs(db)
+        .delete_many(
+            doc! { "status": { "$ne": JOB_STATUS_RUNNING }, "finished_at": { "$lt": expired } },
+            None,
+        )
+        .await?;
+    if result.deleted_count > 0 {
+        println!("🧹 已清理 {} 个超过 {} 天的已结束任务", result.deleted_count, FINISHED_JOB_RETENTION_DAYS);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,184 @@
+use mongodb::bson::{doc, DateTime};
+use mongodb::error::{Error, ErrorKind, WriteFailure};
+use mongodb::options::UpdateOptions;
+use mongodb::{Collection as MongoCollection, Database};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+use tokio::task::JoinHandle;
+use tokio::time::{interval, Duration};
+
+// MongoDB 重复键错误码：租约被其他实例持有且未过期时 upsert 会触发
+const DUPLICATE_KEY_ERROR: i32 = 11000;
+
+static INSTANCE_ID: OnceLock<String> = OnceLock::new();
+
+/// 当前实例ID，优先取环境变量 INSTANCE_ID，未配置时每次启动随机生成
+pub fn instance_id() -> &'static str {
+    INSTANCE_ID.get_or_init(|| {
+        std::env::var("INSTANCE_ID")
+            .ok()
+            .filter(|id| !id.trim().is_empty())
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string())
+    })
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LeaseLockInfo {
+    #[serde(rename = "_id")]
+    pub name: String,
+    pub holder: String, // 每次获取时生成的令牌，续约和释放都按令牌匹配
+    #[serde(default)]
+    pub instance_id: String, // 持有者所在实例，仅用于展示和排查
+    pub acquired_at: DateTime,
+    pub renewed_at: DateTime,
+    pub expires_at: DateTime,
+}
+
+/// 基于 MongoDB 的租约锁：持有者需在租约过期前续约，过期后其他实例可以接管。
+/// 每个 LeaseLock 使用独立的令牌，同一进程内的多次获取也会互斥
+#[derive(Clone)]
+pub struct LeaseLock {
+    collection: MongoCollection<LeaseLockInfo>,
+    name: String,
+    holder: String,
+    ttl_secs: i64,
+}
+
+impl LeaseLock {
+    pub fn new(db: &Database, name: &str, ttl_secs: i64) -> Self {
+        Self {
+            collection: db.collection::<LeaseLockInfo>("leader_locks"),
+            name: name.to_string(),
+            holder: uuid::Uuid::new_v4().to_string(),
+            ttl_secs,
+        }
+    }
+
+    /// 获取或续约租约，返回当前令牌是否持有锁
+    pub async fn try_acquire(&self) -> Result<bool, Error> {
+        let now = DateTime::now();
+        let expires_at = DateTime::from_millis(now.timestamp_millis() + self.ttl_secs * 1000);
+
+        // 已持有则续约
+        let renewed = self
+            .collection
+            .update_one(
+                doc! { "_id": &self.name, "holder": &self.holder },
+                doc! { "$set": { "renewed_at": now, "expires_at": expires_at } },
+                None,
+            )
+            .await?;
+        if renewed.matched_count > 0 {
+            return Ok(true);
+        }
+
+        // 锁不存在或已过期时接管
+        let options = UpdateOptions::builder().upsert(true).build();
+        let result = self
+            .collection
+            .update_one(
+                doc! { "_id": &self.name, "expires_at": { "$lt": now } },
+                doc! {
+                    "$set": {
+                        "holder": &self.holder,
+                        "instance_id": instance_id(),
+                        "acquired_at": now,
+                        "renewed_at": now,
+                        "expires_at": expires_at,
+                    }
+                },
+                options,
+            )
+            .await;
+
+        match result {
+            Ok(_) => Ok(true),
+            Err(e) if is_duplicate_key(&e) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// 主动释放租约（仅当前令牌持有时生效）
+    pub async fn release(&self) -> Result<(), Error> {
+        self.collection
+            .delete_one(doc! { "_id": &self.name, "holder": &self.holder }, None)
+            .await?;
+        Ok(())
+    }
+
+    /// 当前租约信息
+    pub async fn current(&self) -> Result<Option<LeaseLockInfo>, Error> {
+        self.collection.find_one(doc! { "_id": &self.name }, None).await
+    }
+
+    /// 获取租约并在后台按 TTL 的三分之一周期续约，获取失败返回 None。
+    /// 续约发现租约已被接管时标记 guard 为丢失，持有者应在批次之间检查并停止
+    pub async fn acquire_guard(self) -> Result<Option<LeaseGuard>, Error> {
+        if !self.try_acquire().await? {
+            return Ok(None);
+        }
+
+        let lock = self.clone();
+        let lost = Arc::new(AtomicBool::new(false));
+        let lost_flag = lost.clone();
+        let period = Duration::from_secs((self.ttl_secs / 3).max(1) as u64);
+        let heartbeat = tokio::spawn(async move {
+            let mut timer = interval(period);
+            timer.tick().await;
+            loop {
+                timer.tick().await;
+                match lock.try_acquire().await {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        eprintln!("⚠️ 租约 {} 已被其他持有者接管", lock.name);
+                        lost_flag.store(true, Ordering::Release);
+                        break;
+                    }
+                    Err(e) => eprintln!("❌ 续约 {} 失败: {}", lock.name, e),
+                }
+            }
+        });
+
+        Ok(Some(LeaseGuard {
+            lock: self,
+            heartbeat: Some(heartbeat),
+            lost,
+        }))
+    }
+}
+
+/// 持有期间自动续约，释放（drop）时停止续约并删除租约
+pub struct LeaseGuard {
+    lock: LeaseLock,
+    heartbeat: Option<JoinHandle<()>>,
+    lost: Arc<AtomicBool>,
+}
+
+impl LeaseGuard {
+    /// 租约是否已被其他持有者接管，接管后不应再继续执行受保护的工作
+    pub fn is_lost(&self) -> bool {
+        self.lost.load(Ordering::Acquire)
+    }
+}
+
+impl Drop for LeaseGuard {
+    fn drop(&mut self) {
+        if let Some(heartbeat) = self.heartbeat.take() {
+            heartbeat.abort();
+        }
+        let lock = self.lock.clone();
+        tokio::spawn(async move {
+            if let Err(e) = lock.release().await {
+                eprintln!("❌ 释放租约 {} 失败: {}", lock.name, e);
+            }
+        });
+    }
+}
+
+fn is_duplicate_key(error: &Error) -> bool {
+    matches!(
+        error.kind.as_ref(),
+        ErrorKind::Write(WriteFailure::WriteError(e)) if e.code == DUPLICATE_KEY_ERROR
+    )
+}
@@ -0,0 +1,533 @@
+use crate::dto::{Category, JsonResponse, VodApiListEntry};
+use quick_xml::events::{BytesCData, BytesDecl, BytesText, Event};
+use quick_xml::Writer;
+use serde::Deserialize;
+use std::error::Error;
+use std::io::Write;
+
+// MacCMS 多播放源分隔符
+pub const PLAY_SEPARATOR: &str = "$$$";
+
+// 播放源名称的分隔符：标准格式以 $$$ 分隔且与地址一一对应，旧格式以逗号分隔且共用同一组地址
+pub fn play_from_separator(vod_play_from: &str) -> &'static str {
+    if vod_play_from.contains(PLAY_SEPARATOR) {
+        PLAY_SEPARATOR
+    } else {
+        ","
+    }
+}
+
+// 生成 MacCMS 10 格式的视频XML（detail=true 为详情结构，否则为列表结构）
+pub fn render_vod_xml(
+    response: &JsonResponse<VodApiListEntry>,
+    detail: bool,
+) -> Result<String, quick_xml::Error> {
+    let mut writer = Writer::new(Vec::new());
+
+    writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("utf-8"), None)))?;
+
+    writer
+        .create_element("rss")
+        .with_attribute(("version", "5.1"))
+        .write_inner_content(|writer| {
+            let page = response.page.to_string();
+            let pagecount = response.pagecount.to_string();
+            let pagesize = response.limit.to_string();
+            let recordcount = response.total.to_string();
+
+            writer
+                .create_element("list")
+                .with_attribute(("page", page.as_str()))
+                .with_attribute(("pagecount", pagecount.as_str()))
+                .with_attribute(("pagesize", pagesize.as_str()))
+                .with_attribute(("recordcount", recordcount.as_str()))
+                .write_inner_content(|writer| {
+                    for vod in &response.list {
+                        if detail {
+                            write_detail_video(writer, vod)?;
+                        } else {
+                            write_list_video(writer, vod)?;
+                        }
+                    }
+                    Ok::<(), quick_xml::Error>(())
+                })?;
+
+            if !response.categories.is_empty() {
+                write_class(writer, &response.categories)?;
+            }
+            Ok::<(), quick_xml::Error>(())
+        })?;
+
+    Ok(String::from_utf8_lossy(&writer.into_inner()).into_owned())
+}
+
+// 列表结构：<last><id><tid><name><type><dt><note>
+fn write_list_video<W: Write>(
+    writer: &mut Writer<W>,
+    vod: &VodApiListEntry,
+) -> Result<(), quick_xml::Error> {
+    writer
+        .create_element("video")
+        .write_inner_content(|writer| {
+            write_text(writer, "last", &vod.vod_time)?;
+            write_text(writer, "id", &vod.vod_id.to_string())?;
+            write_text(writer, "tid", &vod.type_id.to_string())?;
+            write_cdata(writer, "name", &vod.vod_name)?;
+            write_text(writer, "type", vod.type_name.as_deref().unwrap_or_default())?;
+            write_text(writer, "dt", &vod.vod_play_from)?;
+            write_cdata(writer, "note", &vod.vod_remarks)?;
+            Ok::<(), quick_xml::Error>(())
+        })?;
+    Ok(())
+}
+
+// 详情结构：在列表字段基础上输出图片、演员、播放地址<dl><dd flag>和简介
+fn write_detail_video<W: Write>(
+    writer: &mut Writer<W>,
+    vod: &VodApiListEntry,
+) -> Result<(), quick_xml::Error> {
+    writer
+        .create_element("video")
+        .write_inner_content(|writer| {
+            write_text(writer, "last", &vod.vod_time)?;
+            write_text(writer, "id", &vod.vod_id.to_string())?;
+            write_text(writer, "tid", &vod.type_id.to_string())?;
+            write_cdata(writer, "name", &vod.vod_name)?;
+            write_text(writer, "type", vod.type_name.as_deref().unwrap_or_default())?;
+            write_text(writer, "pic", vod.vod_pic.as_deref().unwrap_or_default())?;
+            write_text(writer, "lang", vod.vod_lang.as_deref().unwrap_or_default())?;
+            write_text(writer, "area", vod.vod_area.as_deref().unwrap_or_default())?;
+            write_text(writer, "year", vod.vod_year.as_deref().unwrap_or_default())?;
+            write_text(writer, "state", vod.vod_serial.as_deref().unwrap_or_default())?;
+            write_cdata(writer, "note", &vod.vod_remarks)?;
+            write_cdata(writer, "actor", vod.vod_actor.as_deref().unwrap_or_default())?;
+            write_cdata(
+                writer,
+                "director",
+                vod.vod_director.as_deref().unwrap_or_default(),
+            )?;
+
+            writer.create_element("dl").write_inner_content(|writer| {
+                // 与入库解析一致：$$$ 格式地址与播放源一一对应，逗号格式共用同一组地址
+                let play_url = vod.vod_play_url.as_deref().unwrap_or_default();
+                let separator = play_from_separator(&vod.vod_play_from);
+                let urls: Vec<&str> = play_url.split(PLAY_SEPARATOR).collect();
+                for (i, flag) in vod
+                    .vod_play_from
+                    .split(separator)
+                    .map(str::trim)
+                    .enumerate()
+                    .filter(|(_, flag)| !flag.is_empty())
+                {
+                    let url = if separator == PLAY_SEPARATOR {
+                        urls.get(i).copied().unwrap_or_default()
+                    } else {
+                        play_url
+                    };
+                    writer
+                        .create_element("dd")
+                        .with_attribute(("flag", flag))
+                        .write_inner_content(|writer| write_cdata_events(writer, url))?;
+                }
+                Ok::<(), quick_xml::Error>(())
+            })?;
+
+            write_cdata(writer, "des", vod.vod_content.as_deref().unwrap_or_default())?;
+            Ok::<(), quick_xml::Error>(())
+        })?;
+    Ok(())
+}
+
+// 分类列表：<class><ty id="1">电影</ty></class>
+fn write_class<W: Write>(
+    writer: &mut Writer<W>,
+    categories: &[Category],
+) -> Result<(), quick_xml::Error> {
+    writer
+        .create_element("class")
+        .write_inner_content(|writer| {
+            for category in categories {
+                let type_id = category.type_id.to_string();
+                writer
+                    .create_element("ty")
+                    .with_attribute(("id", type_id.as_str()))
+                    .write_text_content(BytesText::new(&category.type_name))?;
+            }
+            Ok::<(), quick_xml::Error>(())
+        })?;
+    Ok(())
+}
+
+fn write_text<W: Write>(
+    writer: &mut Writer<W>,
+    name: &str,
+    value: &str,
+) -> Result<(), quick_xml::Error> {
+    writer
+        .create_element(name)
+        .write_text_content(BytesText::new(value))?;
+    Ok(())
+}
+
+fn write_cdata<W: Write>(
+    writer: &mut Writer<W>,
+    name: &str,
+    value: &str,
+) -> Result<(), quick_xml::Error> {
+    writer
+        .create_element(name)
+        .write_inner_content(|writer| write_cdata_events(writer, value))?;
+    Ok(())
+}
+
+// CDATA内容中的"]]>"需要拆分为多个CDATA段
+fn write_cdata_events<W: Write>(
+    writer: &mut Writer<W>,
+    value: &str,
+) -> Result<(), quick_xml::Error> {
+    let mut parts = value.split("]]>").peekable();
+    while let Some(part) = parts.next() {
+        if parts.peek().is_some() {
+            writer.write_event(Event::CData(BytesCData::new(format!("{}]]", part))))?;
+            writer.write_event(Event::CData(BytesCData::new(">")))?;
+        } else {
+            writer.write_event(Event::CData(BytesCData::new(part)))?;
+        }
+    }
+    Ok(())
+}
+
+// ---- MacCMS XML 解析（采集 XML 格式的资源站） ----
+
+#[derive(Debug, Deserialize)]
+struct XmlRss {
+    list: XmlList,
+    #[serde(default)]
+    class: Option<XmlClass>,
+}
+
+#[derive(Debug, Deserialize)]
+struct XmlList {
+    #[serde(rename = "@page", default)]
+    page: String,
+    #[serde(rename = "@pagecount", default)]
+    pagecount: String,
+    #[serde(rename = "@pagesize", default)]
+    pagesize: String,
+    #[serde(rename = "@recordcount", default)]
+    recordcount: String,
+    #[serde(default)]
+    video: Vec<XmlVideo>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct XmlVideo {
+    last: String,
+    id: String,
+    tid: String,
+    name: String,
+    #[serde(rename = "type")]
+    type_name: String,
+    pic: String,
+    lang: String,
+    area: String,
+    year: String,
+    state: String,
+    note: String,
+    actor: String,
+    director: String,
+    dt: String,
+    dl: Option<XmlDl>,
+    des: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct XmlDl {
+    #[serde(default)]
+    dd: Vec<XmlDd>,
+}
+
+#[derive(Debug, Deserialize)]
+struct XmlDd {
+    #[serde(rename = "@flag", default)]
+    flag: String,
+    #[serde(rename = "$text", default)]
+    url: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct XmlClass {
+    #[serde(default)]
+    ty: Vec<XmlTy>,
+}
+
+#[derive(Debug, Deserialize)]
+struct XmlTy {
+    #[serde(rename = "@id")]
+    id: String,
+    #[serde(rename = "$text", default)]
+    name: String,
+}
+
+// 解析 MacCMS 10 格式的视频XML（列表或详情结构），转换为与JSON接口相同的结构
+pub fn parse_vod_xml(xml: &str) -> Result<JsonResponse<VodApiListEntry>, Box<dyn Error + Send + Sync>> {
+    let rss: XmlRss = quick_xml::de::from_str(xml.trim_start_matches('\u{feff}'))?;
+
+    let limit = parse_number(&rss.list.pagesize).unwrap_or(20);
+    let total = parse_number(&rss.list.recordcount).unwrap_or(0);
+    let pagecount = parse_number(&rss.list.pagecount)
+        .unwrap_or_else(|| if limit > 0 { total.div_ceil(limit) } else { 0 });
+
+    let mut list = Vec::with_capacity(rss.list.video.len());
+    for video in rss.list.video {
+        list.push(xml_video_to_entry(video)?);
+    }
+
+    let categories = rss
+        .class
+        .unwrap_or_default()
+        .ty
+        .into_iter()
+        .filter_map(|ty| {
+            Some(Category {
+                type_id: ty.id.trim().parse().ok()?,
+                type_name: ty.name.trim().to_string(),
+                type_pid: 0,
+            })
+        })
+        .collect();
+
+    Ok(JsonResponse {
+        code: 1,
+        msg: "success".to_string(),
+        page: parse_number(&rss.list.page).unwrap_or(1),
+        pagecount,
+        limit,
+        total,
+        list,
+        categories,
+    })
+}
+
+fn parse_number(value: &str) -> Option<u64> {
+    value.trim().parse().ok()
+}
+
+// <dl><dd flag> 播放列表按 MacCMS 约定以 $$$ 合并为 vod_play_from / vod_play_url
+fn xml_video_to_entry(video: XmlVideo) -> Result<VodApiListEntry, Box<dyn Error + Send + Sync>> {
+    let dds = video.dl.map(|dl| dl.dd).unwrap_or_default();
+    let (vod_play_from, vod_play_url) = if dds.is_empty() {
+        (video.dt.trim().to_string(), None)
+    } else {
+        let from = dds
+            .iter()
+            .map(|dd| dd.flag.trim())
+            .collect::<Vec<_>>()
+            .join(PLAY_SEPARATOR);
+        let url = dds
+            .iter()
+            .map(|dd| dd.url.trim())
+            .collect::<Vec<_>>()
+            .join(PLAY_SEPARATOR);
+        (from, Some(url))
+    };
+
+    let non_empty = |value: String| {
+        let value = value.trim().to_string();
+        if value.is_empty() { None } else { Some(value) }
+    };
+
+    let entry = serde_json::json!({
+        "vod_id": video.id.trim(),
+        "vod_name": video.name.trim(),
+        "type_id": video.tid.trim().parse::<i32>().unwrap_or(0),
+        "type_name": non_empty(video.type_name),
+        "vod_time": video.last.trim(),
+        "vod_remarks": video.note.trim(),
+        "vod_play_from": vod_play_from,
+        "vod_play_url": vod_play_url,
+        "vod_pic": non_empty(video.pic),
+        "vod_lang": non_empty(video.lang),
+        "vod_area": non_empty(video.area),
+        "vod_year": non_empty(video.year),
+        "vod_serial": non_empty(video.state),
+        "vod_actor": non_empty(video.actor),
+        "vod_director": non_empty(video.director),
+        "vod_content": non_empty(video.des),
+    });
+
+    Ok(serde_json::from_value(entry)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_response() -> JsonResponse<VodApiListEntry> {
+        let vod = serde_json::json!({
+            "vod_id": 101,
+            "vod_name": "庆余年 第二季",
+            "type_id": 6,
+            "type_name": "国产剧",
+            "vod_time": "2024-05-01 12:00:00",
+            "vod_remarks": "更新至36集",
+            "vod_play_from": "ffm3u8$$$lzm3u8",
+            "vod_play_url": "第1集$https://a.example.com/1.m3u8#第2集$https://a.example.com/2.m3u8$$$第1集$https://b.example.com/1.m3u8",
+            "vod_actor": "张若昀,李沁",
+            "vod_content": "<p>简介</p>",
+        });
+        JsonResponse {
+            code: 1,
+            msg: "success".to_string(),
+            page: 2,
+            pagecount: 3,
+            limit: 20,
+            total: 45,
+            list: vec![serde_json::from_value(vod).unwrap()],
+            categories: vec![Category {
+                type_id: 6,
+                type_name: "国产剧".to_string(),
+                type_pid: 0,
+            }],
+        }
+    }
+
+    #[test]
+    fn detail_xml_writes_one_dd_per_source() {
+        let xml = render_vod_xml(&sample_response(), true).unwrap();
+        assert!(xml.starts_with(r#"<?xml version="1.0" encoding="utf-8"?><rss version="5.1">"#));
+        assert!(xml.contains(r#"<list page="2" pagecount="3" pagesize="20" recordcount="45">"#));
+        assert!(xml.contains(
+            r#"<dd flag="ffm3u8"><![CDATA[第1集$https://a.example.com/1.m3u8#第2集$https://a.example.com/2.m3u8]]></dd>"#
+        ));
+        assert!(xml.contains(r#"<dd flag="lzm3u8"><![CDATA[第1集$https://b.example.com/1.m3u8]]></dd>"#));
+        assert!(xml.contains("<des><![CDATA[<p>简介</p>]]></des>"));
+        assert!(xml.contains(r#"<class><ty id="6">国产剧</ty></class>"#));
+    }
+
+    #[test]
+    fn detail_xml_shares_urls_between_comma_separated_sources() {
+        let mut response = sample_response();
+        response.list[0].vod_play_from = "ffm3u8,lzm3u8".to_string();
+        response.list[0].vod_play_url = Some("第1集$https://a.example.com/1.m3u8".to_string());
+        let xml = render_vod_xml(&response, true).unwrap();
+        assert!(xml.contains(r#"<dd flag="ffm3u8"><![CDATA[第1集$https://a.example.com/1.m3u8]]></dd>"#));
+        assert!(xml.contains(r#"<dd flag="lzm3u8"><![CDATA[第1集$https://a.example.com/1.m3u8]]></dd>"#));
+    }
+
+    #[test]
+    fn list_xml_has_no_play_urls() {
+        let xml = render_vod_xml(&sample_response(), false).unwrap();
+        assert!(!xml.contains("<dl>"));
+        assert!(xml.contains("<dt>ffm3u8$$$lzm3u8</dt>"));
+        assert!(xml.contains("<note><![CDATA[更新至36集]]></note>"));
+    }
+
+    #[test]
+    fn cdata_terminator_is_split() {
+        let mut response = sample_response();
+        response.list[0].vod_content = Some("a]]>b]]>c".to_string());
+        let xml = render_vod_xml(&response, true).unwrap();
+        assert!(xml.contains(
+            "<des><![CDATA[a]]]]><![CDATA[>]]><![CDATA[b]]]]><![CDATA[>]]><![CDATA[c]]></des>"
+        ));
+    }
+
+    #[test]
+    fn text_fields_are_escaped() {
+        let mut response = sample_response();
+        response.list[0].type_name = Some("欧美<剧>&".to_string());
+        let xml = render_vod_xml(&response, false).unwrap();
+        assert!(xml.contains("<type>欧美&lt;剧&gt;&amp;</type>"));
+    }
+
+    const DETAIL_XML: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<rss version="5.1">
+  <list page="2" pagecount="" pagesize="20" recordcount="45">
+    <video>
+      <last>2024-05-01 12:00:00</last>
+      <id>101</id>
+      <tid>6</tid>
+      <name><![CDATA[庆余年 第二季]]></name>
+      <type>国产剧</type>
+      <note><![CDATA[更新至36集]]></note>
+      <actor><![CDATA[张若昀,李沁]]></actor>
+      <dl>
+        <dd flag="ffm3u8"><![CDATA[第1集$https://a.example.com/1.m3u8#第2集$https://a.example.com/2.m3u8]]></dd>
+        <dd flag=" lzm3u8 "><![CDATA[ 第1集$https://b.example.com/1.m3u8 ]]></dd>
+      </dl>
+      <des><![CDATA[<p>简介</p>]]></des>
+    </video>
+  </list>
+  <class>
+    <ty id="6">国产剧</ty>
+    <ty id="x">无效分类</ty>
+  </class>
+</rss>"#;
+
+    #[test]
+    fn parse_detail_joins_play_sources() {
+        let response = parse_vod_xml(DETAIL_XML).unwrap();
+        assert_eq!((response.page, response.limit, response.total), (2, 20, 45));
+        // pagecount 为空时按总数和每页数量计算
+        assert_eq!(response.pagecount, 3);
+
+        let vod = &response.list[0];
+        assert_eq!(vod.vod_id.to_string(), "101");
+        assert_eq!(vod.type_id, 6);
+        assert_eq!(vod.vod_name, "庆余年 第二季");
+        assert_eq!(vod.vod_play_from, "ffm3u8$$$lzm3u8");
+        assert_eq!(
+            vod.vod_play_url.as_deref(),
+            Some("第1集$https://a.example.com/1.m3u8#第2集$https://a.example.com/2.m3u8$$$第1集$https://b.example.com/1.m3u8")
+        );
+        assert_eq!(vod.vod_pic, None);
+        assert_eq!(vod.vod_content.as_deref(), Some("<p>简介</p>"));
+
+        // 无法解析ID的分类被忽略
+        assert_eq!(response.categories.len(), 1);
+        assert_eq!(response.categories[0].type_name, "国产剧");
+    }
+
+    #[test]
+    fn parse_list_uses_dt_as_play_from() {
+        let xml = r#"<rss><list page="1" pagecount="1" pagesize="20" recordcount="1">
+            <video><last>2024-05-01</last><id>7</id><tid>1</tid><name>测试</name><dt>ffm3u8,lzm3u8</dt><note></note></video>
+        </list></rss>"#;
+        let vod = &parse_vod_xml(xml).unwrap().list[0];
+        assert_eq!(vod.vod_play_from, "ffm3u8,lzm3u8");
+        assert_eq!(vod.vod_play_url, None);
+    }
+
+    #[test]
+    fn rendered_xml_round_trips() {
+        for detail in [true, false] {
+            let response = sample_response();
+            let xml = render_vod_xml(&response, detail).unwrap();
+            let again = parse_vod_xml(&xml).unwrap();
+
+            assert_eq!(again.pagecount, response.pagecount);
+            assert_eq!(again.categories.len(), 1);
+            let (vod, original) = (&again.list[0], &response.list[0]);
+            assert_eq!(vod.vod_id.to_string(), original.vod_id.to_string());
+            assert_eq!(vod.vod_name, original.vod_name);
+            assert_eq!(vod.vod_remarks, original.vod_remarks);
+            assert_eq!(vod.vod_play_from, original.vod_play_from);
+            if detail {
+                assert_eq!(vod.vod_actor, original.vod_actor);
+                assert_eq!(vod.vod_play_url, original.vod_play_url);
+                assert_eq!(vod.vod_content, original.vod_content);
+            }
+        }
+    }
+
+    #[test]
+    fn split_cdata_round_trips() {
+        let mut response = sample_response();
+        response.list[0].vod_content = Some("a]]>b]]>c".to_string());
+        let xml = render_vod_xml(&response, true).unwrap();
+        let again = parse_vod_xml(&xml).unwrap();
+        assert_eq!(again.list[0].vod_content.as_deref(), Some("a]]>b]]>c"));
+    }
+}
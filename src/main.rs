@@ -1,24 +1,38 @@
 mod admin_auth_handlers;
 mod admin_handlers;
 mod api_handlers;
+mod audio_handlers;
 mod auth;
 mod auth_handlers;
+mod binding_suggest;
 mod collect_handlers;
+mod collect_rules;
+mod comment_handlers;
 mod db;
 mod dto;
+mod favorite_handlers;
+mod history_handlers;
+mod hit_counter;
 mod image_handlers;
 mod index_manager;
 mod init_data;
+mod jobs;
 mod jwt_auth;
+mod leader_lock;
+mod maccms_xml;
 mod models;
 mod processing_handlers;
 mod processing_service;
+mod rating_handlers;
 mod scheduled_task;
+mod search_index;
+mod series_handlers;
 mod site_data;
 mod storage_handlers;
 mod storage_service;
 mod template;
 mod unified_auth_handlers;
+mod vod_match;
 mod web_handlers;
 
 use admin_auth_handlers::{admin_logout, get_current_admin_info, refresh_token};
@@ -28,25 +42,41 @@ use admin_handlers::{
     create_user, create_vod, delete_binding, delete_cards, delete_collection, delete_config,
     delete_type, delete_users, delete_vod, generate_cards, get_batch_delete_progress_handler,
     get_bindings, get_cards_list, get_collect_progress, get_collection_binding_status,
+    get_binding_suggestions, accept_binding_suggestions,
     get_collections, get_config_by_key, get_configs, get_index_status, get_indexes_data,
-    get_running_batch_delete_tasks_handler, get_running_tasks, get_scheduled_task_logs,
+    get_running_batch_delete_tasks_handler, get_running_tasks, get_scheduled_task_logs, get_collect_tasks, resume_collect_task, get_jobs, get_job_detail, cancel_job, retry_job, bulk_vods, get_vod_merge_logs, test_collect_rules, download_dry_run_report,
     get_scheduled_task_status, get_statistics, get_types, get_user_by_id, get_users_list,
-    get_vods_admin, list_indexes, search_cards, search_users, start_collection_collect,
+    get_vods_admin, get_arts_admin, get_art_admin, create_art, update_art, delete_art,
+    batch_delete_arts, list_indexes, search_cards, search_users, start_collection_collect,
     start_scheduled_task, stop_batch_delete_task_handler, stop_collect_task, stop_scheduled_task,
     update_collection, update_config, update_scheduled_task_config, update_type, update_user,
-    update_vod,
+    update_vod, get_comments_admin, moderate_comments, ban_comment_user, get_audios_admin,
+    get_audio_admin, create_audio, update_audio, delete_audio, get_galleries_admin, review_galleries,
+};
+use audio_handlers::{
+    get_audio_tracks, get_user_audios, handle_audio_processing_webhook, submit_audio,
 };
 use auth_handlers::{get_current_user, logout, register};
 use collect_handlers::{get_collect_categories, get_collect_videos, start_collect_task};
+use comment_handlers::{comment_list_fragment, create_comment, delete_own_comment, toggle_comment_like};
+use favorite_handlers::{add_favorite, check_favorite, get_favorites, remove_favorite};
+use history_handlers::{
+    clear_history, delete_history, get_continue_watching, get_history, record_history,
+};
 use image_handlers::{
-    delete_image, get_image_detail, get_user_images, handle_image_processing_webhook, submit_image,
-    update_image,
+    delete_image, get_gallery_pages, get_image_detail, get_image_like_status, get_user_images,
+    handle_image_processing_webhook, submit_image, toggle_image_like, update_image,
 };
 use processing_handlers::{
     create_batch_processing_job, create_processing_job, get_batch_processing_job,
     get_batch_processing_jobs, get_processing_job, get_processing_jobs, get_webhook_notifications,
     handle_webhook, verify_webhook_signature,
 };
+use rating_handlers::{get_my_rating, rate_vod};
+use series_handlers::{
+    create_series, delete_series, get_followed_series, get_series_follow_status, get_user_series,
+    submit_chapter, toggle_series_follow,
+};
 use site_data::SiteDataManager;
 use storage_handlers::{
     admin_storage_page, complete_chunk_upload, create_storage_server, delete_storage_server,
@@ -200,6 +230,8 @@ async fn main() -> std::io::Result<()> {
             // 不退出应用，因为基本功能仍可使用
         }
     }
+    // 排行榜依赖点击统计，定期重新计算
+    site_data_manager.start_rank_refresh();
 
     // 初始化测试数据
     println!("🔧 正在初始化测试数据...");
@@ -213,6 +245,59 @@ async fn main() -> std::io::Result<()> {
         }
     }
 
+    // 为历史视频补齐数字ID
+    match db::backfill_vod_ids(&db).await {
+        Ok(0) => {}
+        Ok(count) => {
+            println!("✅ 已为 {} 个视频补齐 vod_id", count);
+        }
+        Err(e) => {
+            eprintln!("⚠️  补齐 vod_id 失败: {}", e);
+        }
+    }
+
+    match db::backfill_vod_name_keys(&db).await {
+        Ok(0) => {}
+        Ok(count) => {
+            println!("✅ 已为 {} 个视频补齐规范化名称", count);
+        }
+        Err(e) => {
+            eprintln!("⚠️  补齐 vod_name_key 失败: {}", e);
+        }
+    }
+
+    match db::backfill_image_review_status(&db).await {
+        Ok(0) => {}
+        Ok(count) => {
+            println!("✅ 已为 {} 个图集补齐审核状态", count);
+        }
+        Err(e) => {
+            eprintln!("⚠️  补齐图集审核状态失败: {}", e);
+        }
+    }
+
+    // 依赖图集审核状态，需在其后执行
+    match db::backfill_series_review_status(&db).await {
+        Ok(0) => {}
+        Ok(count) => {
+            println!("✅ 已为 {} 个连载补齐审核状态", count);
+        }
+        Err(e) => {
+            eprintln!("⚠️  补齐连载审核状态失败: {}", e);
+        }
+    }
+
+    // 上次运行中断的后台任务标记为已中断，可在任务中心重试或从断点继续采集
+    match jobs::recover_stale_jobs(&db, true).await {
+        Ok(0) => {}
+        Ok(count) => {
+            println!("⚠️  {} 个后台任务因服务重启中断，可在任务中心重试", count);
+        }
+        Err(e) => {
+            eprintln!("⚠️  标记中断后台任务失败: {}", e);
+        }
+    }
+
     // 初始化定时任务配置
     println!("🔧 正在初始化定时任务配置...");
     let scheduled_task_manager =
@@ -226,10 +311,21 @@ async fn main() -> std::io::Result<()> {
             // 不退出应用，因为基本功能仍可使用
         }
     }
+    // 所有实例都运行调度循环，由 MongoDB 租约选出唯一执行采集的主节点
+    scheduled_task_manager.start_scheduler_loop();
+
+    // 视频点击数先在内存中累加，由后台任务定期写回并处理日/周/月重置
+    let hit_counter = hit_counter::HitCounter::new(db.clone());
+    hit_counter.start();
+    let server_hit_counter = hit_counter.clone();
+
+    // 搜索索引由后台任务增量同步，多实例时由 MongoDB 租约保证只有一个实例在写
+    let search_indexer = search_index::SearchIndexer::new(db.clone());
+    search_indexer.start();
 
     println!("Starting server at http://127.0.0.1:8080");
 
-    HttpServer::new(move || {
+    let result = HttpServer::new(move || {
         App::new()
             // Store the database connection in the application state
             .app_data(web::Data::new(db.clone()))
@@ -237,6 +333,8 @@ async fn main() -> std::io::Result<()> {
             .app_data(web::Data::new(site_data_manager.clone()))
             // Store the scheduled task manager in the application state
             .app_data(web::Data::new(scheduled_task_manager.clone()))
+            // Store the buffered hit counter in the application state
+            .app_data(web::Data::new(server_hit_counter.clone()))
             // Gzip compression middleware
             .wrap(middleware::Compress::default())
             // Static file cache middleware
@@ -267,6 +365,51 @@ async fn main() -> std::io::Result<()> {
                 web::resource("/search")
                     .route(web::get().to(web_handlers::search_page_handler_wrapper)),
             )
+            .service(web::resource("/rank").route(web::get().to(web_handlers::rank_page_handler)))
+            .service(
+                web::resource("/rank/{type_id}")
+                    .route(web::get().to(web_handlers::rank_type_page_handler)),
+            )
+            .service(web::resource("/audio").route(web::get().to(web_handlers::audio_list_handler)))
+            .service(
+                web::resource("/audio/{id}").route(web::get().to(web_handlers::audio_detail_handler)),
+            )
+            .service(
+                web::resource("/api/audio/{id}/tracks").route(web::get().to(get_audio_tracks)),
+            )
+            .service(
+                web::resource("/gallery").route(web::get().to(web_handlers::gallery_list_handler)),
+            )
+            .service(
+                web::resource("/gallery/{id}")
+                    .route(web::get().to(web_handlers::gallery_detail_handler)),
+            )
+            .service(
+                web::resource("/gallery/{id}/read")
+                    .route(web::get().to(web_handlers::gallery_reader_handler)),
+            )
+            .service(
+                web::resource("/api/gallery/{id}/pages").route(web::get().to(get_gallery_pages)),
+            )
+            .service(
+                web::resource("/series").route(web::get().to(web_handlers::series_list_handler)),
+            )
+            .service(
+                web::resource("/series/{id}")
+                    .route(web::get().to(web_handlers::series_detail_handler)),
+            )
+            .service(
+                web::resource("/comments/{media_type}/{media_id}")
+                    .route(web::get().to(comment_list_fragment)),
+            )
+            .service(
+                web::resource("/art/list/{type_id}")
+                    .route(web::get().to(web_handlers::art_list_page_handler)),
+            )
+            .service(
+                web::resource("/art/detail/{art_id}")
+                    .route(web::get().to(web_handlers::art_detail_handler)),
+            )
             // Static pages
             .service(web::resource("/about").route(web::get().to(web_handlers::about_page)))
             .service(web::resource("/contact").route(web::get().to(web_handlers::contact_page)))
@@ -281,6 +424,14 @@ async fn main() -> std::io::Result<()> {
                 web::resource("/user/submit-image")
                     .route(web::get().to(web_handlers::submit_image_page)),
             )
+            .service(
+                web::resource("/user/submit-audio")
+                    .route(web::get().to(web_handlers::submit_audio_page)),
+            )
+            .service(
+                web::resource("/user/submit-series")
+                    .route(web::get().to(web_handlers::submit_series_page)),
+            )
             // Static files with cache configuration
             .service(
                 Files::new("/static", "./static")
@@ -299,6 +450,22 @@ async fn main() -> std::io::Result<()> {
             .service(
                 web::resource("/admin/vods").route(web::get().to(web_handlers::admin_vods_page)),
             )
+            .service(
+                web::resource("/admin/arts").route(web::get().to(web_handlers::admin_arts_page)),
+            )
+            .service(
+                web::resource("/admin/jobs").route(web::get().to(web_handlers::admin_jobs_page)),
+            )
+            .service(
+                web::resource("/admin/audios").route(web::get().to(web_handlers::admin_audios_page)),
+            )
+            .service(
+                web::resource("/admin/images").route(web::get().to(web_handlers::admin_images_page)),
+            )
+            .service(
+                web::resource("/admin/comments")
+                    .route(web::get().to(web_handlers::admin_comments_page)),
+            )
             .service(
                 web::resource("/admin/collect")
                     .route(web::get().to(web_handlers::admin_collect_page)),
@@ -345,6 +512,7 @@ async fn main() -> std::io::Result<()> {
                 web::resource("/api/videos/{type_id}")
                     .route(web::get().to(api_handlers::get_videos_by_type)),
             )
+            .service(web::resource("/api/rank").route(web::get().to(api_handlers::get_rankings)))
             .service(
                 web::resource("/api/categories/hierarchy")
                     .route(web::get().to(api_handlers::get_category_hierarchy)),
@@ -418,6 +586,14 @@ async fn main() -> std::io::Result<()> {
                         web::resource("/collections/{id}/binding-status")
                             .route(web::get().to(get_collection_binding_status)),
                     )
+                    .service(
+                        web::resource("/collections/{id}/binding-suggestions")
+                            .route(web::get().to(get_binding_suggestions)),
+                    )
+                    .service(
+                        web::resource("/collections/{id}/binding-suggestions/accept")
+                            .route(web::post().to(accept_binding_suggestions)),
+                    )
                     .service(
                         web::resource("/collections/{id}/collect")
                             .route(web::post().to(start_collection_collect)),
@@ -429,6 +605,62 @@ async fn main() -> std::io::Result<()> {
                     .service(
                         web::resource("/running-tasks").route(web::get().to(get_running_tasks)),
                     )
+                    .service(
+                        web::resource("/collect-tasks").route(web::get().to(get_collect_tasks)),
+                    )
+                    .service(
+                        web::resource("/collect-tasks/{task_id}/resume")
+                            .route(web::post().to(resume_collect_task)),
+                    )
+                    .service(web::resource("/jobs").route(web::get().to(get_jobs)))
+                    .service(web::resource("/jobs/{job_id}").route(web::get().to(get_job_detail)))
+                    .service(
+                        web::resource("/jobs/{job_id}/cancel").route(web::post().to(cancel_job)),
+                    )
+                    .service(
+                        web::resource("/jobs/{job_id}/retry").route(web::post().to(retry_job)),
+                    )
+                    // Audio Management
+                    .service(
+                        web::resource("/audios")
+                            .route(web::get().to(get_audios_admin))
+                            .route(web::post().to(create_audio)),
+                    )
+                    .service(
+                        web::resource("/audios/{id}")
+                            .route(web::get().to(get_audio_admin))
+                            .route(web::put().to(update_audio))
+                            .route(web::delete().to(delete_audio)),
+                    )
+                    // Gallery Moderation
+                    .service(web::resource("/images").route(web::get().to(get_galleries_admin)))
+                    .service(
+                        web::resource("/images/review").route(web::post().to(review_galleries)),
+                    )
+                    .service(web::resource("/series").route(web::get().to(admin_handlers::get_series_admin)))
+                    .service(
+                        web::resource("/series/review")
+                            .route(web::post().to(admin_handlers::review_series)),
+                    )
+                    // Comment Moderation
+                    .service(web::resource("/comments").route(web::get().to(get_comments_admin)))
+                    .service(
+                        web::resource("/comments/moderate").route(web::post().to(moderate_comments)),
+                    )
+                    .service(
+                        web::resource("/comments/{comment_id}/ban-user")
+                            .route(web::post().to(ban_comment_user)),
+                    )
+                    .service(
+                        web::resource("/collect/dry-run-report/{task_id}")
+                            .route(web::get().to(download_dry_run_report)),
+                    )
+                    .service(
+                        web::resource("/vod-merge-logs").route(web::get().to(get_vod_merge_logs)),
+                    )
+                    .service(
+                        web::resource("/collect-rules/test").route(web::post().to(test_collect_rules)),
+                    )
                     // Video Management
                     .service(
                         web::resource("/vods")
@@ -439,6 +671,7 @@ async fn main() -> std::io::Result<()> {
                     .service(
                         web::resource("/vods/batch-set-vip").route(web::post().to(batch_set_vip)),
                     )
+                    .service(web::resource("/vods/bulk").route(web::post().to(bulk_vods)))
                     .service(
                         web::resource("/batch-delete-source")
                             .route(web::post().to(batch_delete_source)),
@@ -460,6 +693,19 @@ async fn main() -> std::io::Result<()> {
                             .route(web::put().to(update_vod))
                             .route(web::delete().to(delete_vod)),
                     )
+                    // 文章管理
+                    .service(
+                        web::resource("/arts")
+                            .route(web::get().to(get_arts_admin))
+                            .route(web::post().to(create_art))
+                            .route(web::delete().to(batch_delete_arts)),
+                    )
+                    .service(
+                        web::resource("/arts/{id}")
+                            .route(web::get().to(get_art_admin))
+                            .route(web::put().to(update_art))
+                            .route(web::delete().to(delete_art)),
+                    )
                     // Index Management
                     .service(web::resource("/indexes/create").route(web::post().to(create_indexes)))
                     .service(
@@ -467,6 +713,15 @@ async fn main() -> std::io::Result<()> {
                     )
                     .service(web::resource("/indexes/list").route(web::get().to(list_indexes)))
                     .service(web::resource("/indexes/data").route(web::get().to(get_indexes_data)))
+                    // Search Index
+                    .service(
+                        web::resource("/search/status")
+                            .route(web::get().to(admin_handlers::get_search_index_status)),
+                    )
+                    .service(
+                        web::resource("/search/sync")
+                            .route(web::post().to(admin_handlers::sync_search_index)),
+                    )
                     // Statistics
                     .service(web::resource("/statistics").route(web::get().to(get_statistics)))
                     // Card Management
@@ -547,6 +802,48 @@ async fn main() -> std::io::Result<()> {
                     .service(web::resource("/use-card").route(web::post().to(use_card)))
                     .service(web::resource("/vip-info").route(web::get().to(get_user_vip_info)))
                     .service(web::resource("/vip-check").route(web::post().to(vip_check_handler)))
+                    // Watch history - Requires user login
+                    .service(
+                        web::resource("/history")
+                            .route(web::get().to(get_history))
+                            .route(web::post().to(record_history))
+                            .route(web::delete().to(clear_history)),
+                    )
+                    .service(
+                        web::resource("/history/continue/{media_id}")
+                            .route(web::get().to(get_continue_watching)),
+                    )
+                    .service(
+                        web::resource("/history/{history_id}")
+                            .route(web::delete().to(delete_history)),
+                    )
+                    // Favorites - Requires user login
+                    .service(
+                        web::resource("/favorites")
+                            .route(web::get().to(get_favorites))
+                            .route(web::post().to(add_favorite)),
+                    )
+                    .service(
+                        web::resource("/favorites/check/{media_id}")
+                            .route(web::get().to(check_favorite)),
+                    )
+                    .service(
+                        web::resource("/favorites/{media_id}")
+                            .route(web::delete().to(remove_favorite)),
+                    )
+                    // Ratings - Requires user login
+                    .service(web::resource("/ratings").route(web::post().to(rate_vod)))
+                    .service(web::resource("/ratings/{vod_id}").route(web::get().to(get_my_rating)))
+                    // Comments - Requires user login
+                    .service(web::resource("/comments").route(web::post().to(create_comment)))
+                    .service(
+                        web::resource("/comments/{comment_id}")
+                            .route(web::delete().to(delete_own_comment)),
+                    )
+                    .service(
+                        web::resource("/comments/{comment_id}/like")
+                            .route(web::post().to(toggle_comment_like)),
+                    )
                     // Storage servers - Requires user login
                     .service(
                         web::resource("/storage/servers")
@@ -569,6 +866,10 @@ async fn main() -> std::io::Result<()> {
                         web::resource("/storage/upload/chunk/{server_id}/status/{upload_id}")
                             .route(web::get().to(get_upload_status)),
                     )
+                    .service(
+                        web::resource("/storage/upload/archive/{server_id}")
+                            .route(web::post().to(generate_archive_upload_url)),
+                    )
                     // Image Gallery API routes - Requires user login
                     .service(web::resource("/images/submit").route(web::post().to(submit_image)))
                     .service(web::resource("/images").route(web::get().to(get_user_images)))
@@ -578,6 +879,33 @@ async fn main() -> std::io::Result<()> {
                             .route(web::put().to(update_image))
                             .route(web::delete().to(delete_image)),
                     )
+                    .service(
+                        web::resource("/images/{image_id}/like")
+                            .route(web::get().to(get_image_like_status))
+                            .route(web::post().to(toggle_image_like)),
+                    )
+                    // Gallery Series API routes - Requires user login
+                    .service(
+                        web::resource("/series")
+                            .route(web::get().to(get_user_series))
+                            .route(web::post().to(create_series)),
+                    )
+                    .service(
+                        web::resource("/series/following").route(web::get().to(get_followed_series)),
+                    )
+                    .service(web::resource("/series/{series_id}").route(web::delete().to(delete_series)))
+                    .service(
+                        web::resource("/series/{series_id}/chapters")
+                            .route(web::post().to(submit_chapter)),
+                    )
+                    .service(
+                        web::resource("/series/{series_id}/follow")
+                            .route(web::get().to(get_series_follow_status))
+                            .route(web::post().to(toggle_series_follow)),
+                    )
+                    // Audio API routes - Requires user login
+                    .service(web::resource("/audios/submit").route(web::post().to(submit_audio)))
+                    .service(web::resource("/audios").route(web::get().to(get_user_audios)))
                     // Processing API routes - Requires admin login
                     .service(
                         web::resource("/processing/job")
@@ -620,6 +948,10 @@ async fn main() -> std::io::Result<()> {
                 web::resource("/api/webhook/image-processing")
                     .route(web::post().to(handle_image_processing_webhook)),
             )
+            .service(
+                web::resource("/api/webhook/audio-processing")
+                    .route(web::post().to(handle_audio_processing_webhook)),
+            )
             // Config API routes
             .service(
                 web::resource("/api/config/buy_card").route(web::get().to(get_buy_card_config)),
@@ -633,5 +965,9 @@ async fn main() -> std::io::Result<()> {
             .unwrap(),
     ))?
     .run()
-    .await
+    .await;
+
+    // 停机前写回尚未落库的点击数，进行中的后台写回会先完成
+    hit_counter.flush().await;
+    result
 }
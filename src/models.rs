@@ -1,5 +1,6 @@
 use mongodb::bson::{oid::ObjectId, DateTime};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 // Note: In a real application, you would want to use a library like `chrono` for more robust date/time handling.
 // Here we use mongodb::bson::DateTime for simplicity.
@@ -8,13 +9,21 @@ use serde::{Deserialize, Serialize};
 pub struct Vod {
     #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
     pub id: Option<ObjectId>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vod_id: Option<i64>, // Stable numeric ID used by the provide API
     pub vod_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vod_name_key: Option<String>, // Normalized name used for duplicate matching
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vod_douban_id: Option<i64>,
     pub type_id: i32,
     pub vod_status: i32,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub vod_class: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub vod_pic: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vod_pic_source: Option<String>, // Original poster URL when vod_pic was localized
     #[serde(skip_serializing_if = "Option::is_none")]
     pub vod_actor: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -40,6 +49,10 @@ pub struct Vod {
     pub vod_hits_month: Option<i32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub vod_score: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vod_score_all: Option<i32>, // Sum of all user ratings
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vod_score_num: Option<i32>, // Number of user ratings
     #[serde(default)]
     pub need_vip: i32, // 0=no, 1=vip level 1, 2=vip level2, 3=vip level3
     // In MongoDB, this is better represented as a nested structure
@@ -76,6 +89,14 @@ pub struct Art {
     pub art_pubdate: DateTime,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub art_content: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub art_blurb: Option<String>, // 摘要
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub art_tag: Option<String>, // 标签，逗号分隔
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub art_hits: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub art_pic_source: Option<String>, // 图片本地化前的原始地址
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -144,6 +165,8 @@ pub struct Binding {
     pub id: String, // e.g., "source_flag_external_id"
     pub source_flag: String,     // 采集源标识，如API的唯一标识符
     pub external_id: String,     // 外部分类ID
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub external_name: Option<String>, // 外部分类名称，用于自动推荐
     pub local_type_id: i32,      // 本地分类ID
     pub local_type_name: String, // 本地分类名称
     pub created_at: DateTime,    // 创建时间
@@ -197,28 +220,94 @@ pub struct Collection {
     pub collect_convert_webp: i32, // Convert to WebP: 0=no, 1=yes
     #[serde(default = "default_download_retry")]
     pub collect_download_retry: i32, // Download retry times
+    #[serde(default)]
+    pub collect_format: i32, // Response format: 0=auto, 1=json, 2=xml
+    #[serde(default)]
+    pub collect_match: i32, // Duplicate matching: 0=exact, 1=normalized name, 2=name+year+director, 3=douban id
+    #[serde(default)]
+    pub collect_update_policy: HashMap<String, String>, // Re-collect policy per vod field: overwrite, fill or ignore
+    #[serde(default)]
+    pub collect_cron: String, // Cron schedule, e.g. "0 */6 * * *"; collect_opt is used as the window
+    #[serde(default)]
+    pub collect_cron_status: i32, // Scheduled collection: 1=enabled, 0=disabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collect_last_run: Option<DateTime>, // Last scheduled run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collect_next_run: Option<DateTime>, // Next scheduled run
     pub collect_status: i32,    // Status: 1=enabled, 0=disabled
     pub created_at: DateTime,
     pub updated_at: DateTime,
 }
 
-// Collection task model
+// Collection task model, one record per collection run; the run itself executes as a job
+// and its resumable checkpoint lives in the job's params.checkpoint
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CollectTask {
     #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
     pub id: Option<ObjectId>,
+    pub task_id: String,      // Job ID of the run (jobs.job_id), also used by the progress and resume APIs
     pub task_name: String,    // Task name
     pub collect_id: ObjectId, // Collection source ID
-    pub task_status: i32,     // Status: 0=pending, 1=running, 2=completed, 3=failed
+    pub task_status: i32,     // Status: 1=running, 2=completed, 3=failed, 4=stopped
     pub task_progress: i32,   // Progress percentage
-    pub task_total: i32,      // Total items
+    pub task_total: i32,      // Total pages
     pub task_success: i32,    // Success count
     pub task_failed: i32,     // Failed count
     pub task_log: String,     // Task log
+    #[serde(default)]
+    pub task_hours: Option<String>, // Hours window passed to the source
+    #[serde(default)]
+    pub current_page: i32, // Checkpoint: last fully collected page
+    #[serde(default)]
+    pub dry_run: bool, // Preview only: nothing is written to vods
     pub created_at: DateTime,
     pub updated_at: DateTime,
 }
 
+// Dry-run report of a collection task
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct CollectDryRunReport {
+    pub created: u32,         // Items that would be created
+    pub updated: u32,         // Items that would update an existing vod
+    pub skipped: u32,         // Items dropped by collection rules
+    pub missing_binding: u32, // Items without a category binding
+    pub missing_bindings: Vec<DryRunMissingBinding>,
+    pub items: Vec<DryRunItem>, // Per-item details, capped
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DryRunMissingBinding {
+    pub type_id: i32,              // Source category ID
+    pub type_name: Option<String>, // Source category name
+    pub count: u32,                // Affected items
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DryRunItem {
+    pub source_vod_id: String,
+    pub vod_name: String,
+    pub action: String,         // create, update, skip, missing_binding
+    pub detail: Option<String>, // Skip reason or matched vod name
+}
+
+// Collect merge log model (incoming item merged into an existing vod under a different name)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct VodMergeLog {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub task_id: String,                 // Collect task ID, empty for single-video collection
+    pub collect_id: Option<ObjectId>,    // Collection source ID
+    pub collect_name: String,            // Collection source name
+    pub collect_match: i32,              // Matching strategy used
+    pub source_vod_id: String,           // Video ID on the source site
+    pub source_vod_name: String,         // Incoming video name
+    pub source_vod_year: Option<String>, // Incoming video year
+    pub vod_id: Option<ObjectId>,        // Existing vod merged into
+    pub vod_name: String,                // Existing vod name
+    pub vod_year: Option<String>,        // Existing vod year
+    pub created_at: DateTime,
+}
+
 // Media type enum
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub enum MediaType {
@@ -227,6 +316,35 @@ pub enum MediaType {
     Audio, // Audio collection
 }
 
+impl MediaType {
+    // 对应媒体所在的集合
+    pub fn collection(&self) -> &'static str {
+        match self {
+            MediaType::Vod => "vods",
+            MediaType::Image => "images",
+            MediaType::Audio => "audios",
+        }
+    }
+
+    // 用于 URL 路径的小写标识
+    pub fn slug(&self) -> &'static str {
+        match self {
+            MediaType::Vod => "vod",
+            MediaType::Image => "image",
+            MediaType::Audio => "audio",
+        }
+    }
+
+    pub fn from_slug(slug: &str) -> Option<Self> {
+        match slug.to_ascii_lowercase().as_str() {
+            "vod" => Some(MediaType::Vod),
+            "image" => Some(MediaType::Image),
+            "audio" => Some(MediaType::Audio),
+            _ => None,
+        }
+    }
+}
+
 // User watching history model (supports all media types)
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct UserHistory {
@@ -240,6 +358,8 @@ pub struct UserHistory {
     pub poster: String,               // Poster URL
     pub episode_name: Option<String>, // Episode name (for vod only)
     pub current_time: Option<i64>,    // Current playback time in seconds (for vod only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration: Option<i64>,        // Total duration in seconds (for vod only)
     pub watch_time: Option<String>,   // Formatted watch time (for vod only)
     pub timestamp: i64,               // Unix timestamp when record was created
     pub created_at: DateTime,
@@ -262,6 +382,56 @@ pub struct UserFavorite {
     pub updated_at: DateTime,
 }
 
+// User rating of a video, one per user per video
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct VodRating {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub user_id: ObjectId, // User ID
+    pub vod_id: ObjectId,  // Video ID
+    pub score: i32,        // Rating 1-10
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+// Comment status
+pub const COMMENT_STATUS_PENDING: i32 = 0;
+pub const COMMENT_STATUS_APPROVED: i32 = 1;
+pub const COMMENT_STATUS_REJECTED: i32 = 2;
+
+// Comment on a vod, image gallery or audio; replies are flattened to two levels
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Comment {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub media_type: MediaType,             // Media type (Vod, Image, Audio)
+    pub media_id: ObjectId,                // Media ID
+    pub media_name: String,                // Media name at the time of commenting
+    pub user_id: ObjectId,                 // Author user ID
+    pub user_name: String,                 // Author user name
+    pub parent_id: Option<ObjectId>,       // Top-level comment ID for replies
+    pub reply_to_user: Option<String>,     // User name being replied to
+    pub content: String,                   // Content after sensitive-word filtering
+    pub status: i32,                       // 0=pending, 1=approved, 2=rejected
+    #[serde(default)]
+    pub sensitive_hit: bool,               // Whether the content contained sensitive words
+    #[serde(default)]
+    pub likes: i32,                        // Number of likes
+    pub ip: Option<String>,                // Client IP
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+// Comment like record, one per user per comment
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CommentLike {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub comment_id: ObjectId,
+    pub user_id: ObjectId,
+    pub created_at: DateTime,
+}
+
 // Card model for membership cards
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Card {
@@ -284,6 +454,7 @@ pub struct Image {
     pub title: String,               // Image title
     pub en_title: Option<String>,    // English title
     pub description: Option<String>, // Image description
+    #[serde(default)]
     pub images: Vec<ImageItem>,      // Array of images
     pub cover: ImageItem,            // Cover image
     pub tags: Vec<String>,           // Tags
@@ -294,10 +465,103 @@ pub struct Image {
     pub language: Option<String>,    // Language
     pub artists: Option<String>,     // Artists
     pub need_vip: i32,               // Whether VIP is required: 0=no, 1=yes
+    #[serde(default = "default_image_status")]
+    pub status: String,              // processing, published, failed
+    #[serde(default = "default_image_review_status")]
+    pub review_status: String,       // pending, approved, rejected
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub review_reason: Option<String>, // Rejection reason shown to the uploader
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reviewed_at: Option<DateTime>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub series_id: Option<ObjectId>, // Series this chapter belongs to, None for standalone galleries
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chapter_no: Option<i32>,     // Chapter order within the series
     pub created_at: DateTime,
     pub updated_at: DateTime,
 }
 
+// 图集审核状态
+pub const IMAGE_REVIEW_PENDING: &str = "pending";
+pub const IMAGE_REVIEW_APPROVED: &str = "approved";
+pub const IMAGE_REVIEW_REJECTED: &str = "rejected";
+
+// 投稿时尚未写入状态的图集视为处理中
+fn default_image_status() -> String {
+    "processing".to_string()
+}
+
+// 启动时由 db::backfill_image_review_status 为历史图集补齐，缺失时按待审核处理
+fn default_image_review_status() -> String {
+    IMAGE_REVIEW_PENDING.to_string()
+}
+
+// Image like record, one per user per gallery
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImageLike {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub image_id: ObjectId,
+    pub user_id: ObjectId,
+    pub created_at: DateTime,
+}
+
+// Gallery series model, chapters are Image documents referencing the series
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GallerySeries {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub title: String,               // Series title
+    pub en_title: Option<String>,    // English title
+    pub description: Option<String>, // Series description
+    pub cover: ImageItem,            // Cover image, defaults to the first approved chapter cover
+    pub tags: Vec<String>,           // Tags
+    pub category: String,            // Category
+    pub uploader: ObjectId,          // User who created the series
+    #[serde(default)]
+    pub followers: i32,              // Number of followers
+    #[serde(default = "default_image_review_status")]
+    pub review_status: String,       // pending, approved, rejected; title and description are public only when approved
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub review_reason: Option<String>, // Rejection reason shown to the uploader
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reviewed_at: Option<DateTime>,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,        // Last time a chapter was added
+}
+
+// Series follow record, one per user per series
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SeriesFollow {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub series_id: ObjectId,
+    pub user_id: ObjectId,
+    pub created_at: DateTime,
+}
+
+// Search index entry, one per searchable vod, article, gallery or audio album
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SearchEntry {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,                // Same as the source document id
+    pub kind: String,                // vod, art, gallery, audio
+    pub title: String,
+    pub title_key: String,           // Normalized title used for exact and prefix ranking
+    #[serde(default)]
+    pub subtitle: String,            // Actors, author or category shown under the title
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cover: Option<String>,
+    #[serde(default)]
+    pub terms: Vec<String>,          // N-gram and pinyin terms of every searchable field
+    #[serde(default)]
+    pub title_terms: Vec<String>,    // Terms of the title only, used for ranking
+    pub popularity: i32,             // log2 bucket of hits or likes
+    pub fingerprint: String,         // Hash of the indexed content, unchanged entries are skipped on sync
+    pub source_updated_at: DateTime,
+    pub indexed_at: DateTime,
+}
+
 // Image item structure for images array
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ImageItem {
@@ -317,12 +581,23 @@ pub struct Audio {
     pub tags: Vec<String>,           // Tags
     pub category: String,            // Category
     pub cover: ImageItem,            // Cover image
+    #[serde(default)]
     pub audios: Vec<String>,         // Array of audio URLs
+    #[serde(default)]
+    pub track_names: Vec<String>,    // Track names, aligned with audios
     pub need_vip: i32,               // Whether VIP is required: 0=no, 1=yes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uploader: Option<ObjectId>,  // User who uploaded, None for admin-created albums
+    #[serde(default = "default_audio_status")]
+    pub status: String,              // processing, published, failed
     pub created_at: DateTime,
     pub updated_at: DateTime,
 }
 
+fn default_audio_status() -> String {
+    "published".to_string()
+}
+
 // Distributed storage server model
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct StorageServer {
@@ -463,3 +738,43 @@ pub struct ProcessingServerConfig {
     pub created_at: DateTime,
     pub updated_at: DateTime,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mongodb::bson::{doc, from_document};
+
+    fn cover() -> mongodb::bson::Document {
+        doc! { "url": "", "width": 0, "height": 0 }
+    }
+
+    // 列表和详情页以 {"audios": 0} / {"images": 0} 投影读取，缺少的字段必须有默认值
+    #[test]
+    fn projected_media_documents_deserialize() {
+        let now = DateTime::now();
+        let audio: Audio = from_document(doc! {
+            "title": "专辑", "tags": [], "category": "音乐", "cover": cover(),
+            "need_vip": 0, "created_at": now, "updated_at": now,
+        })
+        .unwrap();
+        assert!(audio.audios.is_empty() && audio.track_names.is_empty());
+        assert_eq!(audio.status, "published");
+
+        let image: Image = from_document(doc! {
+            "title": "图集", "cover": cover(), "tags": [], "category": "插画", "pages": 3,
+            "uploader": ObjectId::new(), "likes": 0, "need_vip": 0,
+            "created_at": now, "updated_at": now,
+        })
+        .unwrap();
+        assert!(image.images.is_empty());
+        assert_eq!(image.review_status, IMAGE_REVIEW_PENDING);
+
+        let series: GallerySeries = from_document(doc! {
+            "title": "连载", "cover": cover(), "tags": [], "category": "漫画",
+            "uploader": ObjectId::new(), "created_at": now, "updated_at": now,
+        })
+        .unwrap();
+        assert_eq!(series.followers, 0);
+        assert_eq!(series.review_status, IMAGE_REVIEW_PENDING);
+    }
+}
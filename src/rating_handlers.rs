@@ -0,0 +1,221 @@
+use crate::jwt_auth::AuthenticatedUser;
+use crate::models::{Vod, VodRating};
+use actix_web::{web, HttpResponse, Responder};
+use mongodb::bson::{doc, oid::ObjectId, DateTime};
+use mongodb::options::{FindOneAndUpdateOptions, FindOneOptions, ReturnDocument};
+use mongodb::Database;
+use serde::Deserialize;
+use serde_json::json;
+
+pub const RATING_COLLECTION: &str = "vod_ratings";
+const MIN_SCORE: i32 = 1;
+const MAX_SCORE: i32 = 10;
+
+// 评分请求
+#[derive(Debug, Deserialize)]
+pub struct RateVodRequest {
+    pub vod_id: String,
+    pub score: i32,
+}
+
+// 由总分和人数计算 vod_score，保留一位小数
+fn format_score(score_all: i32, score_num: i32) -> String {
+    if score_num > 0 {
+        format!("{:.1}", score_all as f64 / score_num as f64)
+    } else {
+        "0.0".to_string()
+    }
+}
+
+// 读取视频当前的评分汇总 (vod_score, vod_score_all, vod_score_num)
+async fn load_vod_score(
+    db: &Database,
+    vod_id: ObjectId,
+) -> Result<Option<(String, i32, i32)>, mongodb::error::Error> {
+    let options = FindOneOptions::builder()
+        .projection(doc! { "vod_score": 1, "vod_score_all": 1, "vod_score_num": 1 })
+        .build();
+    Ok(db
+        .collection::<mongodb::bson::Document>("vods")
+        .find_one(doc! { "_id": vod_id }, options)
+        .await?
+        .map(|vod| {
+            let score_all = vod.get_i32("vod_score_all").unwrap_or(0);
+            let score_num = vod.get_i32("vod_score_num").unwrap_or(0);
+            let score = vod
+                .get_str("vod_score")
+                .map(str::to_string)
+                .unwrap_or_else(|_| format_score(score_all, score_num));
+            (score, score_all, score_num)
+        }))
+}
+
+/// 将评分变化累加到视频的总分和人数上，并按新的汇总值重算 vod_score
+async fn apply_score_change(
+    db: &Database,
+    vod_id: ObjectId,
+    score_delta: i32,
+    num_delta: i32,
+) -> Result<(String, i32, i32), mongodb::error::Error> {
+    let collection = db.collection::<Vod>("vods");
+    let options = FindOneAndUpdateOptions::builder()
+        .return_document(ReturnDocument::After)
+        .build();
+    let updated = collection
+        .find_one_and_update(
+            doc! { "_id": vod_id },
+            doc! { "$inc": { "vod_score_all": score_delta, "vod_score_num": num_delta } },
+            options,
+        )
+        .await?;
+
+    let Some(vod) = updated else {
+        return Ok(("0.0".to_string(), 0, 0));
+    };
+    let score_all = vod.vod_score_all.unwrap_or(0);
+    let score_num = vod.vod_score_num.unwrap_or(0);
+    let score = format_score(score_all, score_num);
+
+    // 仅在汇总值未被并发评分改动时写入，否则由后一次评分负责
+    collection
+        .update_one(
+            doc! { "_id": vod_id, "vod_score_all": score_all, "vod_score_num": score_num },
+            doc! { "$set": { "vod_score": &score } },
+            None,
+        )
+        .await?;
+
+    Ok((score, score_all, score_num))
+}
+
+// 提交或修改评分，每个用户对每个视频只保留一条评分
+pub async fn rate_vod(
+    user: AuthenticatedUser,
+    db: web::Data<Database>,
+    req: web::Json<RateVodRequest>,
+) -> impl Responder {
+    let Some(user_id) = user.user.id else {
+        return HttpResponse::Unauthorized().json(json!({"success": false, "message": "用户信息无效"}));
+    };
+    if !(MIN_SCORE..=MAX_SCORE).contains(&req.score) {
+        return HttpResponse::BadRequest().json(json!({
+            "success": false,
+            "message": format!("评分必须在 {} 到 {} 之间", MIN_SCORE, MAX_SCORE)
+        }));
+    }
+    let vod_id = match ObjectId::parse_str(&req.vod_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return HttpResponse::BadRequest().json(json!({"success": false, "message": "无效的视频ID"}));
+        }
+    };
+
+    match db
+        .collection::<Vod>("vods")
+        .count_documents(doc! { "_id": vod_id }, None)
+        .await
+    {
+        Ok(0) => {
+            return HttpResponse::NotFound().json(json!({"success": false, "message": "视频不存在"}));
+        }
+        Ok(_) => {}
+        Err(e) => {
+            eprintln!("查询视频失败: {}", e);
+            return HttpResponse::InternalServerError().json(json!({"success": false, "message": "评分失败"}));
+        }
+    }
+
+    // 返回更新前的评分，用于计算总分变化
+    let now = DateTime::now();
+    let options = FindOneAndUpdateOptions::builder()
+        .upsert(true)
+        .return_document(ReturnDocument::Before)
+        .build();
+    let previous = match db
+        .collection::<VodRating>(RATING_COLLECTION)
+        .find_one_and_update(
+            doc! { "user_id": user_id, "vod_id": vod_id },
+            doc! {
+                "$set": { "score": req.score, "updated_at": now },
+                "$setOnInsert": { "created_at": now },
+            },
+            options,
+        )
+        .await
+    {
+        Ok(previous) => previous,
+        Err(e) => {
+            eprintln!("保存评分失败: {}", e);
+            return HttpResponse::InternalServerError().json(json!({"success": false, "message": "评分失败"}));
+        }
+    };
+
+    let (score_delta, num_delta) = match &previous {
+        Some(old) => (req.score - old.score, 0),
+        None => (req.score, 1),
+    };
+    let result = if score_delta == 0 && num_delta == 0 {
+        load_vod_score(&db, vod_id).await.map(Option::unwrap_or_default)
+    } else {
+        apply_score_change(&db, vod_id, score_delta, num_delta).await
+    };
+
+    match result {
+        Ok((vod_score, score_all, score_num)) => HttpResponse::Ok().json(json!({
+            "success": true,
+            "message": if previous.is_some() { "评分已修改" } else { "评分成功" },
+            "my_score": req.score,
+            "vod_score": vod_score,
+            "vod_score_all": score_all,
+            "vod_score_num": score_num
+        })),
+        Err(e) => {
+            eprintln!("更新视频评分失败: {}", e);
+            HttpResponse::InternalServerError().json(json!({"success": false, "message": "评分失败"}))
+        }
+    }
+}
+
+// 获取当前用户对视频的评分及视频评分汇总
+pub async fn get_my_rating(
+    user: AuthenticatedUser,
+    db: web::Data<Database>,
+    path: web::Path<String>,
+) -> impl Responder {
+    let Some(user_id) = user.user.id else {
+        return HttpResponse::Unauthorized().json(json!({"success": false, "message": "用户信息无效"}));
+    };
+    let vod_id = match ObjectId::parse_str(path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => {
+            return HttpResponse::BadRequest().json(json!({"success": false, "message": "无效的视频ID"}));
+        }
+    };
+
+    let rating = match db
+        .collection::<VodRating>(RATING_COLLECTION)
+        .find_one(doc! { "user_id": user_id, "vod_id": vod_id }, None)
+        .await
+    {
+        Ok(rating) => rating,
+        Err(e) => {
+            eprintln!("查询评分失败: {}", e);
+            return HttpResponse::InternalServerError().json(json!({"success": false, "message": "查询失败"}));
+        }
+    };
+
+    match load_vod_score(&db, vod_id).await {
+        Ok(Some((vod_score, score_all, score_num))) => HttpResponse::Ok().json(json!({
+            "success": true,
+            "my_score": rating.map(|r| r.score),
+            "vod_score": vod_score,
+            "vod_score_all": score_all,
+            "vod_score_num": score_num
+        })),
+        Ok(None) => HttpResponse::NotFound().json(json!({"success": false, "message": "视频不存在"})),
+        Err(e) => {
+            eprintln!("查询视频评分失败: {}", e);
+            HttpResponse::InternalServerError().json(json!({"success": false, "message": "查询失败"}))
+        }
+    }
+}
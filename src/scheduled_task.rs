@@ -1,101 +1,177 @@
 use mongodb::{Database, Collection as MongoCollection};
-use mongodb::bson::{doc, oid::ObjectId, DateTime};
+use mongodb::bson::{doc, oid::ObjectId, DateTime, Document};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use chrono::{DateTime as ChronoDateTime};
-use tokio::time::{sleep, interval};
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use chrono::{Local, TimeZone};
+use cron::Schedule;
+use tokio::time::interval;
 use futures::TryStreamExt;
+use crate::collect_handlers::{get_task_progress, start_batch_collect};
+use crate::jobs::{self, Job};
+use crate::leader_lock::{instance_id, LeaseLock};
 use crate::models::Collection;
 
+// 默认同时运行的采集源数量
+const DEFAULT_MAX_CONCURRENT: i32 = 2;
+// 并发数量上限
+const MAX_CONCURRENT_LIMIT: i32 = 10;
+// 定时采集主节点租约名称及有效期（秒），调度循环每分钟续约一次
+const SCHEDULER_LOCK_NAME: &str = "scheduled_collection";
+const SCHEDULER_LOCK_TTL_SECS: i64 = 150;
+
+fn default_max_concurrent() -> i32 {
+    DEFAULT_MAX_CONCURRENT
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ScheduledTaskConfig {
     #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
     pub id: Option<ObjectId>,
     pub enabled: bool,
-    pub interval_hours: i32,
+    #[serde(default = "default_max_concurrent")]
+    pub max_concurrent: i32, // 同时运行的采集源数量上限
     pub last_run: Option<DateTime>,
-    pub next_run: Option<DateTime>,
-    pub running_collections: Vec<String>, // 正在运行的采集源ID列表
-    pub current_collection_index: usize, // 当前正在执行的采集源索引
+    pub next_run: Option<DateTime>, // 所有采集源中最近的下次运行时间
     // 运行时状态字段
-    pub is_running: bool,                // 任务是否正在运行
-    pub current_task_id: Option<String>, // 当前任务ID
+    pub is_running: bool, // 定时任务是否已启动
     pub created_at: DateTime,
     pub updated_at: DateTime,
 }
 
+/// 定时采集执行记录，由定时触发的采集任务（Job）生成
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct TaskExecutionLog {
-    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
-    pub id: Option<ObjectId>,
     pub task_id: String,
     pub collection_id: String,
     pub collection_name: String,
-    pub status: String, // "running", "completed", "failed"
+    pub status: String, // "running", "completed", "failed", "stopped", "interrupted"
     pub started_at: DateTime,
     pub completed_at: Option<DateTime>,
     pub message: Option<String>,
     pub videos_collected: Option<i32>,
     pub errors: Option<String>,
+    pub attempts: i32,
+}
+
+impl From<&Job> for TaskExecutionLog {
+    fn from(job: &Job) -> Self {
+        Self {
+            task_id: job.job_id.clone(),
+            collection_id: job
+                .params
+                .get_object_id("collection_id")
+                .map(|id| id.to_hex())
+                .unwrap_or_default(),
+            collection_name: job.job_name.clone(),
+            status: job.status.clone(),
+            started_at: job.started_at.unwrap_or(job.created_at),
+            completed_at: job.finished_at,
+            message: Some(job.log.clone()),
+            videos_collected: Some(job.progress.success as i32),
+            errors: (job.status == jobs::JOB_STATUS_FAILED).then(|| job.log.clone()),
+            attempts: job.attempts,
+        }
+    }
+}
+
+/// 定时触发的采集任务
+fn scheduled_jobs_filter() -> Document {
+    doc! { "job_type": jobs::JOB_TYPE_COLLECT, "params.trigger": jobs::JOB_TRIGGER_SCHEDULE }
+}
+
+/// 解析 cron 表达式，支持标准 5 段（分 时 日 月 周）以及带秒的 6/7 段格式
+pub fn parse_cron(expr: &str) -> Result<Schedule, String> {
+    let expr = expr.trim();
+    let normalized = if expr.split_whitespace().count() == 5 {
+        format!("0 {}", expr)
+    } else {
+        expr.to_string()
+    };
+    Schedule::from_str(&normalized).map_err(|e| format!("无效的 cron 表达式 '{}': {}", expr, e))
+}
+
+/// 计算 cron 表达式在指定时间之后的下一次运行时间（按服务器本地时区）
+pub fn next_cron_run(expr: &str, after: DateTime) -> Option<DateTime> {
+    let schedule = parse_cron(expr).ok()?;
+    let after = Local.timestamp_millis_opt(after.timestamp_millis()).single()?;
+    schedule
+        .after(&after)
+        .next()
+        .map(|next| DateTime::from_millis(next.timestamp_millis()))
+}
+
+/// 采集窗口（Collection.collect_opt）对应的采集小时数：1=今日，2=昨日，3=本周，其余为全部
+fn collect_opt_hours(collect_opt: i32) -> Option<String> {
+    match collect_opt {
+        1 => Some("24".to_string()),
+        2 => Some("48".to_string()),
+        3 => Some("168".to_string()),
+        _ => None,
+    }
 }
 
 pub struct ScheduledTaskManager {
     db: Database,
     config_collection: MongoCollection<ScheduledTaskConfig>,
-    log_collection: MongoCollection<TaskExecutionLog>,
+    collections: MongoCollection<Collection>,
 }
 
 impl ScheduledTaskManager {
     pub fn new(db: Database) -> Self {
         let config_collection = db.collection::<ScheduledTaskConfig>("scheduled_task_configs");
-        let log_collection = db.collection::<TaskExecutionLog>("task_execution_logs");
-        
+        let collections = db.collection::<Collection>("collections");
+
         Self {
             db,
             config_collection,
-            log_collection,
+            collections,
         }
     }
 
     /// 初始化定时任务配置
     pub async fn initialize_config(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         let now = DateTime::now();
-        let next_run_millis = now.timestamp_millis() + (12 * 3600 * 1000);
-        let next_run = DateTime::from_millis(next_run_millis);
-        
+
         // 使用 upsert 操作，如果文档不存在则创建，如果存在则更新缺失的字段
         let update = doc! {
             "$setOnInsert": {
                 "enabled": false,
-                "interval_hours": 12,
+                "max_concurrent": DEFAULT_MAX_CONCURRENT,
                 "last_run": None::<DateTime>,
-                "next_run": next_run,
-                "running_collections": Vec::<String>::new(),
-                "current_collection_index": 0,
+                "next_run": None::<DateTime>,
                 "is_running": false,
-                "current_task_id": None::<String>,
                 "created_at": now,
             },
             "$set": {
                 "updated_at": now,
             }
         };
-        
+
         let options = mongodb::options::UpdateOptions::builder()
             .upsert(true)
             .build();
-            
+
         let result = self.config_collection.update_one(doc! {}, update, options).await?;
-        
+
         if result.upserted_id.is_some() {
             println!("✅ 定时任务配置初始化完成");
         } else {
             println!("✅ 定时任务配置已更新");
         }
-        
+
         Ok(())
     }
 
+    /// 启动调度循环：每个实例都运行，但只有持有主节点租约的实例会真正调度采集
+    pub fn start_scheduler_loop(&self) {
+        let db = self.db.clone();
+        tokio::spawn(async move {
+            let manager = ScheduledTaskManager::new(db);
+            manager.run_scheduled_task_loop().await;
+        });
+    }
+
     /// 获取当前配置
     pub async fn get_config(&self) -> Result<Option<ScheduledTaskConfig>, Box<dyn std::error::Error + Send + Sync>> {
         let config = self.config_collection.find_one(doc! {}, None).await?;
@@ -103,31 +179,18 @@ impl ScheduledTaskManager {
     }
 
     /// 更新配置
-    pub async fn update_config(&self, enabled: bool, interval_hours: Option<i32>) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+    pub async fn update_config(&self, enabled: bool, max_concurrent: Option<i32>) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
         let now = DateTime::now();
-        let interval = interval_hours.unwrap_or(12);
-        
-        // 不管是否启用，都计算下次运行时间，让用户能看到配置效果
-        let next_run = if enabled {
-            // 如果启用，从现在开始计算
-            let next_run_millis = now.timestamp_millis() + ((interval as i64) * 3600 * 1000);
-            Some(DateTime::from_millis(next_run_millis))
-        } else {
-            // 如果禁用，也计算一个时间，让用户知道配置生效后的运行时间
-            let next_run_millis = now.timestamp_millis() + ((interval as i64) * 3600 * 1000);
-            Some(DateTime::from_millis(next_run_millis))
-        };
+        let max_concurrent = max_concurrent
+            .unwrap_or(DEFAULT_MAX_CONCURRENT)
+            .clamp(1, MAX_CONCURRENT_LIMIT);
 
         let update = doc! {
             "$set": {
                 "enabled": enabled,
-                "interval_hours": interval,
-                "next_run": next_run,
+                "max_concurrent": max_concurrent,
                 "updated_at": now,
-                "running_collections": [],
-                "current_collection_index": 0,
                 "is_running": false,
-                "current_task_id": None::<String>
             }
         };
 
@@ -138,445 +201,217 @@ impl ScheduledTaskManager {
     /// 启动定时任务
     pub async fn start_scheduled_task(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         // 检查配置是否已经启用且正在运行
-        if let Some(config) = self.get_config().await? {
-            if config.enabled && config.is_running {
-                println!("⚠️ 定时任务已在运行中");
-                return Ok(());
-            }
+        if let Some(config) = self.get_config().await?
+            && config.enabled
+            && config.is_running
+        {
+            println!("⚠️ 定时任务已在运行中");
+            return Ok(());
         }
 
-        // 步骤1：生成任务ID并立即设置到数据库
-        println!("🔍 步骤1：设置任务运行状态到数据库...");
-        let immediate_task_id = ObjectId::new().to_hex();
-        let task_id_clone = immediate_task_id.clone();
         let now = DateTime::now();
-        
         let update = doc! {
             "$set": {
                 "enabled": true,
                 "is_running": true,
-                "current_task_id": task_id_clone,
                 "updated_at": now
             }
         };
-        
+
         let result = self.config_collection.update_one(doc! {}, update, None).await?;
         if result.modified_count == 0 {
             println!("❌ 更新任务状态失败");
             return Err("更新任务状态失败".into());
         }
-        
-        println!("🚀 定时采集任务已启动");
-
-        // 步骤2：启动定时任务循环（异步执行，不阻塞当前流程）
-        println!("🔍 步骤2：启动定时任务循环...");
-        let db = self.db.clone();
-        
-        tokio::spawn(async move {
-            let manager = ScheduledTaskManager::new(db);
-            manager.run_scheduled_task_loop().await;
-        });
 
-        // 立即执行一次采集任务
-        println!("🔄 立即执行一次采集任务...");
-        
-        // 步骤3：检查是否有启用的采集源
-        println!("🔍 步骤3：检查启用的采集源...");
-        let collections_collection = self.db.collection::<Collection>("collections");
-        let filter = doc! { "collect_status": 1 };
-        let enabled_collections_count = match collections_collection.count_documents(filter.clone(), None).await {
-            Ok(count) => {
-                println!("🔍 找到 {} 个启用的采集源", count);
-                count
-            }
-            Err(e) => {
-                eprintln!("❌ 查询采集源失败: {}", e);
-                // 清除任务状态
-                self.clear_task_status().await?;
-                return Ok(());
-            }
-        };
-        
-        if enabled_collections_count == 0 {
-            println!("⚠️ 没有启用的采集源，跳过立即执行");
-            self.clear_task_status().await?;
-            return Ok(());
-        }
-        
-        // 步骤4：获取配置
-        println!("🔍 步骤4：获取定时任务配置...");
-        let config = match self.get_config().await {
-            Ok(Some(config)) => {
-                println!("🔍 获取配置成功，启用状态: {}", config.enabled);
-                config
-            }
-            Ok(None) => {
-                println!("⚠️ 没有找到定时任务配置");
-                self.clear_task_status().await?;
-                return Ok(());
-            }
-            Err(e) => {
-                eprintln!("❌ 获取配置失败: {}", e);
-                self.clear_task_status().await?;
-                return Ok(());
-            }
-        };
-        
-        // 步骤5：执行立即采集任务
-        println!("🔍 步骤5：执行立即采集任务...");
-        match self.execute_immediate_collection(&config, &immediate_task_id).await {
-            Ok(_) => {
-                println!("✅ 立即执行采集任务完成");
-            }
-            Err(e) => {
-                eprintln!("❌ 立即执行采集任务失败: {}", e);
-                println!("错误详情: {:?}", e);
-            }
+        // 停止期间错过的运行不再补采，从现在起按各自的 cron 重新计算
+        self.collections
+            .update_many(doc! {}, doc! { "$unset": { "collect_next_run": "" } }, None)
+            .await?;
+
+        let scheduled = self
+            .collections
+            .count_documents(doc! { "collect_status": 1, "collect_cron_status": 1 }, None)
+            .await?;
+        if scheduled == 0 {
+            println!("⚠️ 没有启用定时采集的采集源，调度循环将等待配置");
         }
-        
-        // 步骤6：清除当前任务状态（但保持定时任务运行）
-        println!("🔍 步骤6：清除立即执行任务状态...");
-        self.clear_current_task().await?;
 
+        println!("🚀 定时采集任务已启动，{} 个采集源参与调度", scheduled);
         Ok(())
     }
 
     /// 停止定时任务
     pub async fn stop_scheduled_task(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         // 检查配置是否已经停止
-        if let Some(config) = self.get_config().await? {
-            if !config.enabled && !config.is_running {
-                println!("⚠️ 定时任务已停止");
-                return Ok(());
-            }
+        if let Some(config) = self.get_config().await?
+            && !config.enabled
+            && !config.is_running
+        {
+            println!("⚠️ 定时任务已停止");
+            return Ok(());
         }
-        
-        // 更新配置为禁用状态并清除运行状态
+
+        // 更新配置为禁用状态；已经开始的采集会继续执行到结束
         self.clear_task_status().await?;
-        
+
         println!("🛑 定时采集任务已停止");
         Ok(())
     }
 
-    /// 定时任务主循环
+    /// 定时任务主循环：每分钟续约主节点租约，维护后台任务并检查一次到期的采集源
     async fn run_scheduled_task_loop(&self) {
-        let mut interval_timer = interval(tokio::time::Duration::from_secs(60)); // 每分钟检查一次
+        let lock = LeaseLock::new(&self.db, SCHEDULER_LOCK_NAME, SCHEDULER_LOCK_TTL_SECS);
+        let mut interval_timer = interval(tokio::time::Duration::from_secs(60));
+        let mut is_leader = false;
 
         loop {
-            // 检查是否应该停止
-            if let Ok(Some(config)) = self.get_config().await {
-                if !config.enabled || !config.is_running {
-                    break;
-                }
-
-                // 检查是否到了执行时间
-                if let Some(next_run) = config.next_run {
-                    let now = ChronoDateTime::from_timestamp(DateTime::now().timestamp_millis() as i64 / 1000, 0).unwrap();
-                    let next_run_time = ChronoDateTime::from_timestamp(next_run.timestamp_millis() as i64 / 1000, 0).unwrap();
-                    
-                    if now >= next_run_time {
-                        // 执行采集任务
-                        if let Err(e) = self.execute_scheduled_collection(&config).await {
-                            eprintln!("❌ 执行定时采集任务失败: {}", e);
-                        }
-                    }
-                }
-            }
-
             interval_timer.tick().await;
-        }
-    }
-
-    /// 执行立即采集任务（跳过运行状态检查）
-    async fn execute_immediate_collection(&self, config: &ScheduledTaskConfig, task_id: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        println!("🔄 开始执行立即采集任务");
 
-        // 确保任务状态已设置
-        let update = doc! {
-            "$set": {
-                "current_task_id": task_id,
-                "updated_at": DateTime::now()
-            }
-        };
-        self.config_collection.update_one(doc! {}, update, None).await?;
-
-        // 获取所有启用的采集源
-        let collections_collection = self.db.collection::<Collection>("collections");
-        let filter = doc! { "collect_status": 1 };
-        let mut cursor = collections_collection.find(filter, None).await?;
-        
-        let mut collections: Vec<Collection> = Vec::new();
-        while let Ok(Some(collection)) = cursor.try_next().await {
-            collections.push(collection);
-        }
-
-        if collections.is_empty() {
-            println!("⚠️ 没有找到启用的采集源");
-            return Ok(());
-        }
-
-        // 按顺序执行采集任务
-        let total_collections = collections.len();
-        let mut total_videos_collected = 0;
-        let mut successful_collections = 0;
-        let mut failed_collections = 0;
-
-        for (index, collection) in collections.iter().enumerate() {
-            println!("📥 开始采集第 {}/{} 个采集源: {}", index + 1, total_collections, collection.collect_name);
-            
-            // 记录任务开始
-            let task_id = ObjectId::new().to_hex();
-            let log_entry = TaskExecutionLog {
-                id: None,
-                task_id: task_id.clone(),
-                collection_id: collection.id.clone().unwrap_or(ObjectId::new()).to_hex(),
-                collection_name: collection.collect_name.clone(),
-                status: "running".to_string(),
-                started_at: DateTime::now(),
-                completed_at: None,
-                message: Some(format!("开始采集 {}", collection.collect_name)),
-                videos_collected: None,
-                errors: None,
-            };
-
-            self.log_collection.insert_one(&log_entry, None).await?;
-            
-            // 使用当前采集任务ID
-            let final_task_id = task_id.to_string();
-            
-            // 更新数据库中的当前任务ID
-            let update = doc! {
-                "$set": {
-                    "current_task_id": final_task_id.clone(),
-                    "updated_at": DateTime::now()
+            // 其他实例持有租约时保持被动，只等待接管
+            let leader = match lock.try_acquire().await {
+                Ok(leader) => leader,
+                Err(e) => {
+                    eprintln!("❌ 获取定时采集租约失败: {}", e);
+                    false
                 }
             };
-            self.config_collection.update_one(doc! {}, update, None).await?;
-
-            // 执行采集（这里需要调用实际的采集逻辑）
-            match self.collect_videos_from_source(&collection).await {
-                Ok(videos_collected) => {
-                    total_videos_collected += videos_collected;
-                    successful_collections += 1;
-                    
-                    // 更新日志为完成状态
-                    let update = doc! {
-                        "$set": {
-                            "status": "completed",
-                            "completed_at": DateTime::now(),
-                            "videos_collected": videos_collected,
-                            "message": Some(format!("采集完成，获取 {} 个视频", videos_collected))
-                        }
-                    };
-                    self.log_collection.update_one(doc! { "task_id": &task_id }, update, None).await?;
-                    
-                    println!("✅ 采集完成: {} (获取 {} 个视频)", collection.collect_name, videos_collected);
-                }
-                Err(e) => {
-                    failed_collections += 1;
-                    
-                    // 更新日志为失败状态
-                    let update = doc! {
-                        "$set": {
-                            "status": "failed",
-                            "completed_at": DateTime::now(),
-                            "errors": Some(e.to_string()),
-                            "message": Some(format!("采集失败: {}", e))
-                        }
-                    };
-                    self.log_collection.update_one(doc! { "task_id": &task_id }, update, None).await?;
-                    
-                    eprintln!("❌ 采集失败: {} - {}", collection.collect_name, e);
+            if leader != is_leader {
+                if leader {
+                    println!("👑 实例 {} 成为定时采集主节点", instance_id());
+                } else {
+                    println!("⚠️ 实例 {} 不再是定时采集主节点", instance_id());
                 }
+                is_leader = leader;
+            }
+            if !leader {
+                continue;
             }
 
-            // 清除当前任务ID
-            let update = doc! {
-                "$set": {
-                    "current_task_id": None::<String>,
-                    "updated_at": DateTime::now()
-                }
+            // 标记中断的后台任务并自动重试
+            if let Err(e) = jobs::maintain_jobs(&self.db).await {
+                eprintln!("❌ 维护后台任务失败: {}", e);
+            }
+
+            // 检查定时任务是否已启动
+            let config = match self.get_config().await {
+                Ok(Some(config)) => config,
+                _ => continue,
             };
-            self.config_collection.update_one(doc! {}, update, None).await?;
+            if !config.enabled || !config.is_running {
+                continue;
+            }
 
-            // 采集间隔，避免请求过于频繁
-            sleep(tokio::time::Duration::from_secs(5)).await;
+            if let Err(e) = self.dispatch_due_collections(&config).await {
+                eprintln!("❌ 调度定时采集任务失败: {}", e);
+            }
         }
-
-        println!("🎉 立即采集任务完成: 成功 {}/{}, 共获取 {} 个视频", 
-            successful_collections, total_collections, total_videos_collected);
-
-        Ok(())
     }
 
-    /// 执行定时采集任务
-    async fn execute_scheduled_collection(&self, config: &ScheduledTaskConfig) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        println!("🔄 开始执行定时采集任务");
-
-        // 获取所有启用的采集源
-        let collections_collection = self.db.collection::<Collection>("collections");
-        let filter = doc! { "collect_status": 1 };
-        let mut cursor = collections_collection.find(filter, None).await?;
-        
-        let mut collections: Vec<Collection> = Vec::new();
-        while let Ok(Some(collection)) = cursor.try_next().await {
-            collections.push(collection);
-        }
-
-        if collections.is_empty() {
-            println!("⚠️ 没有找到启用的采集源");
-            println!("🔍 调试信息: 查询条件为 collect_status: 1");
-            return Ok(());
-        }
-
-        // 按顺序执行采集任务
-        let total_collections = collections.len();
-        let mut total_videos_collected = 0;
-        let mut successful_collections = 0;
-        let mut failed_collections = 0;
-
-        for (index, collection) in collections.iter().enumerate() {
-            // 检查任务是否还在运行
-            if let Ok(Some(config)) = self.get_config().await {
-                if !config.is_running {
-                    println!("⏹️ 定时任务已停止，中断采集");
-                    break;
-                }
-            }
+    /// 正在运行的定时采集任务
+    async fn running_scheduled_jobs(&self) -> Result<Vec<Job>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(jobs::running_jobs(&self.db, scheduled_jobs_filter()).await?)
+    }
 
-            println!("📥 开始采集第 {}/{} 个采集源: {}", index + 1, total_collections, collection.collect_name);
-            
-            // 记录任务开始
-            let task_id = ObjectId::new().to_hex();
-            let log_entry = TaskExecutionLog {
-                id: None,
-                task_id: task_id.clone(),
-                collection_id: collection.id.clone().unwrap_or(ObjectId::new()).to_hex(),
-                collection_name: collection.collect_name.clone(),
-                status: "running".to_string(),
-                started_at: DateTime::now(),
-                completed_at: None,
-                message: Some(format!("开始采集 {}", collection.collect_name)),
-                videos_collected: None,
-                errors: None,
+    /// 启动所有到期的采集源，同时运行的数量不超过 max_concurrent
+    async fn dispatch_due_collections(
+        &self,
+        config: &ScheduledTaskConfig,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let now = DateTime::now();
+        let max_concurrent = config.max_concurrent.max(1) as usize;
+        let collections: Vec<Collection> = self
+            .collections
+            .find(doc! { "collect_status": 1, "collect_cron_status": 1 }, None)
+            .await?
+            .try_collect()
+            .await?;
+
+        // 运行状态以任务记录为准，包括其他实例上尚未结束的任务
+        let mut running: HashSet<ObjectId> = self
+            .running_scheduled_jobs()
+            .await?
+            .iter()
+            .filter_map(|job| job.params.get_object_id("collection_id").ok())
+            .collect();
+
+        let mut dispatched = false;
+        let mut earliest_next_run: Option<DateTime> = None;
+
+        for collection in collections {
+            let Some(collection_id) = collection.id else {
+                continue;
             };
 
-            self.log_collection.insert_one(&log_entry, None).await?;
-            
-            // 使用当前采集任务ID
-            let final_task_id = task_id.to_string();
-            
-            // 更新数据库中的当前任务ID
-            let update = doc! {
-                "$set": {
-                    "current_task_id": final_task_id.clone(),
-                    "updated_at": DateTime::now()
-                }
-            };
-            self.config_collection.update_one(doc! {}, update, None).await?;
-
-            // 执行采集（这里需要调用实际的采集逻辑）
-            match self.collect_videos_from_source(&collection).await {
-                Ok(videos_collected) => {
-                    total_videos_collected += videos_collected;
-                    successful_collections += 1;
-                    
-                    // 更新日志为完成状态
-                    let update = doc! {
-                        "$set": {
-                            "status": "completed",
-                            "completed_at": DateTime::now(),
-                            "videos_collected": videos_collected,
-                            "message": Some(format!("采集完成，获取 {} 个视频", videos_collected))
-                        }
-                    };
-                    self.log_collection.update_one(doc! { "task_id": &task_id }, update, None).await?;
-                    
-                    println!("✅ 采集完成: {} (获取 {} 个视频)", collection.collect_name, videos_collected);
-                }
-                Err(e) => {
-                    failed_collections += 1;
-                    
-                    // 更新日志为失败状态
-                    let update = doc! {
-                        "$set": {
-                            "status": "failed",
-                            "completed_at": DateTime::now(),
-                            "errors": Some(e.to_string()),
-                            "message": Some(format!("采集失败: {}", e))
-                        }
+            // 新配置或修改过 cron 的采集源，从现在开始计算下次运行时间
+            let next_run = match collection.collect_next_run {
+                Some(next_run) => next_run,
+                None => {
+                    let Some(next_run) = next_cron_run(&collection.collect_cron, now) else {
+                        eprintln!("⚠️ 采集源 {} 的 cron 表达式无效: {}", collection.collect_name, collection.collect_cron);
+                        continue;
                     };
-                    self.log_collection.update_one(doc! { "task_id": &task_id }, update, None).await?;
-                    
-                    eprintln!("❌ 采集失败: {} - {}", collection.collect_name, e);
-                }
-            }
-
-            // 清除当前任务ID
-            let update = doc! {
-                "$set": {
-                    "current_task_id": None::<String>,
-                    "updated_at": DateTime::now()
+                    self.collections
+                        .update_one(doc! { "_id": collection_id }, doc! { "$set": { "collect_next_run": next_run } }, None)
+                        .await?;
+                    next_run
                 }
             };
-            self.config_collection.update_one(doc! {}, update, None).await?;
 
-            // 采集间隔，避免请求过于频繁
-            sleep(tokio::time::Duration::from_secs(5)).await;
-        }
-
-        // 更新配置中的执行时间
-        let now = DateTime::now();
-        let next_run_millis = now.timestamp_millis() + ((config.interval_hours as i64) * 3600 * 1000);
-        let next_run = DateTime::from_millis(next_run_millis);
-
-        let update = doc! {
-            "$set": {
-                "last_run": now,
-                "next_run": next_run,
-                "updated_at": now
+            if next_run > now {
+                earliest_next_run = Some(earliest_next_run.map_or(next_run, |t| t.min(next_run)));
+                continue;
             }
-        };
-        self.config_collection.update_one(doc! {}, update, None).await?;
 
-        println!("🎉 定时采集任务完成: 成功 {}/{}, 共获取 {} 个视频", 
-            successful_collections, total_collections, total_videos_collected);
+            // 上一轮还没结束的不重复启动；达到并发上限的保持到期状态，下一轮再调度
+            if running.contains(&collection_id) || running.len() >= max_concurrent {
+                continue;
+            }
+            running.insert(collection_id);
+
+            let following = next_cron_run(&collection.collect_cron, now);
+            self.collections
+                .update_one(
+                    doc! { "_id": collection_id },
+                    doc! { "$set": { "collect_last_run": now, "collect_next_run": following } },
+                    None,
+                )
+                .await?;
+            if let Some(following) = following {
+                earliest_next_run = Some(earliest_next_run.map_or(following, |t| t.min(following)));
+            }
+            dispatched = true;
+
+            println!("📥 定时采集启动: {}", collection.collect_name);
+            let db = self.db.clone();
+            tokio::spawn(async move {
+                let manager = ScheduledTaskManager::new(db);
+                manager.execute_collection(&collection).await;
+            });
+        }
 
+        let mut update = doc! { "next_run": earliest_next_run, "updated_at": now };
+        if dispatched {
+            update.insert("last_run", now);
+        }
+        self.config_collection.update_one(doc! {}, doc! { "$set": update }, None).await?;
         Ok(())
     }
 
-    /// 从指定采集源采集视频（调用真实的采集逻辑）
-    async fn collect_videos_from_source(&self, collection: &Collection) -> Result<i32, Box<dyn std::error::Error + Send + Sync>> {
-        use crate::collect_handlers::start_batch_collect;
-        
-        println!("🔄 正在从采集源采集视频: {}", collection.collect_name);
-        
-        // 生成任务ID
+    /// 以后台任务的形式执行单个采集源的定时采集，采集窗口取自采集源的 collect_opt
+    async fn execute_collection(&self, collection: &Collection) {
         let task_id = ObjectId::new().to_hex();
-        
-        // 调用真实的批量采集函数，专门采集当天数据（24小时内）
-        match start_batch_collect(&self.db, collection.clone(), Some("24".to_string()), task_id.clone()).await {
+        let hours = collect_opt_hours(collection.collect_opt);
+
+        println!("🔄 正在从采集源采集视频: {}", collection.collect_name);
+        match start_batch_collect(&self.db, collection.clone(), hours, task_id.clone(), false, jobs::JOB_TRIGGER_SCHEDULE).await {
             Ok(_) => {
-                // 获取采集结果
-                let videos_collected = self.get_videos_collected_count(&task_id).await.unwrap_or(0);
+                let videos_collected = get_task_progress(&self.db, &task_id)
+                    .await
+                    .map_or(0, |progress| progress.success);
                 println!("✅ 采集完成: {} (获取 {} 个视频)", collection.collect_name, videos_collected);
-                Ok(videos_collected)
             }
-            Err(e) => {
-                eprintln!("❌ 采集失败: {} - {}", collection.collect_name, e);
-                Err(e)
-            }
-        }
-    }
-    
-    /// 获取采集的视频数量
-    async fn get_videos_collected_count(&self, task_id: &str) -> Result<i32, Box<dyn std::error::Error + Send + Sync>> {
-        use crate::collect_handlers::get_task_progress;
-        
-        if let Some(progress) = get_task_progress(task_id).await {
-            Ok(progress.success as i32)
-        } else {
-            Ok(0)
+            Err(e) => eprintln!("❌ 采集失败: {} - {}", collection.collect_name, e),
         }
     }
 
@@ -587,20 +422,6 @@ impl ScheduledTaskManager {
             "$set": {
                 "enabled": false,
                 "is_running": false,
-                "current_task_id": None::<String>,
-                "updated_at": now
-            }
-        };
-        self.config_collection.update_one(doc! {}, update, None).await?;
-        Ok(())
-    }
-
-    /// 清除当前任务ID（但保持任务运行状态）
-    async fn clear_current_task(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        let now = DateTime::now();
-        let update = doc! {
-            "$set": {
-                "current_task_id": None::<String>,
                 "updated_at": now
             }
         };
@@ -611,59 +432,55 @@ impl ScheduledTaskManager {
     /// 获取任务状态
     pub async fn get_task_status(&self) -> Result<HashMap<String, serde_json::Value>, Box<dyn std::error::Error + Send + Sync>> {
         let mut status = HashMap::new();
-        
-        // 获取配置状态
-        let config_enabled = if let Some(config) = self.get_config().await? {
-            status.insert("enabled".to_string(), serde_json::Value::Bool(config.enabled));
-            status.insert("interval_hours".to_string(), serde_json::Value::Number(serde_json::Number::from(config.interval_hours)));
-            status.insert("last_run".to_string(), serde_json::Value::String(
-                config.last_run.map_or("从未运行".to_string(), |dt| format!("{}", dt.timestamp_millis()))
-            ));
-            status.insert("next_run".to_string(), serde_json::Value::String(
-                config.next_run.map_or("未设置".to_string(), |dt| format!("{}", dt.timestamp_millis()))
-            ));
-            config.enabled
-        } else {
-            false
-        };
 
-        // 获取当前运行状态：从数据库配置中获取
-        let is_running = if let Some(config) = self.get_config().await? {
-            config.is_running
-        } else {
-            false
+        let config = self.get_config().await?;
+        let (enabled, is_running) = match &config {
+            Some(config) => {
+                status.insert("max_concurrent".to_string(), serde_json::json!(config.max_concurrent));
+                status.insert("last_run".to_string(), serde_json::Value::String(
+                    config.last_run.map_or("从未运行".to_string(), |dt| format!("{}", dt.timestamp_millis()))
+                ));
+                status.insert("next_run".to_string(), serde_json::Value::String(
+                    config.next_run.map_or("未设置".to_string(), |dt| format!("{}", dt.timestamp_millis()))
+                ));
+                (config.enabled, config.is_running)
+            }
+            None => (false, false),
         };
+        status.insert("enabled".to_string(), serde_json::Value::Bool(enabled));
         status.insert("is_running".to_string(), serde_json::Value::Bool(is_running));
-        
-        // 添加调试信息
-        println!("🔍 状态检查 - 配置启用: {}, 数据库运行状态: {}", config_enabled, is_running);
-        
-        // 获取当前任务ID
-        if let Some(config) = self.get_config().await? {
-            if let Some(task_id) = config.current_task_id {
-                status.insert("current_task_id".to_string(), serde_json::Value::String(task_id.clone()));
-                
-                // 获取任务详情
-                if let Some(log) = self.log_collection.find_one(doc! { "task_id": &task_id }, None).await? {
-                    status.insert("current_collection".to_string(), serde_json::Value::String(log.collection_name));
-                    status.insert("current_status".to_string(), serde_json::Value::String(log.status));
-                    status.insert("task_started_at".to_string(), serde_json::Value::String(format!("{}", log.started_at.timestamp_millis())));
-                }
-            }
+
+        let scheduled = self
+            .collections
+            .count_documents(doc! { "collect_status": 1, "collect_cron_status": 1 }, None)
+            .await?;
+        status.insert("scheduled_collections".to_string(), serde_json::json!(scheduled));
+
+        // 多实例部署时的主节点信息
+        let lock = LeaseLock::new(&self.db, SCHEDULER_LOCK_NAME, SCHEDULER_LOCK_TTL_SECS);
+        let leader = lock.current().await?.filter(|info| info.expires_at > DateTime::now());
+        status.insert("instance_id".to_string(), serde_json::Value::String(instance_id().to_string()));
+        status.insert("leader_instance".to_string(), serde_json::json!(leader.as_ref().map(|info| info.instance_id.clone())));
+        status.insert("is_leader".to_string(), serde_json::Value::Bool(
+            leader.is_some_and(|info| info.instance_id == instance_id())
+        ));
+
+        // 正在运行的定时采集任务
+        let running_jobs = self.running_scheduled_jobs().await?;
+        if let Some(first) = running_jobs.first() {
+            let names: Vec<&str> = running_jobs.iter().map(|job| job.job_name.as_str()).collect();
+            status.insert("current_task_id".to_string(), serde_json::Value::String(first.job_id.clone()));
+            status.insert("current_collection".to_string(), serde_json::Value::String(names.join("、")));
+            status.insert("current_status".to_string(), serde_json::Value::String(
+                format!("运行中 {}/{}", running_jobs.len(), config.as_ref().map_or(DEFAULT_MAX_CONCURRENT, |c| c.max_concurrent))
+            ));
+            status.insert("task_started_at".to_string(), serde_json::Value::String(
+                format!("{}", first.started_at.unwrap_or(first.created_at).timestamp_millis())
+            ));
         }
 
         // 获取最近的执行记录
-        let mut logs = Vec::new();
-        let find_options = mongodb::options::FindOptions::builder()
-            .sort(doc! { "started_at": -1 })
-            .limit(10)
-            .build();
-        let mut cursor = self.log_collection.find(doc! {}, find_options).await?;
-        
-        while let Ok(Some(log)) = cursor.try_next().await {
-            logs.push(log);
-        }
-        
+        let logs = self.get_task_logs(Some(10)).await?;
         status.insert("recent_logs".to_string(), serde_json::Value::Array(
             logs.into_iter().map(|log| serde_json::json!({
                 "task_id": log.task_id,
@@ -681,18 +498,58 @@ impl ScheduledTaskManager {
 
     /// 获取任务执行日志
     pub async fn get_task_logs(&self, limit: Option<i32>) -> Result<Vec<TaskExecutionLog>, Box<dyn std::error::Error + Send + Sync>> {
-        let limit = limit.unwrap_or(50);
-        let find_options = mongodb::options::FindOptions::builder()
-            .sort(doc! { "started_at": -1 })
-            .limit(limit as i64)
-            .build();
-        let mut cursor = self.log_collection.find(doc! {}, find_options).await?;
-        
-        let mut logs = Vec::new();
-        while let Ok(Some(log)) = cursor.try_next().await {
-            logs.push(log);
+        let limit = limit.unwrap_or(50).max(1) as i64;
+        let (logs, _) = jobs::list_jobs(&self.db, scheduled_jobs_filter(), 1, limit).await?;
+        Ok(logs.iter().map(TaskExecutionLog::from).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local(y: i32, mo: u32, d: u32, h: u32, mi: u32, s: u32) -> DateTime {
+        let time = Local.with_ymd_and_hms(y, mo, d, h, mi, s).single().unwrap();
+        DateTime::from_millis(time.timestamp_millis())
+    }
+
+    #[test]
+    fn parse_cron_accepts_five_six_and_seven_fields() {
+        assert!(parse_cron("*/15 * * * *").is_ok());
+        assert!(parse_cron("  0 3 * * MON  ").is_ok());
+        assert!(parse_cron("30 0 */6 * * *").is_ok());
+        assert!(parse_cron("0 0 3 * * * 2030").is_ok());
+    }
+
+    #[test]
+    fn parse_cron_rejects_invalid_expressions() {
+        for expr in ["", "abc", "61 * * * *", "* * *"] {
+            let err = parse_cron(expr).unwrap_err();
+            assert!(err.contains("无效的 cron 表达式"), "{}", err);
         }
-        
-        Ok(logs)
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn next_cron_run_uses_local_time() {
+        let after = local(2024, 5, 1, 10, 7, 30);
+        assert_eq!(next_cron_run("*/15 * * * *", after), Some(local(2024, 5, 1, 10, 15, 0)));
+        assert_eq!(next_cron_run("0 3 * * *", after), Some(local(2024, 5, 2, 3, 0, 0)));
+        // 2024-05-01 是周三
+        assert_eq!(next_cron_run("0 9 * * MON", after), Some(local(2024, 5, 6, 9, 0, 0)));
+        assert_eq!(next_cron_run("30 * * * * *", after), Some(local(2024, 5, 1, 10, 8, 30)));
+    }
+
+    #[test]
+    fn next_cron_run_is_strictly_after() {
+        let at = local(2024, 5, 1, 10, 15, 0);
+        assert_eq!(next_cron_run("*/15 * * * *", at), Some(local(2024, 5, 1, 10, 30, 0)));
+        assert_eq!(next_cron_run("invalid", at), None);
+    }
+
+    #[test]
+    fn collect_opt_maps_to_hours() {
+        assert_eq!(collect_opt_hours(1).as_deref(), Some("24"));
+        assert_eq!(collect_opt_hours(3).as_deref(), Some("168"));
+        assert_eq!(collect_opt_hours(0), None);
+    }
+}
@@ -0,0 +1,908 @@
+use crate::audio_handlers::{published_filter, AUDIO_COLLECTION};
+use crate::image_handlers::{published_gallery_filter, IMAGE_COLLECTION};
+use crate::leader_lock::{LeaseGuard, LeaseLock};
+use crate::models::SearchEntry;
+use base64::{engine::general_purpose, Engine as _};
+use futures::stream::TryStreamExt;
+use mongodb::bson::{doc, oid::ObjectId, Bson, DateTime, Document};
+use mongodb::options::{AggregateOptions, FindOptions, ReplaceOptions};
+use mongodb::{Collection, Database};
+use pinyin::ToPinyin;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::time::{interval, Duration};
+
+pub const SEARCH_COLLECTION: &str = "search_entries";
+// 前台搜索每页数量
+pub const SEARCH_PAGE_SIZE: u64 = 20;
+// 可搜索的内容类型及显示名称
+pub const SEARCH_KINDS: [(&str, &str); 4] = [
+    ("vod", "影视"),
+    ("art", "文章"),
+    ("gallery", "图集"),
+    ("audio", "音频"),
+];
+
+// 分词或字段变化时递增，已有条目会在下次同步时全部重建
+const SEARCH_INDEX_VERSION: &str = "2";
+const SYNC_INTERVAL_SECS: u64 = 600;
+const SYNC_LOCK_NAME: &str = "search_index_sync";
+const SYNC_LOCK_TTL_SECS: i64 = 120;
+const SYNC_BATCH_SIZE: usize = 500;
+// 前缀词最大长度，查询词超过该长度时截断
+const MAX_TERM_LEN: usize = 24;
+// 标题中参与生成拼音的最大汉字数
+const MAX_PINYIN_CHARS: usize = 16;
+// 每段汉字只从前几个音节开始生成拼音前缀词，避免长标题生成上百个词
+const MAX_SYLLABLE_STARTS: usize = 3;
+const MAX_QUERY_TERMS: usize = 12;
+const SUBTITLE_MAX_CHARS: usize = 100;
+
+// 同一进程内同时只运行一次同步，避免定时任务和手动同步重叠
+static SYNCING: AtomicBool = AtomicBool::new(false);
+
+/// 当前进程是否正在同步搜索索引
+pub fn is_syncing() -> bool {
+    SYNCING.load(Ordering::Relaxed)
+}
+
+pub fn kind_label(kind: &str) -> &'static str {
+    SEARCH_KINDS
+        .iter()
+        .find(|(k, _)| *k == kind)
+        .map(|(_, label)| *label)
+        .unwrap_or("其他")
+}
+
+pub fn detail_url(kind: &str, id: &ObjectId) -> String {
+    match kind {
+        "vod" => format!("/detail/{}", id.to_hex()),
+        "art" => format!("/art/detail/{}", id.to_hex()),
+        "gallery" => format!("/gallery/{}", id.to_hex()),
+        "audio" => format!("/audio/{}", id.to_hex()),
+        _ => "/".to_string(),
+    }
+}
+
+// --- Tokenizer ---
+
+enum Segment {
+    Cjk(Vec<char>),
+    Word(Vec<char>),
+}
+
+// 全角转半角并转小写，保持一个字符对应一个字符，便于高亮时定位
+fn normalize_char(c: char) -> char {
+    let c = match c {
+        '\u{3000}' => ' ',
+        '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+        'ü' | 'Ü' => 'v',
+        _ => c,
+    };
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30FF}'
+        | '\u{3400}'..='\u{4DBF}'
+        | '\u{4E00}'..='\u{9FFF}'
+        | '\u{AC00}'..='\u{D7AF}'
+        | '\u{F900}'..='\u{FAFF}')
+}
+
+// 按汉字连续段和字母数字单词切分，其余字符作为分隔符
+fn segments(text: &str) -> Vec<Segment> {
+    let mut result = Vec::new();
+    let mut cjk = Vec::new();
+    let mut word = Vec::new();
+    for c in text.chars().map(normalize_char) {
+        if is_cjk(c) {
+            if !word.is_empty() {
+                result.push(Segment::Word(std::mem::take(&mut word)));
+            }
+            cjk.push(c);
+        } else if c.is_alphanumeric() {
+            if !cjk.is_empty() {
+                result.push(Segment::Cjk(std::mem::take(&mut cjk)));
+            }
+            word.push(c);
+        } else {
+            if !cjk.is_empty() {
+                result.push(Segment::Cjk(std::mem::take(&mut cjk)));
+            }
+            if !word.is_empty() {
+                result.push(Segment::Word(std::mem::take(&mut word)));
+            }
+        }
+    }
+    if !cjk.is_empty() {
+        result.push(Segment::Cjk(cjk));
+    }
+    if !word.is_empty() {
+        result.push(Segment::Word(word));
+    }
+    result
+}
+
+/// 去掉空白和标点后的规范化文本，用于标题完全匹配和前缀匹配
+pub fn normalize_key(text: &str) -> String {
+    text.chars()
+        .map(normalize_char)
+        .filter(|c| c.is_alphanumeric())
+        .collect()
+}
+
+fn add_prefixes(chars: impl Iterator<Item = char>, terms: &mut BTreeSet<String>) {
+    let mut prefix = String::new();
+    for c in chars.take(MAX_TERM_LEN) {
+        prefix.push(c);
+        terms.insert(prefix.clone());
+    }
+}
+
+// 汉字生成单字和二元词，单词生成前缀词
+fn add_text_terms(text: &str, terms: &mut BTreeSet<String>) {
+    for segment in segments(text) {
+        match segment {
+            Segment::Cjk(chars) => {
+                terms.extend(chars.iter().map(char::to_string));
+                terms.extend(chars.windows(2).map(|pair| pair.iter().collect::<String>()));
+            }
+            Segment::Word(chars) => add_prefixes(chars.into_iter(), terms),
+        }
+    }
+}
+
+// 从前几个音节开始生成全拼和首字母的前缀词，支持 qingyunian、yunian、qyn 等查询
+fn add_syllable_terms(syllables: &[&str], terms: &mut BTreeSet<String>) {
+    for start in 0..syllables.len().min(MAX_SYLLABLE_STARTS) {
+        let rest = &syllables[start..];
+        add_prefixes(rest.iter().flat_map(|s| s.chars()).map(normalize_char), terms);
+        add_prefixes(rest.iter().filter_map(|s| s.chars().next()), terms);
+    }
+}
+
+fn add_pinyin_terms(text: &str, terms: &mut BTreeSet<String>) {
+    let mut taken = 0;
+    for segment in segments(text) {
+        let Segment::Cjk(chars) = segment else {
+            continue;
+        };
+        // 没有拼音的字符（如假名）打断音节序列
+        let mut syllables = Vec::new();
+        for c in chars {
+            if taken >= MAX_PINYIN_CHARS {
+                break;
+            }
+            taken += 1;
+            match c.to_pinyin() {
+                Some(pinyin) => syllables.push(pinyin.plain()),
+                None => {
+                    add_syllable_terms(&syllables, terms);
+                    syllables.clear();
+                }
+            }
+        }
+        add_syllable_terms(&syllables, terms);
+    }
+}
+
+/// 查询词：汉字按二元词切分（单字直接使用），单词整体作为前缀匹配，所有词都需命中
+pub fn query_terms(query: &str) -> Vec<String> {
+    let mut terms: Vec<String> = Vec::new();
+    for segment in segments(query) {
+        let candidates: Vec<String> = match segment {
+            Segment::Cjk(chars) if chars.len() == 1 => vec![chars[0].to_string()],
+            Segment::Cjk(chars) => chars.windows(2).map(|pair| pair.iter().collect()).collect(),
+            Segment::Word(chars) => vec![chars.into_iter().take(MAX_TERM_LEN).collect()],
+        };
+        for term in candidates {
+            if !terms.contains(&term) {
+                terms.push(term);
+            }
+        }
+    }
+    terms.truncate(MAX_QUERY_TERMS);
+    terms
+}
+
+// 高亮片段：汉字按二元词（单字直接使用），单词整体匹配
+fn highlight_needles(query: &str) -> Vec<Vec<char>> {
+    segments(query)
+        .into_iter()
+        .flat_map(|segment| match segment {
+            Segment::Cjk(chars) if chars.len() > 1 => {
+                chars.windows(2).map(<[char]>::to_vec).collect::<Vec<_>>()
+            }
+            Segment::Cjk(chars) | Segment::Word(chars) => vec![chars],
+        })
+        .collect()
+}
+
+/// 转义文本并用 <mark> 包裹命中查询的部分
+pub fn highlight(text: &str, query: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let normalized: Vec<char> = chars.iter().map(|&c| normalize_char(c)).collect();
+    let mut marked = vec![false; chars.len()];
+    for needle in highlight_needles(query) {
+        if needle.is_empty() || needle.len() > normalized.len() {
+            continue;
+        }
+        for start in 0..=normalized.len() - needle.len() {
+            if normalized[start..start + needle.len()] == needle[..] {
+                marked[start..start + needle.len()].fill(true);
+            }
+        }
+    }
+
+    let mut html = String::new();
+    let mut start = 0;
+    while start < chars.len() {
+        let is_marked = marked[start];
+        let mut end = start;
+        while end < chars.len() && marked[end] == is_marked {
+            end += 1;
+        }
+        let segment: String = chars[start..end].iter().collect();
+        let escaped = html_escape::encode_text(&segment);
+        if is_marked {
+            html.push_str("<mark>");
+            html.push_str(&escaped);
+            html.push_str("</mark>");
+        } else {
+            html.push_str(&escaped);
+        }
+        start = end;
+    }
+    html
+}
+
+// --- Index entries ---
+
+// 从源文档中提取的可搜索内容
+struct SearchSource {
+    title: String,
+    extra: Vec<String>,
+    subtitle: String,
+    cover: Option<String>,
+    hits: i64,
+    updated_at: DateTime,
+}
+
+fn doc_str(doc: &Document, key: &str) -> String {
+    doc.get_str(key).unwrap_or_default().trim().to_string()
+}
+
+fn doc_strings(doc: &Document, key: &str) -> Vec<String> {
+    doc.get_array(key)
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(Bson::as_str)
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn doc_i64(doc: &Document, key: &str) -> i64 {
+    match doc.get(key) {
+        Some(Bson::Int32(v)) => *v as i64,
+        Some(Bson::Int64(v)) => *v,
+        Some(Bson::Double(v)) => *v as i64,
+        Some(Bson::String(v)) => v.parse().unwrap_or(0),
+        _ => 0,
+    }
+}
+
+fn doc_datetime(doc: &Document, key: &str) -> DateTime {
+    doc.get_datetime(key)
+        .copied()
+        .unwrap_or_else(|_| DateTime::from_millis(0))
+}
+
+fn cover_url(doc: &Document) -> Option<String> {
+    doc.get_document("cover")
+        .ok()
+        .and_then(|cover| cover.get_str("url").ok())
+        .filter(|url| !url.is_empty())
+        .map(str::to_string)
+}
+
+fn non_empty(value: String) -> Option<String> {
+    Some(value).filter(|v| !v.is_empty())
+}
+
+// 各类型的源集合、公开内容过滤条件和需要读取的字段
+fn source_query(kind: &str) -> Option<(&'static str, Document, Document)> {
+    match kind {
+        "vod" => Some((
+            "vods",
+            doc! { "vod_status": 1 },
+            doc! { "vod_name": 1, "vod_actor": 1, "vod_director": 1, "vod_pic": 1, "vod_hits": 1, "vod_pubdate": 1 },
+        )),
+        "art" => Some((
+            "arts",
+            doc! { "art_status": 1 },
+            doc! { "art_name": 1, "art_author": 1, "art_tag": 1, "art_pic": 1, "art_hits": 1, "art_pubdate": 1 },
+        )),
+        "gallery" => Some((
+            IMAGE_COLLECTION,
+            published_gallery_filter(),
+            doc! { "title": 1, "en_title": 1, "artists": 1, "tags": 1, "category": 1, "cover": 1, "likes": 1, "updated_at": 1 },
+        )),
+        "audio" => Some((
+            AUDIO_COLLECTION,
+            published_filter(),
+            doc! { "title": 1, "en_title": 1, "tags": 1, "category": 1, "cover": 1, "updated_at": 1 },
+        )),
+        _ => None,
+    }
+}
+
+fn extract_source(kind: &str, doc: &Document) -> Option<SearchSource> {
+    let source = match kind {
+        "vod" => {
+            let actor = doc_str(doc, "vod_actor");
+            SearchSource {
+                title: doc_str(doc, "vod_name"),
+                extra: vec![actor.clone(), doc_str(doc, "vod_director")],
+                subtitle: actor,
+                cover: non_empty(doc_str(doc, "vod_pic")),
+                hits: doc_i64(doc, "vod_hits"),
+                updated_at: doc_datetime(doc, "vod_pubdate"),
+            }
+        }
+        "art" => {
+            let author = doc_str(doc, "art_author");
+            SearchSource {
+                title: doc_str(doc, "art_name"),
+                extra: vec![author.clone(), doc_str(doc, "art_tag")],
+                subtitle: author,
+                cover: non_empty(doc_str(doc, "art_pic")),
+                hits: doc_i64(doc, "art_hits"),
+                updated_at: doc_datetime(doc, "art_pubdate"),
+            }
+        }
+        "gallery" => {
+            let artists = doc_str(doc, "artists");
+            let category = doc_str(doc, "category");
+            let mut extra = vec![doc_str(doc, "en_title"), artists.clone(), category.clone()];
+            extra.extend(doc_strings(doc, "tags"));
+            SearchSource {
+                title: doc_str(doc, "title"),
+                extra,
+                subtitle: if artists.is_empty() { category } else { artists },
+                cover: cover_url(doc),
+                hits: doc_i64(doc, "likes"),
+                updated_at: doc_datetime(doc, "updated_at"),
+            }
+        }
+        "audio" => {
+            let category = doc_str(doc, "category");
+            let mut extra = vec![doc_str(doc, "en_title"), category.clone()];
+            extra.extend(doc_strings(doc, "tags"));
+            SearchSource {
+                title: doc_str(doc, "title"),
+                extra,
+                subtitle: category,
+                cover: cover_url(doc),
+                hits: 0,
+                updated_at: doc_datetime(doc, "updated_at"),
+            }
+        }
+        _ => return None,
+    };
+    Some(source).filter(|s| !s.title.is_empty())
+}
+
+fn build_entry(kind: &str, doc: &Document) -> Option<SearchEntry> {
+    let id = doc.get_object_id("_id").ok()?;
+    let source = extract_source(kind, doc)?;
+
+    let mut title_terms = BTreeSet::new();
+    add_text_terms(&source.title, &mut title_terms);
+    add_pinyin_terms(&source.title, &mut title_terms);
+    let mut terms = title_terms.clone();
+    for text in &source.extra {
+        add_text_terms(text, &mut terms);
+    }
+    // 热度按 log2 分档，点击数小幅变化不会触发重建
+    let popularity = (source.hits.max(0) as u64 + 1).ilog2() as i32;
+
+    let mut hasher = Sha256::new();
+    for part in [
+        SEARCH_INDEX_VERSION,
+        &source.title,
+        &source.extra.join("\u{1f}"),
+        &source.subtitle,
+        source.cover.as_deref().unwrap_or_default(),
+        &popularity.to_string(),
+        &source.updated_at.timestamp_millis().to_string(),
+    ] {
+        hasher.update(part.as_bytes());
+        hasher.update([0u8]);
+    }
+
+    Some(SearchEntry {
+        id,
+        kind: kind.to_string(),
+        title_key: normalize_key(&source.title),
+        title: source.title,
+        subtitle: source.subtitle,
+        cover: source.cover,
+        terms: terms.into_iter().collect(),
+        title_terms: title_terms.into_iter().collect(),
+        popularity,
+        fingerprint: general_purpose::STANDARD.encode(hasher.finalize()),
+        source_updated_at: source.updated_at,
+        indexed_at: DateTime::now(),
+    })
+}
+
+// --- Query ---
+
+#[derive(Debug, Serialize)]
+pub struct SearchHit {
+    pub kind: String,
+    pub kind_label: &'static str,
+    pub id: String,
+    pub url: String,
+    pub title: String,
+    pub title_html: String,
+    pub subtitle_html: String,
+    pub cover: Option<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct SearchResults {
+    pub hits: Vec<SearchHit>,
+    pub total: u64,
+    pub kind_counts: HashMap<String, u64>,
+}
+
+// 相关度：标题命中的查询词数量、标题完全匹配、标题前缀匹配，再加上热度
+fn score_stage(terms: &[String], key: &str) -> Document {
+    doc! {
+        "$addFields": {
+            "score": {
+                "$add": [
+                    { "$multiply": [{ "$size": { "$setIntersection": ["$title_terms", terms.to_vec()] } }, 10] },
+                    { "$cond": [{ "$eq": ["$title_key", key] }, 100, 0] },
+                    { "$cond": [{ "$eq": [{ "$indexOfCP": ["$title_key", key] }, 0] }, 30, 0] },
+                    "$popularity"
+                ]
+            }
+        }
+    }
+}
+
+/// 在指定类型中搜索，返回当前页结果、当前类型总数和各类型命中数
+pub async fn search(
+    db: &Database,
+    query: &str,
+    kinds: &[&str],
+    kind: Option<&str>,
+    page: u64,
+    page_size: u64,
+) -> mongodb::error::Result<SearchResults> {
+    let terms = query_terms(query);
+    if terms.is_empty() || kinds.is_empty() {
+        return Ok(SearchResults::default());
+    }
+    let key = normalize_key(query);
+    let kind_filter = kind.map(|k| doc! { "kind": k }).unwrap_or_default();
+
+    let pipeline = vec![
+        doc! { "$match": { "terms": { "$all": terms.clone() }, "kind": { "$in": kinds.to_vec() } } },
+        score_stage(&terms, &key),
+        doc! { "$project": { "terms": 0, "title_terms": 0 } },
+        doc! {
+            "$facet": {
+                "kinds": [{ "$group": { "_id": "$kind", "count": { "$sum": 1 } } }],
+                "items": [
+                    { "$match": kind_filter },
+                    { "$sort": { "score": -1, "source_updated_at": -1, "_id": -1 } },
+                    { "$skip": ((page.max(1) - 1) * page_size) as i64 },
+                    { "$limit": page_size as i64 }
+                ]
+            }
+        },
+    ];
+    let options = AggregateOptions::builder().allow_disk_use(true).build();
+    let mut cursor = db
+        .collection::<Document>(SEARCH_COLLECTION)
+        .aggregate(pipeline, options)
+        .await?;
+    let Some(result) = cursor.try_next().await? else {
+        return Ok(SearchResults::default());
+    };
+
+    let kind_counts: HashMap<String, u64> = result
+        .get_array("kinds")
+        .map(|groups| {
+            groups
+                .iter()
+                .filter_map(Bson::as_document)
+                .filter_map(|g| Some((g.get_str("_id").ok()?.to_string(), doc_i64(g, "count") as u64)))
+                .collect()
+        })
+        .unwrap_or_default();
+    let total = match kind {
+        Some(k) => kind_counts.get(k).copied().unwrap_or(0),
+        None => kind_counts.values().sum(),
+    };
+
+    let hits = result
+        .get_array("items")
+        .map(|items| items.iter().filter_map(Bson::as_document).cloned().collect::<Vec<_>>())
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|item| mongodb::bson::from_document::<SearchEntry>(item).ok())
+        .map(|entry| {
+            let subtitle: String = entry.subtitle.chars().take(SUBTITLE_MAX_CHARS).collect();
+            SearchHit {
+                kind_label: kind_label(&entry.kind),
+                id: entry.id.to_hex(),
+                url: detail_url(&entry.kind, &entry.id),
+                title_html: highlight(&entry.title, query),
+                subtitle_html: highlight(&subtitle, query),
+                title: entry.title,
+                cover: entry.cover,
+                kind: entry.kind,
+            }
+        })
+        .collect();
+
+    Ok(SearchResults { hits, total, kind_counts })
+}
+
+/// 按相关度返回命中内容的ID，供采集接口等只需要ID的场景使用
+pub async fn matching_ids(
+    db: &Database,
+    query: &str,
+    kind: &str,
+    limit: i64,
+) -> mongodb::error::Result<Vec<ObjectId>> {
+    let terms = query_terms(query);
+    if terms.is_empty() {
+        return Ok(Vec::new());
+    }
+    let key = normalize_key(query);
+    let pipeline = vec![
+        doc! { "$match": { "terms": { "$all": terms.clone() }, "kind": kind } },
+        score_stage(&terms, &key),
+        doc! { "$sort": { "score": -1, "source_updated_at": -1 } },
+        doc! { "$limit": limit },
+        doc! { "$project": { "_id": 1 } },
+    ];
+    let results: Vec<Document> = db
+        .collection::<Document>(SEARCH_COLLECTION)
+        .aggregate(pipeline, None)
+        .await?
+        .try_collect()
+        .await?;
+    Ok(results
+        .iter()
+        .filter_map(|d| d.get_object_id("_id").ok())
+        .collect())
+}
+
+/// 删除指定内容的索引条目，内容被删除时调用
+pub async fn remove_entries(db: &Database, ids: &[ObjectId]) -> mongodb::error::Result<u64> {
+    if ids.is_empty() {
+        return Ok(0);
+    }
+    let result = db
+        .collection::<Document>(SEARCH_COLLECTION)
+        .delete_many(doc! { "_id": { "$in": ids } }, None)
+        .await?;
+    Ok(result.deleted_count)
+}
+
+/// 内容写入后立即更新其索引条目：公开内容重建条目，已不再公开的内容删除条目
+/// 失败只记录日志，遗漏的条目由定期同步修复
+pub async fn refresh_entries(db: &Database, kind: &str, ids: &[ObjectId]) {
+    if ids.is_empty() {
+        return;
+    }
+    if let Err(e) = refresh_entries_inner(db, kind, ids).await {
+        eprintln!("❌ 更新搜索索引失败 {}: {}", kind, e);
+    }
+}
+
+async fn refresh_entries_inner(db: &Database, kind: &str, ids: &[ObjectId]) -> mongodb::error::Result<()> {
+    let Some((collection, mut filter, projection)) = source_query(kind) else {
+        return Ok(());
+    };
+    let entries = db.collection::<SearchEntry>(SEARCH_COLLECTION);
+    for chunk in ids.chunks(SYNC_BATCH_SIZE) {
+        filter.insert("_id", doc! { "$in": chunk.to_vec() });
+        let options = FindOptions::builder().projection(projection.clone()).build();
+        let sources: Vec<Document> = db
+            .collection::<Document>(collection)
+            .find(filter.clone(), options)
+            .await?
+            .try_collect()
+            .await?;
+        let batch: Vec<SearchEntry> = sources.iter().filter_map(|doc| build_entry(kind, doc)).collect();
+        let indexed: Vec<ObjectId> = batch.iter().map(|e| e.id).collect();
+        let hidden: Vec<ObjectId> = chunk.iter().filter(|id| !indexed.contains(id)).copied().collect();
+        write_entries(&entries, batch).await?;
+        remove_entries(db, &hidden).await?;
+    }
+    Ok(())
+}
+
+/// 各类型的索引条目数
+pub async fn entry_counts(db: &Database) -> mongodb::error::Result<HashMap<String, u64>> {
+    let pipeline = vec![doc! { "$group": { "_id": "$kind", "count": { "$sum": 1 } } }];
+    let groups: Vec<Document> = db
+        .collection::<Document>(SEARCH_COLLECTION)
+        .aggregate(pipeline, None)
+        .await?
+        .try_collect()
+        .await?;
+    Ok(groups
+        .iter()
+        .filter_map(|g| Some((g.get_str("_id").ok()?.to_string(), doc_i64(g, "count") as u64)))
+        .collect())
+}
+
+// --- Sync ---
+
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct SyncStats {
+    pub scanned: u64,
+    pub indexed: u64,
+    pub removed: u64,
+}
+
+/// 搜索索引同步：写入路径会即时更新条目，这里定期扫描公开内容作为修复，
+/// 按内容指纹更新变化的条目并删除已下架的条目
+#[derive(Clone)]
+pub struct SearchIndexer {
+    db: Database,
+}
+
+impl SearchIndexer {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// 启动后台同步，第一次在启动时立即执行
+    pub fn start(&self) {
+        let indexer = self.clone();
+        tokio::spawn(async move {
+            let mut timer = interval(Duration::from_secs(SYNC_INTERVAL_SECS));
+            loop {
+                timer.tick().await;
+                match indexer.sync_with_lock().await {
+                    Ok(Some(stats)) if stats.indexed > 0 || stats.removed > 0 => {
+                        println!(
+                            "✅ 搜索索引同步完成: 扫描 {} 条，更新 {} 条，删除 {} 条",
+                            stats.scanned, stats.indexed, stats.removed
+                        );
+                    }
+                    Ok(_) => {}
+                    Err(e) => eprintln!("❌ 同步搜索索引失败: {}", e),
+                }
+            }
+        });
+    }
+
+    /// 持有租约时执行同步，其他实例或本进程正在同步时返回 None
+    pub async fn sync_with_lock(&self) -> mongodb::error::Result<Option<SyncStats>> {
+        if SYNCING.swap(true, Ordering::AcqRel) {
+            return Ok(None);
+        }
+        let result = async {
+            let lock = LeaseLock::new(&self.db, SYNC_LOCK_NAME, SYNC_LOCK_TTL_SECS);
+            let Some(guard) = lock.acquire_guard().await? else {
+                return Ok(None);
+            };
+            self.sync(&guard).await.map(Some)
+        }
+        .await;
+        SYNCING.store(false, Ordering::Release);
+        result
+    }
+
+    // 租约丢失后其他实例会接管同步，本实例在当前批次结束后停止
+    async fn sync(&self, guard: &LeaseGuard) -> mongodb::error::Result<SyncStats> {
+        let mut total = SyncStats::default();
+        for (kind, _) in SEARCH_KINDS {
+            if guard.is_lost() {
+                eprintln!("⚠️ 搜索索引同步租约已丢失，停止同步");
+                break;
+            }
+            let stats = self.sync_kind(kind, guard).await?;
+            total.scanned += stats.scanned;
+            total.indexed += stats.indexed;
+            total.removed += stats.removed;
+        }
+        Ok(total)
+    }
+
+    // 源内容和索引条目都按 _id 顺序读取并归并比较，内存中只保留待写入的一批条目
+    async fn sync_kind(&self, kind: &str, guard: &LeaseGuard) -> mongodb::error::Result<SyncStats> {
+        let Some((collection, filter, projection)) = source_query(kind) else {
+            return Ok(SyncStats::default());
+        };
+        let entries = self.db.collection::<SearchEntry>(SEARCH_COLLECTION);
+
+        let options = FindOptions::builder()
+            .projection(doc! { "fingerprint": 1 })
+            .sort(doc! { "_id": 1 })
+            .batch_size(SYNC_BATCH_SIZE as u32)
+            .build();
+        let mut existing = self
+            .db
+            .collection::<Document>(SEARCH_COLLECTION)
+            .find(doc! { "kind": kind }, options)
+            .await?;
+        let mut next_existing = existing.try_next().await?;
+
+        let mut stats = SyncStats::default();
+        let mut changed = Vec::new();
+        let mut stale = Vec::new();
+        let options = FindOptions::builder()
+            .projection(projection)
+            .sort(doc! { "_id": 1 })
+            .batch_size(SYNC_BATCH_SIZE as u32)
+            .build();
+        let mut cursor = self
+            .db
+            .collection::<Document>(collection)
+            .find(filter, options)
+            .await?;
+        while let Some(source) = cursor.try_next().await? {
+            let Some(entry) = build_entry(kind, &source) else {
+                continue;
+            };
+            stats.scanned += 1;
+
+            // 排在当前内容之前的条目已没有对应的公开内容
+            let mut fingerprint = None;
+            while let Some(current) = next_existing.take() {
+                let id = current.get_object_id("_id").ok();
+                match id.map(|id| id.cmp(&entry.id)) {
+                    Some(std::cmp::Ordering::Greater) => {
+                        next_existing = Some(current);
+                        break;
+                    }
+                    Some(std::cmp::Ordering::Equal) => {
+                        fingerprint = current.get_str("fingerprint").ok().map(str::to_string);
+                        next_existing = existing.try_next().await?;
+                        break;
+                    }
+                    _ => {
+                        stale.extend(id);
+                        next_existing = existing.try_next().await?;
+                    }
+                }
+            }
+
+            if fingerprint.as_deref() != Some(entry.fingerprint.as_str()) {
+                changed.push(entry);
+            }
+            // 每写入一批前确认租约仍然有效
+            if (changed.len() >= SYNC_BATCH_SIZE || stale.len() >= SYNC_BATCH_SIZE) && guard.is_lost() {
+                return Ok(stats);
+            }
+            if changed.len() >= SYNC_BATCH_SIZE {
+                stats.indexed += write_entries(&entries, std::mem::take(&mut changed)).await?;
+            }
+            if stale.len() >= SYNC_BATCH_SIZE {
+                stats.removed += remove_entries(&self.db, &std::mem::take(&mut stale)).await?;
+            }
+        }
+        if guard.is_lost() {
+            return Ok(stats);
+        }
+        stats.indexed += write_entries(&entries, changed).await?;
+
+        while let Some(current) = next_existing {
+            stale.extend(current.get_object_id("_id").ok());
+            if stale.len() >= SYNC_BATCH_SIZE {
+                if guard.is_lost() {
+                    return Ok(stats);
+                }
+                stats.removed += remove_entries(&self.db, &std::mem::take(&mut stale)).await?;
+            }
+            next_existing = existing.try_next().await?;
+        }
+        if guard.is_lost() {
+            return Ok(stats);
+        }
+        stats.removed += remove_entries(&self.db, &stale).await?;
+        Ok(stats)
+    }
+}
+
+// 按 _id 逐条覆盖写入，条目不存在时插入；写入中途失败也不会丢失已有条目
+async fn write_entries(
+    entries: &Collection<SearchEntry>,
+    batch: Vec<SearchEntry>,
+) -> mongodb::error::Result<u64> {
+    let options = ReplaceOptions::builder().upsert(true).build();
+    for entry in &batch {
+        entries
+            .replace_one(doc! { "_id": entry.id }, entry, options.clone())
+            .await?;
+    }
+    Ok(batch.len() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pinyin_terms(text: &str) -> BTreeSet<String> {
+        let mut terms = BTreeSet::new();
+        add_pinyin_terms(text, &mut terms);
+        terms
+    }
+
+    #[test]
+    fn normalize_char_folds_width_and_case() {
+        assert_eq!(normalize_char('Ａ'), 'a');
+        assert_eq!(normalize_char('９'), '9');
+        assert_eq!(normalize_char('\u{3000}'), ' ');
+        assert_eq!(normalize_char('Ü'), 'v');
+        assert_eq!(normalize_char('庆'), '庆');
+    }
+
+    #[test]
+    fn query_terms_split_cjk_into_bigrams() {
+        assert_eq!(query_terms("庆余年"), vec!["庆余", "余年"]);
+        assert_eq!(query_terms("庆"), vec!["庆"]);
+        assert_eq!(query_terms("Ｈello 庆余年 hello"), vec!["hello", "庆余", "余年"]);
+        assert!(query_terms(" ，。").is_empty());
+    }
+
+    #[test]
+    fn query_terms_are_capped() {
+        let query = "一二三四五六七八九十甲乙丙丁戊己庚辛";
+        assert_eq!(query_terms(query).len(), MAX_QUERY_TERMS);
+        let long_word = "a".repeat(MAX_TERM_LEN + 10);
+        assert_eq!(query_terms(&long_word)[0].chars().count(), MAX_TERM_LEN);
+    }
+
+    #[test]
+    fn highlight_marks_matches_and_escapes() {
+        assert_eq!(highlight("庆余年 第二季", "余年"), "庆<mark>余年</mark> 第二季");
+        assert_eq!(highlight("ＡＢＣ", "abc"), "<mark>ＡＢＣ</mark>");
+        assert_eq!(highlight("<b>庆余年</b>", "庆"), "&lt;b&gt;<mark>庆</mark>余年&lt;/b&gt;");
+        assert_eq!(highlight("庆余年", ""), "庆余年");
+    }
+
+    #[test]
+    fn pinyin_terms_cover_full_initials_and_suffixes() {
+        let terms = pinyin_terms("庆余年");
+        for term in ["q", "qing", "qingyunian", "qyn", "yunian", "yn", "nian"] {
+            assert!(terms.contains(term), "missing {}", term);
+        }
+    }
+
+
+    #[test]
+    fn pinyin_skips_characters_without_pinyin() {
+        let terms = pinyin_terms("进击の巨人");
+        assert!(terms.contains("jinji"));
+        assert!(terms.contains("juren"));
+        assert!(!terms.contains("jinjijuren"));
+    }
+
+    #[test]
+    fn pinyin_suffixes_start_only_from_leading_syllables() {
+        let terms = pinyin_terms("名侦探柯南");
+        assert!(terms.contains("mingzhentankenan"));
+        assert!(terms.contains("tankenan"));
+        assert!(!terms.contains("kenan"));
+        assert!(!terms.contains("nan"));
+    }
+}
@@ -0,0 +1,577 @@
+use crate::image_handlers::{create_archive_job, delete_galleries, published_gallery_filter, IMAGE_COLLECTION};
+use crate::jwt_auth::AuthenticatedUser;
+use crate::models::{GallerySeries, Image, ImageItem, SeriesFollow, IMAGE_REVIEW_APPROVED, IMAGE_REVIEW_PENDING};
+use actix_web::{web, HttpResponse, Responder};
+use futures::stream::TryStreamExt;
+use mongodb::bson::{doc, oid::ObjectId, DateTime, Document};
+use mongodb::options::{FindOneOptions, FindOptions};
+use mongodb::Database;
+use serde::Deserialize;
+use serde_json::json;
+
+pub const SERIES_COLLECTION: &str = "gallery_series";
+const SERIES_FOLLOW_COLLECTION: &str = "series_follows";
+// 前台连载列表每页数量
+pub const SERIES_PER_PAGE: u64 = 24;
+
+// 创建连载请求
+#[derive(Debug, Deserialize)]
+pub struct CreateSeriesRequest {
+    pub title: String,
+    pub en_title: Option<String>,
+    pub description: Option<String>,
+    pub category: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+// 上传章节请求，upload_id 为压缩包上传后返回的 file_id
+#[derive(Debug, Deserialize)]
+pub struct SubmitChapterRequest {
+    pub title: String,
+    pub chapter_no: Option<i32>,
+    #[serde(default)]
+    pub need_vip: i32,
+    pub upload_id: String,
+}
+
+// 列表分页参数
+#[derive(Debug, Deserialize)]
+pub struct SeriesPageQuery {
+    pub page: Option<u64>,
+    pub limit: Option<u64>,
+}
+
+/// 读取连载中前台可见的章节，按章节序号排序，不包含图片数组
+pub async fn load_series_chapters(
+    db: &Database,
+    series_id: ObjectId,
+) -> mongodb::error::Result<Vec<Image>> {
+    let mut filter = published_gallery_filter();
+    filter.insert("series_id", series_id);
+    let options = FindOptions::builder()
+        .sort(doc! { "chapter_no": 1, "created_at": 1 })
+        .projection(doc! { "images": 0 })
+        .build();
+    db.collection::<Image>(IMAGE_COLLECTION)
+        .find(filter, options)
+        .await?
+        .try_collect()
+        .await
+}
+
+/// 在已排序的章节中查找当前章节的上一章和下一章
+pub fn chapter_neighbors(chapters: &[Image], current: ObjectId) -> (Option<&Image>, Option<&Image>) {
+    match chapters.iter().position(|c| c.id == Some(current)) {
+        Some(index) => (
+            index.checked_sub(1).and_then(|i| chapters.get(i)),
+            chapters.get(index + 1),
+        ),
+        None => (None, None),
+    }
+}
+
+/// 至少有一个可见章节的连载ID
+pub async fn visible_series_ids(db: &Database) -> mongodb::error::Result<Vec<ObjectId>> {
+    let mut filter = published_gallery_filter();
+    filter.insert("series_id", doc! { "$ne": null });
+    Ok(db
+        .collection::<Image>(IMAGE_COLLECTION)
+        .distinct("series_id", filter, None)
+        .await?
+        .into_iter()
+        .filter_map(|id| id.as_object_id())
+        .collect())
+}
+
+/// 审核通过的连载，标题和简介才会在前台展示
+pub fn approved_series_filter() -> Document {
+    doc! { "review_status": IMAGE_REVIEW_APPROVED }
+}
+
+/// 章节审核通过后，为还没有封面的连载补上封面（按章节序号取第一个）
+pub async fn fill_series_covers(db: &Database, image_ids: &[ObjectId]) {
+    let mut filter = published_gallery_filter();
+    filter.insert("_id", doc! { "$in": image_ids });
+    filter.insert("series_id", doc! { "$ne": null });
+    let options = FindOptions::builder()
+        .sort(doc! { "chapter_no": 1, "created_at": 1 })
+        .projection(doc! { "series_id": 1, "cover": 1 })
+        .build();
+    let chapters: Vec<Document> = match db.collection::<Document>(IMAGE_COLLECTION).find(filter, options).await {
+        Ok(cursor) => cursor.try_collect().await.unwrap_or_default(),
+        Err(e) => {
+            eprintln!("查询章节失败: {}", e);
+            return;
+        }
+    };
+
+    let series = db.collection::<GallerySeries>(SERIES_COLLECTION);
+    for chapter in chapters {
+        let (Ok(series_id), Ok(cover)) = (chapter.get_object_id("series_id"), chapter.get_document("cover")) else {
+            continue;
+        };
+        if cover.get_str("url").unwrap_or_default().is_empty() {
+            continue;
+        }
+        if let Err(e) = series
+            .update_one(doc! { "_id": series_id, "cover.url": "" }, doc! { "$set": { "cover": cover } }, None)
+            .await
+        {
+            eprintln!("更新连载封面失败: {}", e);
+        }
+    }
+}
+
+// 读取当前用户创建的连载，不存在或不属于该用户时返回错误响应
+async fn find_own_series(
+    db: &Database,
+    series_id: &str,
+    user_id: ObjectId,
+) -> Result<GallerySeries, HttpResponse> {
+    let Ok(series_id) = ObjectId::parse_str(series_id) else {
+        return Err(HttpResponse::BadRequest().json(json!({"success": false, "message": "无效的连载ID"})));
+    };
+    match db
+        .collection::<GallerySeries>(SERIES_COLLECTION)
+        .find_one(doc! { "_id": series_id }, None)
+        .await
+    {
+        Ok(Some(series)) if series.uploader == user_id => Ok(series),
+        Ok(Some(_)) => Err(HttpResponse::Forbidden().json(json!({"success": false, "message": "无权限操作此连载"}))),
+        Ok(None) => Err(HttpResponse::NotFound().json(json!({"success": false, "message": "连载不存在"}))),
+        Err(e) => {
+            eprintln!("查询连载失败: {}", e);
+            Err(HttpResponse::InternalServerError().json(json!({"success": false, "message": "查询失败"})))
+        }
+    }
+}
+
+// 创建连载
+pub async fn create_series(
+    user: AuthenticatedUser,
+    db: web::Data<Database>,
+    req: web::Json<CreateSeriesRequest>,
+) -> impl Responder {
+    let Some(user_id) = user.user.id else {
+        return HttpResponse::Unauthorized().json(json!({"success": false, "message": "用户信息无效"}));
+    };
+    let req = req.into_inner();
+    let title = req.title.trim();
+    if title.is_empty() {
+        return HttpResponse::BadRequest().json(json!({"success": false, "message": "标题不能为空"}));
+    }
+    if req.category.trim().is_empty() {
+        return HttpResponse::BadRequest().json(json!({"success": false, "message": "分类不能为空"}));
+    }
+
+    let now = DateTime::now();
+    let series = GallerySeries {
+        id: None,
+        title: title.to_string(),
+        en_title: req.en_title.filter(|t| !t.trim().is_empty()),
+        description: req.description.filter(|d| !d.trim().is_empty()),
+        cover: ImageItem { url: String::new(), width: 0, height: 0 },
+        tags: req.tags.into_iter().map(|t| t.trim().to_string()).filter(|t| !t.is_empty()).collect(),
+        category: req.category.trim().to_string(),
+        uploader: user_id,
+        followers: 0,
+        review_status: IMAGE_REVIEW_PENDING.to_string(),
+        review_reason: None,
+        reviewed_at: None,
+        created_at: now,
+        updated_at: now,
+    };
+
+    match db
+        .collection::<GallerySeries>(SERIES_COLLECTION)
+        .insert_one(&series, None)
+        .await
+    {
+        Ok(result) => HttpResponse::Ok().json(json!({
+            "success": true,
+            "message": "连载创建成功，审核通过后将在前台展示",
+            "series_id": result.inserted_id.as_object_id().map(|id| id.to_hex())
+        })),
+        Err(e) => {
+            eprintln!("创建连载失败: {}", e);
+            HttpResponse::InternalServerError().json(json!({"success": false, "message": "创建连载失败"}))
+        }
+    }
+}
+
+// 获取当前用户创建的连载及各连载的章节数
+pub async fn get_user_series(user: AuthenticatedUser, db: web::Data<Database>) -> impl Responder {
+    let Some(user_id) = user.user.id else {
+        return HttpResponse::Unauthorized().json(json!({"success": false, "message": "用户信息无效"}));
+    };
+
+    let options = FindOptions::builder().sort(doc! { "updated_at": -1 }).build();
+    let series: Vec<GallerySeries> = match db
+        .collection::<GallerySeries>(SERIES_COLLECTION)
+        .find(doc! { "uploader": user_id }, options)
+        .await
+    {
+        Ok(cursor) => cursor.try_collect().await.unwrap_or_default(),
+        Err(e) => {
+            eprintln!("查询连载失败: {}", e);
+            return HttpResponse::InternalServerError().json(json!({"success": false, "message": "查询失败"}));
+        }
+    };
+
+    let images = db.collection::<Image>(IMAGE_COLLECTION);
+    let mut items = Vec::with_capacity(series.len());
+    for s in series {
+        let chapter_count = match s.id {
+            Some(id) => images.count_documents(doc! { "series_id": id }, None).await.unwrap_or(0),
+            None => 0,
+        };
+        items.push(json!({ "series": s, "chapter_count": chapter_count }));
+    }
+
+    HttpResponse::Ok().json(json!({"success": true, "series": items}))
+}
+
+// 删除连载及其全部章节
+pub async fn delete_series(
+    user: AuthenticatedUser,
+    db: web::Data<Database>,
+    path: web::Path<String>,
+) -> impl Responder {
+    let Some(user_id) = user.user.id else {
+        return HttpResponse::Unauthorized().json(json!({"success": false, "message": "用户信息无效"}));
+    };
+    let series = match find_own_series(&db, &path.into_inner(), user_id).await {
+        Ok(series) => series,
+        Err(response) => return response,
+    };
+    let Some(series_id) = series.id else {
+        return HttpResponse::NotFound().json(json!({"success": false, "message": "连载不存在"}));
+    };
+
+    let chapter_ids: Vec<ObjectId> = match db
+        .collection::<Image>(IMAGE_COLLECTION)
+        .distinct("_id", doc! { "series_id": series_id }, None)
+        .await
+    {
+        Ok(ids) => ids.into_iter().filter_map(|id| id.as_object_id()).collect(),
+        Err(e) => {
+            eprintln!("查询章节失败: {}", e);
+            return HttpResponse::InternalServerError().json(json!({"success": false, "message": "删除失败"}));
+        }
+    };
+    if let Err(e) = delete_galleries(&db, &chapter_ids).await {
+        eprintln!("删除章节失败: {}", e);
+        return HttpResponse::InternalServerError().json(json!({"success": false, "message": "删除失败"}));
+    }
+    if let Err(e) = db
+        .collection::<SeriesFollow>(SERIES_FOLLOW_COLLECTION)
+        .delete_many(doc! { "series_id": series_id }, None)
+        .await
+    {
+        eprintln!("删除追更记录失败: {}", e);
+    }
+
+    match db
+        .collection::<GallerySeries>(SERIES_COLLECTION)
+        .delete_one(doc! { "_id": series_id }, None)
+        .await
+    {
+        Ok(_) => HttpResponse::Ok().json(json!({
+            "success": true,
+            "message": format!("连载已删除，共删除 {} 个章节", chapter_ids.len())
+        })),
+        Err(e) => {
+            eprintln!("删除连载失败: {}", e);
+            HttpResponse::InternalServerError().json(json!({"success": false, "message": "删除失败"}))
+        }
+    }
+}
+
+// 上传章节：创建待审核的章节图集，并交给 archive-process 处理压缩包
+pub async fn submit_chapter(
+    user: AuthenticatedUser,
+    db: web::Data<Database>,
+    path: web::Path<String>,
+    req: web::Json<SubmitChapterRequest>,
+) -> impl Responder {
+    let Some(user_id) = user.user.id else {
+        return HttpResponse::Unauthorized().json(json!({"success": false, "message": "用户信息无效"}));
+    };
+    let series = match find_own_series(&db, &path.into_inner(), user_id).await {
+        Ok(series) => series,
+        Err(response) => return response,
+    };
+    let Some(series_id) = series.id else {
+        return HttpResponse::NotFound().json(json!({"success": false, "message": "连载不存在"}));
+    };
+    let req = req.into_inner();
+    let title = req.title.trim();
+    if title.is_empty() {
+        return HttpResponse::BadRequest().json(json!({"success": false, "message": "章节标题不能为空"}));
+    }
+    if req.upload_id.is_empty() {
+        return HttpResponse::BadRequest().json(json!({"success": false, "message": "请先上传章节压缩包"}));
+    }
+    if !(0..=5).contains(&req.need_vip) {
+        return HttpResponse::BadRequest().json(json!({"success": false, "message": "VIP等级必须在 0 到 5 之间"}));
+    }
+
+    let images = db.collection::<Image>(IMAGE_COLLECTION);
+    // 未指定章节序号时排在最后
+    let chapter_no = match req.chapter_no {
+        Some(no) if no > 0 => no,
+        Some(_) => {
+            return HttpResponse::BadRequest().json(json!({"success": false, "message": "章节序号必须大于 0"}));
+        }
+        None => {
+            let options = FindOneOptions::builder()
+                .sort(doc! { "chapter_no": -1 })
+                .projection(doc! { "chapter_no": 1 })
+                .build();
+            match db
+                .collection::<Document>(IMAGE_COLLECTION)
+                .find_one(doc! { "series_id": series_id }, options)
+                .await
+            {
+                Ok(last) => last.and_then(|c| c.get_i32("chapter_no").ok()).unwrap_or(0) + 1,
+                Err(e) => {
+                    eprintln!("查询章节失败: {}", e);
+                    return HttpResponse::InternalServerError().json(json!({"success": false, "message": "上传失败"}));
+                }
+            }
+        }
+    };
+
+    let now = DateTime::now();
+    let chapter = Image {
+        id: Some(ObjectId::new()),
+        title: title.to_string(),
+        en_title: None,
+        description: None,
+        images: Vec::new(),
+        cover: ImageItem { url: String::new(), width: 0, height: 0 },
+        tags: series.tags.clone(),
+        category: series.category.clone(),
+        pages: 0,
+        uploader: user_id,
+        likes: 0,
+        language: None,
+        artists: None,
+        need_vip: req.need_vip,
+        status: "processing".to_string(),
+        review_status: IMAGE_REVIEW_PENDING.to_string(),
+        review_reason: None,
+        reviewed_at: None,
+        series_id: Some(series_id),
+        chapter_no: Some(chapter_no),
+        created_at: now,
+        updated_at: now,
+    };
+    if let Err(e) = images.insert_one(&chapter, None).await {
+        eprintln!("保存章节失败: {}", e);
+        return HttpResponse::InternalServerError().json(json!({"success": false, "message": "上传失败"}));
+    }
+    if let Err(e) = db
+        .collection::<GallerySeries>(SERIES_COLLECTION)
+        .update_one(doc! { "_id": series_id }, doc! { "$set": { "updated_at": now } }, None)
+        .await
+    {
+        eprintln!("更新连载时间失败: {}", e);
+    }
+
+    let chapter_id = chapter.id.map(|id| id.to_hex());
+    match create_archive_job(&db, &chapter, &req.upload_id).await {
+        Ok(()) => HttpResponse::Ok().json(json!({
+            "success": true,
+            "message": "章节上传成功，压缩包正在处理中，审核通过后公开",
+            "chapter_id": chapter_id,
+            "chapter_no": chapter_no
+        })),
+        Err(e) => {
+            // 处理任务创建失败，但章节记录已保存
+            eprintln!("创建处理任务失败: {}", e);
+            HttpResponse::Ok().json(json!({
+                "success": true,
+                "message": "章节已保存，但处理任务创建失败，请联系管理员",
+                "chapter_id": chapter_id,
+                "chapter_no": chapter_no
+            }))
+        }
+    }
+}
+
+// 获取当前用户对连载的追更状态
+pub async fn get_series_follow_status(
+    user: AuthenticatedUser,
+    db: web::Data<Database>,
+    path: web::Path<String>,
+) -> impl Responder {
+    let Some(user_id) = user.user.id else {
+        return HttpResponse::Unauthorized().json(json!({"success": false, "message": "用户信息无效"}));
+    };
+    let Ok(series_id) = ObjectId::parse_str(path.into_inner()) else {
+        return HttpResponse::BadRequest().json(json!({"success": false, "message": "无效的连载ID"}));
+    };
+
+    match db
+        .collection::<SeriesFollow>(SERIES_FOLLOW_COLLECTION)
+        .count_documents(doc! { "series_id": series_id, "user_id": user_id }, None)
+        .await
+    {
+        Ok(count) => HttpResponse::Ok().json(json!({
+            "success": true,
+            "following": count > 0,
+            "followers": load_followers(&db, series_id).await
+        })),
+        Err(e) => {
+            eprintln!("查询追更状态失败: {}", e);
+            HttpResponse::InternalServerError().json(json!({"success": false, "message": "查询失败"}))
+        }
+    }
+}
+
+// 读取连载当前追更人数
+async fn load_followers(db: &Database, series_id: ObjectId) -> i32 {
+    let options = FindOneOptions::builder().projection(doc! { "followers": 1 }).build();
+    match db
+        .collection::<Document>(SERIES_COLLECTION)
+        .find_one(doc! { "_id": series_id }, options)
+        .await
+    {
+        Ok(Some(doc)) => doc.get_i32("followers").unwrap_or(0),
+        _ => 0,
+    }
+}
+
+// 追更或取消追更连载
+pub async fn toggle_series_follow(
+    user: AuthenticatedUser,
+    db: web::Data<Database>,
+    path: web::Path<String>,
+) -> impl Responder {
+    let Some(user_id) = user.user.id else {
+        return HttpResponse::Unauthorized().json(json!({"success": false, "message": "用户信息无效"}));
+    };
+    let Ok(series_id) = ObjectId::parse_str(path.into_inner()) else {
+        return HttpResponse::BadRequest().json(json!({"success": false, "message": "无效的连载ID"}));
+    };
+
+    let series = db.collection::<GallerySeries>(SERIES_COLLECTION);
+    let mut filter = approved_series_filter();
+    filter.insert("_id", series_id);
+    match series.count_documents(filter, None).await {
+        Ok(0) => {
+            return HttpResponse::NotFound().json(json!({"success": false, "message": "连载不存在"}));
+        }
+        Ok(_) => {}
+        Err(e) => {
+            eprintln!("查询连载失败: {}", e);
+            return HttpResponse::InternalServerError().json(json!({"success": false, "message": "操作失败"}));
+        }
+    }
+
+    // 依赖 series_id + user_id 唯一索引，插入重复即表示已追更，改为取消
+    let follows = db.collection::<SeriesFollow>(SERIES_FOLLOW_COLLECTION);
+    let following = match crate::db::toggle_unique(&follows, doc! { "series_id": series_id, "user_id": user_id }).await {
+        Ok(Some(following)) => following,
+        // 已被并发请求取消
+        Ok(None) => {
+            return HttpResponse::Conflict().json(json!({"success": false, "message": "操作过于频繁"}));
+        }
+        Err(e) => {
+            eprintln!("切换追更失败: {}", e);
+            return HttpResponse::InternalServerError().json(json!({"success": false, "message": "操作失败"}));
+        }
+    };
+
+    let delta = if following { 1 } else { -1 };
+    if let Err(e) = series
+        .update_one(doc! { "_id": series_id }, doc! { "$inc": { "followers": delta } }, None)
+        .await
+    {
+        eprintln!("更新追更人数失败: {}", e);
+    }
+
+    HttpResponse::Ok().json(json!({
+        "success": true,
+        "following": following,
+        "message": if following { "追更成功" } else { "已取消追更" },
+        "followers": load_followers(&db, series_id).await
+    }))
+}
+
+// 获取当前用户追更的连载，附带最新可见章节
+pub async fn get_followed_series(
+    user: AuthenticatedUser,
+    db: web::Data<Database>,
+    query: web::Query<SeriesPageQuery>,
+) -> impl Responder {
+    let Some(user_id) = user.user.id else {
+        return HttpResponse::Unauthorized().json(json!({"success": false, "message": "用户信息无效"}));
+    };
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query.limit.unwrap_or(20).clamp(1, 100);
+
+    let follows = db.collection::<SeriesFollow>(SERIES_FOLLOW_COLLECTION);
+    let filter = doc! { "user_id": user_id };
+    let total = match follows.count_documents(filter.clone(), None).await {
+        Ok(count) => count,
+        Err(e) => {
+            eprintln!("查询追更总数失败: {}", e);
+            return HttpResponse::InternalServerError().json(json!({"success": false, "message": "查询失败"}));
+        }
+    };
+    let options = FindOptions::builder()
+        .sort(doc! { "created_at": -1 })
+        .skip((page - 1) * limit)
+        .limit(limit as i64)
+        .build();
+    let series_ids: Vec<ObjectId> = match follows.find(filter, options).await {
+        Ok(cursor) => cursor
+            .try_collect::<Vec<SeriesFollow>>()
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .map(|f| f.series_id)
+            .collect(),
+        Err(e) => {
+            eprintln!("查询追更列表失败: {}", e);
+            return HttpResponse::InternalServerError().json(json!({"success": false, "message": "查询失败"}));
+        }
+    };
+
+    let series_list: Vec<GallerySeries> = match db
+        .collection::<GallerySeries>(SERIES_COLLECTION)
+        .find(doc! { "_id": { "$in": &series_ids }, "review_status": IMAGE_REVIEW_APPROVED }, None)
+        .await
+    {
+        Ok(cursor) => cursor.try_collect().await.unwrap_or_default(),
+        Err(e) => {
+            eprintln!("查询连载失败: {}", e);
+            return HttpResponse::InternalServerError().json(json!({"success": false, "message": "查询失败"}));
+        }
+    };
+
+    // 保持追更时间顺序
+    let mut items = Vec::with_capacity(series_ids.len());
+    for series_id in &series_ids {
+        let Some(series) = series_list.iter().find(|s| s.id.as_ref() == Some(series_id)) else {
+            continue;
+        };
+        let latest_chapter = load_series_chapters(&db, *series_id)
+            .await
+            .unwrap_or_default()
+            .pop();
+        items.push(json!({ "series": series, "latest_chapter": latest_chapter }));
+    }
+
+    HttpResponse::Ok().json(json!({
+        "success": true,
+        "series": items,
+        "page": page,
+        "limit": limit,
+        "total": total,
+        "total_pages": total.div_ceil(limit)
+    }))
+}
@@ -5,7 +5,21 @@ use serde::{Serialize, Deserialize};
 use mongodb::Database;
 use crate::models::{Type, Config};
 use futures::stream::TryStreamExt;
-use mongodb::bson::doc;
+use mongodb::bson::{doc, oid::ObjectId, Document};
+use tokio::time::{interval, Duration};
+
+/// 排行榜类型：(标识, 名称)
+pub const RANK_BOARDS: [(&str, &str); 5] = [
+    ("day", "今日热播"),
+    ("week", "本周热播"),
+    ("month", "本月热播"),
+    ("all", "总热播榜"),
+    ("score", "高分榜"),
+];
+// 每个榜单缓存的条目数
+pub const RANK_LIMIT: usize = 50;
+// 排行榜缓存刷新周期
+const RANK_REFRESH_SECS: u64 = 300;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NavigationCategory {
@@ -13,12 +27,45 @@ pub struct NavigationCategory {
     pub sub_categories: Vec<Type>,
 }
 
+/// 排行榜条目，只保留列表展示需要的字段
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RankItem {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vod_id: Option<i64>,
+    pub vod_name: String,
+    pub type_id: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vod_pic: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vod_remarks: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vod_actor: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vod_area: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vod_year: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vod_score: Option<String>,
+    #[serde(default)]
+    pub vod_hits: i64,
+    #[serde(default)]
+    pub vod_hits_day: i64,
+    #[serde(default)]
+    pub vod_hits_week: i64,
+    #[serde(default)]
+    pub vod_hits_month: i64,
+}
+
 #[derive(Debug, Clone)]
 pub struct SiteData {
     pub navigation_categories: Vec<NavigationCategory>,
     pub all_categories: Vec<Type>,
     pub all_categories_map: HashMap<i32, Type>,
     pub configs: HashMap<String, String>,
+    // 排行榜缓存，键为 (榜单标识, 分类ID)，分类ID为0表示全站
+    pub rankings: HashMap<(String, i32), Vec<RankItem>>,
     pub last_updated: std::time::Instant,
 }
 
@@ -29,6 +76,7 @@ impl SiteData {
             all_categories: Vec::new(),
             all_categories_map: HashMap::new(),
             configs: HashMap::new(),
+            rankings: HashMap::new(),
             last_updated: std::time::Instant::now(),
         }
     }
@@ -52,16 +100,41 @@ impl SiteDataManager {
     pub async fn initialize(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         println!("🔧 正在初始化站点数据缓存...");
         
-        // 加载分类数据
-        self.load_categories().await?;
-        
-        // 加载配置数据
-        self.load_configs().await?;
+        self.load_all().await?;
         
         println!("✅ 站点数据缓存初始化完成");
         Ok(())
     }
 
+    /// 依次加载分类、配置和排行榜，某一项失败时记录日志并继续加载其余项
+    async fn load_all(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut failed = Vec::new();
+
+        // 加载分类数据
+        if let Err(e) = self.load_categories().await {
+            eprintln!("❌ 加载分类缓存失败: {}", e);
+            failed.push("分类");
+        }
+
+        // 加载配置数据
+        if let Err(e) = self.load_configs().await {
+            eprintln!("❌ 加载配置缓存失败: {}", e);
+            failed.push("配置");
+        }
+
+        // 加载排行榜数据，失败时保留旧榜单
+        if let Err(e) = self.load_rankings().await {
+            eprintln!("❌ 加载排行榜缓存失败: {}", e);
+            failed.push("排行榜");
+        }
+
+        if failed.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("{}加载失败", failed.join("、")).into())
+        }
+    }
+
     /// 加载分类数据
     async fn load_categories(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         let type_collection = self.db.collection::<Type>("types");
@@ -140,6 +213,124 @@ impl SiteDataManager {
         Ok(())
     }
 
+    /// 加载排行榜数据：全站及每个启用的视频分类（含子分类）各生成一份榜单
+    ///
+    /// 每个榜单单独查询，按对应的点击数索引排序后只取前 RANK_LIMIT 条
+    async fn load_rankings(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let categories = self.get_all_categories().await;
+
+        // 榜单范围：(分类ID, 包含的分类ID)，全站为0且不限分类
+        let mut scopes: Vec<(i32, Vec<i32>)> = vec![(0, Vec::new())];
+        for category in categories
+            .iter()
+            .filter(|cat| cat.type_status == 1 && cat.type_mid.unwrap_or(1) == 1)
+        {
+            let mut type_ids = vec![category.type_id];
+            type_ids.extend(
+                categories
+                    .iter()
+                    .filter(|cat| cat.type_pid == category.type_id)
+                    .map(|cat| cat.type_id),
+            );
+            scopes.push((category.type_id, type_ids));
+        }
+
+        let mut rankings = HashMap::new();
+        for (scope, type_ids) in &scopes {
+            for (board, _) in RANK_BOARDS {
+                let items = self.query_ranking(board, type_ids).await?;
+                rankings.insert((board.to_string(), *scope), items);
+            }
+        }
+
+        let mut data = self.data.write().await;
+        data.rankings = rankings;
+
+        Ok(())
+    }
+
+    /// 查询单个榜单的前 RANK_LIMIT 条，type_ids 为空时查询全站
+    async fn query_ranking(
+        &self,
+        board: &str,
+        type_ids: &[i32],
+    ) -> Result<Vec<RankItem>, mongodb::error::Error> {
+        let collection = self.db.collection::<Document>("vods");
+        let mut filter = doc! { "vod_status": 1 };
+        if !type_ids.is_empty() {
+            filter.insert("type_id", doc! { "$in": type_ids });
+        }
+
+        let docs: Vec<Document> = match rank_hits_field(board) {
+            Some(field) => {
+                filter.insert(field, doc! { "$gt": 0 });
+                let options = mongodb::options::FindOptions::builder()
+                    .projection(rank_projection())
+                    .sort(doc! { field: -1 })
+                    .limit(RANK_LIMIT as i64)
+                    .build();
+                collection.find(filter, options).await?.try_collect().await?
+            }
+            None => {
+                // vod_score 以字符串保存，在数据库中转换为数字后排序
+                filter.insert("vod_score", doc! { "$nin": [null, "", "0", "0.0"] });
+                let pipeline = vec![
+                    doc! { "$match": filter },
+                    doc! { "$addFields": { "rank_score": { "$convert": {
+                        "input": { "$trim": { "input": "$vod_score" } },
+                        "to": "double",
+                        "onError": 0.0,
+                        "onNull": 0.0,
+                    } } } },
+                    doc! { "$match": { "rank_score": { "$gt": 0.0 } } },
+                    doc! { "$sort": { "rank_score": -1, "vod_hits": -1 } },
+                    doc! { "$limit": RANK_LIMIT as i64 },
+                    doc! { "$project": rank_projection() },
+                ];
+                collection.aggregate(pipeline, None).await?.try_collect().await?
+            }
+        };
+
+        let mut entries: Vec<(RankKey, RankItem)> = docs
+            .into_iter()
+            .filter_map(|d| {
+                let pubdate = d
+                    .get_datetime("vod_pubdate")
+                    .map(|t| t.timestamp_millis())
+                    .unwrap_or(0);
+                let item = mongodb::bson::from_document::<RankItem>(d).ok()?;
+                rank_key(board, &item, pubdate).map(|key| (key, item))
+            })
+            .collect();
+        truncate_ranking(&mut entries);
+        Ok(entries.into_iter().map(|(_, item)| item).collect())
+    }
+
+    /// 启动排行榜定时刷新任务
+    pub fn start_rank_refresh(&self) {
+        let manager = self.clone();
+        tokio::spawn(async move {
+            let mut timer = interval(Duration::from_secs(RANK_REFRESH_SECS));
+            // 第一次 tick 立即返回，初始化时已加载过
+            timer.tick().await;
+            loop {
+                timer.tick().await;
+                if let Err(e) = manager.load_rankings().await {
+                    eprintln!("❌ 刷新排行榜缓存失败: {}", e);
+                }
+            }
+        });
+    }
+
+    /// 获取排行榜，type_id 为0表示全站榜单
+    pub async fn get_ranking(&self, board: &str, type_id: i32) -> Vec<RankItem> {
+        let data = self.data.read().await;
+        data.rankings
+            .get(&(board.to_string(), type_id))
+            .cloned()
+            .unwrap_or_default()
+    }
+
     /// 获取导航分类数据
     pub async fn get_navigation_categories(&self) -> Vec<NavigationCategory> {
         let data = self.data.read().await;
@@ -173,8 +364,7 @@ impl SiteDataManager {
     /// 刷新数据缓存
     pub async fn refresh(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         println!("🔄 正在刷新站点数据缓存...");
-        self.load_categories().await?;
-        self.load_configs().await?;
+        self.load_all().await?;
         println!("✅ 站点数据缓存刷新完成");
         Ok(())
     }
@@ -188,6 +378,7 @@ impl SiteDataManager {
         stats.insert("navigation_categories_count".to_string(), serde_json::json!(data.navigation_categories.len()));
         stats.insert("all_categories_count".to_string(), serde_json::json!(data.all_categories.len()));
         stats.insert("configs_count".to_string(), serde_json::json!(data.configs.len()));
+        stats.insert("rankings_count".to_string(), serde_json::json!(data.rankings.len()));
         stats.insert("last_updated_seconds_ago".to_string(), serde_json::json!(elapsed.as_secs()));
         stats.insert("last_updated".to_string(), serde_json::json!(format!("{:?}", data.last_updated)));
         
@@ -199,4 +390,106 @@ impl SiteDataManager {
         let data = self.data.read().await;
         data.last_updated.elapsed().as_secs() > max_age_seconds
     }
-}
\ No newline at end of file
+}
+
+/// 热播榜对应的点击数字段，高分榜返回 None
+fn rank_hits_field(board: &str) -> Option<&'static str> {
+    match board {
+        "day" => Some("vod_hits_day"),
+        "week" => Some("vod_hits_week"),
+        "month" => Some("vod_hits_month"),
+        "score" => None,
+        _ => Some("vod_hits"),
+    }
+}
+
+/// 榜单条目需要读取的字段
+fn rank_projection() -> Document {
+    doc! {
+        "vod_id": 1, "vod_name": 1, "type_id": 1, "vod_pic": 1, "vod_remarks": 1,
+        "vod_actor": 1, "vod_area": 1, "vod_year": 1, "vod_score": 1, "vod_pubdate": 1,
+        "vod_hits": 1, "vod_hits_day": 1, "vod_hits_week": 1, "vod_hits_month": 1,
+    }
+}
+
+/// 排行排序键：(主排序值, 次排序值)，均按降序
+type RankKey = (f64, i64);
+
+/// 热播榜按对应周期点击数排序、同点击按上架时间；高分榜按评分排序、同分按总点击。
+/// 不应上榜的条目返回 None
+fn rank_key(board: &str, item: &RankItem, pubdate: i64) -> Option<RankKey> {
+    let hits = match board {
+        "day" => item.vod_hits_day,
+        "week" => item.vod_hits_week,
+        "month" => item.vod_hits_month,
+        "score" => {
+            // vod_score 以字符串保存，转换为数字后再排序
+            let score = item
+                .vod_score
+                .as_deref()
+                .and_then(|s| s.trim().parse::<f64>().ok())
+                .filter(|s| s.is_finite() && *s > 0.0)?;
+            return Some((score, item.vod_hits));
+        }
+        _ => item.vod_hits,
+    };
+    (hits > 0).then_some((hits as f64, pubdate))
+}
+
+/// 按排序键降序排列并只保留前 RANK_LIMIT 条
+fn truncate_ranking(entries: &mut Vec<(RankKey, RankItem)>) {
+    entries.sort_by(|(a, _), (b, _)| b.0.total_cmp(&a.0).then(b.1.cmp(&a.1)));
+    entries.truncate(RANK_LIMIT);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(hits: i64, score: Option<&str>) -> RankItem {
+        mongodb::bson::from_document(doc! {
+            "_id": ObjectId::new(),
+            "vod_name": "测试",
+            "type_id": 1,
+            "vod_score": score,
+            "vod_hits": hits,
+            "vod_hits_day": hits,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn rank_key_skips_items_without_hits_or_score() {
+        assert_eq!(rank_key("day", &item(0, None), 0), None);
+        assert_eq!(rank_key("week", &item(5, None), 0), None);
+        assert_eq!(rank_key("all", &item(5, None), 7), Some((5.0, 7)));
+        assert_eq!(rank_key("score", &item(3, Some("8.5")), 0), Some((8.5, 3)));
+        assert_eq!(rank_key("score", &item(3, Some("0.0")), 0), None);
+        assert_eq!(rank_key("score", &item(3, Some("暂无")), 0), None);
+    }
+
+    #[test]
+    fn rank_boards_sort_on_indexed_hit_fields() {
+        assert_eq!(rank_hits_field("day"), Some("vod_hits_day"));
+        assert_eq!(rank_hits_field("all"), Some("vod_hits"));
+        assert_eq!(rank_hits_field("score"), None);
+    }
+
+    #[test]
+    fn rank_item_accepts_int64_hit_counters() {
+        let parsed = item(i64::from(i32::MAX) + 1, None);
+        assert_eq!(parsed.vod_hits, i64::from(i32::MAX) + 1);
+    }
+
+    #[test]
+    fn truncate_ranking_keeps_top_entries_in_order() {
+        let mut entries: Vec<(RankKey, RankItem)> = (0..RANK_LIMIT as i64 + 10)
+            .map(|hits| ((hits as f64, 0), item(hits, None)))
+            .collect();
+        entries.push(((3.0, 9), item(3, None)));
+        truncate_ranking(&mut entries);
+        assert_eq!(entries.len(), RANK_LIMIT);
+        assert_eq!(entries[0].1.vod_hits, RANK_LIMIT as i64 + 9);
+        assert!(entries.windows(2).all(|w| w[0].0 >= w[1].0));
+    }
+}
@@ -0,0 +1,229 @@
+use crate::dto::VodApiListEntry;
+use crate::models::{Collection, Vod, VodMergeLog};
+use futures::stream::TryStreamExt;
+use lazy_static::lazy_static;
+use mongodb::{
+    bson::{doc, DateTime, Document},
+    Database,
+};
+use regex::Regex;
+use std::collections::HashMap;
+
+// 重复视频判定方式（Collection.collect_match），0 为默认的名称完全一致（有年份时同时比较年份）
+pub const MATCH_NORMALIZED: i32 = 1; // 规范化名称（忽略空格/符号/全半角/繁简/季数写法）
+pub const MATCH_NAME_YEAR_DIRECTOR: i32 = 2; // 规范化名称 + 年份 + 导演
+pub const MATCH_DOUBAN: i32 = 3; // 豆瓣ID，缺失时按规范化名称
+
+// 常见繁体字 -> 简体字对照（仅用于名称比对）
+const TRAD_SIMP_PAIRS: &str = "與与專专業业東东絲丝兩两嚴严個个豐丰臨临為为麗丽舉举義义烏乌樂乐喬乔習习鄉乡書书買买亂乱爭争於于虧亏雲云亞亚產产親亲億亿僅仅從从倉仓儀仪們们價价眾众優优夥伙會会傘伞偉伟傳传傷伤倫伦偽伪體体餘余傭佣俠侠侶侣偵侦側侧僑侨儉俭債债傾倾兒儿黨党蘭兰關关興兴養养獸兽內内岡冈冊册寫写軍军農农馮冯沖冲決决況况凍冻淨净涼凉減减幾几鳳凤憑凭凱凯擊击劃划劉刘則则剛刚創创刪删別别劑剂劍剑勸劝辦办務务動动勵励勁劲勞劳勢势勳勋區区醫医華华協协單单賣卖盧卢衛卫卻却廠厂廳厅歷历厲厉壓压厭厌縣县參参雙双發发變变敘叙疊叠葉叶號号嘆叹後后嚇吓呂吕嗎吗嗚呜員员聽听啟启吳吴鳴鸣響响團团園园圍围圖图國国圓圆聖圣場场壞坏塊块堅坚壇坛墳坟墜坠塗涂壺壶處处備备復复夠够頭头誇夸夾夹奪夺奮奋獎奖妝妆婦妇媽妈嬌娇孫孙學学寶宝實实寵宠審审憲宪宮宫寬宽賓宾寢寝對对尋寻導导將将爾尔塵尘嘗尝屍尸盡尽層层屆届屬属歲岁豈岂島岛嶺岭崗岗峽峡幣币帥帅師师帳帐帶带幫帮幹干並并廣广莊庄慶庆庫库應应廟庙廢废開开異异棄弃張张彌弥彎弯彈弹強强歸归當当錄录彥彦徹彻徑径憶忆懷怀態态總总戀恋懇恳惡恶惱恼悅悦驚惊慘惨懶懒戲戏戰战戶户執执擴扩掃扫揚扬擾扰撫抚拋抛搶抢護护報报擔担擁拥擇择掛挂擋挡撥拨擠挤揮挥損损換换據据擺摆攜携攝摄斂敛數数齋斋鬥斗斬斩斷断無无舊旧時时曠旷晝昼顯显晉晋曉晓暈晕暫暂曆历術术樸朴機机殺杀雜杂權权條条來来楊杨極极構构槍枪楓枫櫃柜標标棧栈欄栏樹树樣样橋桥檔档夢梦檢检樓楼橫横歡欢歐欧殘残殲歼毆殴毀毁氣气漢汉湯汤溝沟沒没滬沪淚泪潑泼澤泽潔洁灑洒濁浊測测濟济瀏浏渾浑濃浓漲涨淵渊漁渔溫温滅灭燈灯靈灵災灾爐炉點点煉炼爍烁煩烦燒烧熱热愛爱爺爷牽牵犧牺狀状猶犹狹狭獅狮獨独獄狱貓猫獵猎獻献現现環环瑪玛璽玺瓊琼電电畫画暢畅療疗瘋疯癢痒盞盏監监盤盘睜睁瞞瞒礦矿碼码磚砖確确禮礼禍祸禪禅離离禿秃種种積积穩稳窮穷竊窃競竞筆笔節节範范築筑簡简籃篮類类糧粮糾纠紅红紀纪約约級级紙纸紛纷納纳純纯線线練练組组細细終终結结絕绝給给統统絡络經经綠绿維维網网緣缘編编緝缉縮缩織织繪绘續续纏缠罰罚罷罢羅罗聯联聲声職职聰聪聞闻肅肃腸肠膚肤腦脑臉脸膽胆艦舰艱艰藝艺蘇苏蘋苹莖茎萬万蕭萧薩萨藍蓝藥药萊莱蟲虫蝦虾螢萤蠻蛮襲袭裝装補补裡里裏里見见規规視视覺觉覽览觀观觸触計计訂订認认討讨讓让訓训記记講讲許许論论設设訪访證证評评識识詞词譯译試试詩诗話话誠诚該该說说請请諸诸讀读誰谁調调談谈謎谜謝谢謀谋諜谍誘诱語语貝贝負负財财貢贡貨货販贩貧贫責责貴贵費费貼贴賀贺資资賊贼賞赏賭赌賢贤賽赛贊赞贏赢趕赶趙赵趨趋躍跃蹤踪車车軌轨軟软輪轮較较輕轻載载輩辈輝辉輸输轉转辭辞邊边遼辽達达遷迁過过運运還还這这進进遠远違违連连遲迟適适選选遺遗郵邮鄰邻鄭郑醜丑釋释針针釣钓鈴铃鉤钩銀银銅铜鋼钢錢钱錯错鍋锅鍵键鏡镜鐘钟鐵铁鑰钥長长門门閃闪閉闭問问閒闲間间閱阅闖闯鬧闹陽阳陰阴陣阵陳陈際际陸陆險险隊队階阶隨随隱隐隻只難难雞鸡霧雾靜静韓韩頁页頂顶項项順顺須须預预領领題题顏颜願愿風风飛飞飯饭飲饮餓饿館馆馬马駕驾騎骑騰腾驗验鬆松魚鱼鮮鲜鳥鸟鴨鸭鷹鹰麥麦黃黄齊齐齒齿龍龙龜龟劇剧煙烟憂忧麼么麽么奧奥鏢镖盜盗捲卷斃毙殭僵遊游週周麵面髮发鬱郁臺台颱台係系繫系鍾钟蔣蒋濕湿鑒鉴鑑鉴緊紧歎叹寧宁懸悬瀟潇灣湾嶼屿鎮镇齡龄壯壮塢坞";
+
+lazy_static! {
+    static ref TRAD_TO_SIMP: HashMap<char, char> = {
+        let chars: Vec<char> = TRAD_SIMP_PAIRS.chars().collect();
+        chars.chunks(2).map(|pair| (pair[0], pair[1])).collect()
+    };
+    static ref SEASON_CN_RE: Regex = Regex::new(r"第([0-9一二三四五六七八九十]+)[季部]").unwrap();
+    static ref SEASON_EN_RE: Regex =
+        Regex::new(r"(?i)(?:season\s*|\bs)0*([0-9]{1,2})\b").unwrap();
+}
+
+// 中文数字季数（1-99）转为阿拉伯数字
+fn parse_season_number(value: &str) -> Option<u32> {
+    if let Ok(n) = value.parse() {
+        return Some(n);
+    }
+
+    let digit = |c: char| "一二三四五六七八九".chars().position(|d| d == c).map(|i| i as u32 + 1);
+    let chars: Vec<char> = value.chars().collect();
+    match chars.as_slice() {
+        ['十'] => Some(10),
+        ['十', b] => Some(10 + digit(*b)?),
+        [a, '十'] => Some(digit(*a)? * 10),
+        [a, '十', b] => Some(digit(*a)? * 10 + digit(*b)?),
+        [a] => digit(*a),
+        _ => None,
+    }
+}
+
+// 生成用于比对的规范化名称：
+// 全角转半角、繁体转简体、忽略大小写/空格/符号，季数统一写法（第一季视为无季数）
+pub fn normalize_vod_name(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| match c {
+            '\u{3000}' => ' ',
+            '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+            _ => *TRAD_TO_SIMP.get(&c).unwrap_or(&c),
+        })
+        .collect();
+
+    let mut season = None;
+    let name = SEASON_CN_RE.replace_all(&name, |caps: &regex::Captures| {
+        season = parse_season_number(&caps[1]);
+        ""
+    });
+    let name = SEASON_EN_RE.replace_all(&name, |caps: &regex::Captures| {
+        season = caps[1].parse().ok();
+        ""
+    });
+
+    let mut key: String = name
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect();
+
+    if let Some(n) = season.filter(|n| *n > 1) {
+        key.push_str(&format!("#s{}", n));
+    }
+    key
+}
+
+// 导演字段拆分后是否有交集
+fn directors_overlap(a: &str, b: &str) -> bool {
+    let split = |value: &str| -> Vec<String> {
+        value
+            .split([',', '，', '/', '、', '|', ' '])
+            .map(normalize_vod_name)
+            .filter(|name| !name.is_empty())
+            .collect()
+    };
+    let a = split(a);
+    split(b).iter().any(|name| a.contains(name))
+}
+
+fn with_year(mut filter: Document, vod_data: &VodApiListEntry) -> Document {
+    if let Some(ref year) = vod_data.vod_year {
+        filter.insert("vod_year", year);
+    }
+    filter
+}
+
+// 按采集源配置的判定方式查找已存在的视频
+pub async fn find_existing_vod(
+    db: &Database,
+    collection: &Collection,
+    vod_data: &VodApiListEntry,
+) -> Result<Option<Vod>, mongodb::error::Error> {
+    let vods_collection = db.collection::<Vod>("vods");
+
+    match collection.collect_match {
+        MATCH_NAME_YEAR_DIRECTOR => {
+            let filter = with_year(
+                doc! { "vod_name_key": normalize_vod_name(&vod_data.vod_name) },
+                vod_data,
+            );
+            let candidates: Vec<Vod> = vods_collection.find(filter, None).await?.try_collect().await?;
+
+            // 任一方缺少导演信息时无法区分，视为同一视频
+            let director = vod_data.vod_director.as_deref().unwrap_or_default();
+            Ok(candidates.into_iter().find(|vod| {
+                let existing = vod.vod_director.as_deref().unwrap_or_default();
+                director.trim().is_empty()
+                    || existing.trim().is_empty()
+                    || directors_overlap(existing, director)
+            }))
+        }
+        MATCH_NORMALIZED | MATCH_DOUBAN => {
+            if collection.collect_match == MATCH_DOUBAN
+                && let Some(douban_id) = vod_data.vod_douban_id.filter(|id| *id > 0)
+            {
+                let existing = vods_collection
+                    .find_one(doc! { "vod_douban_id": douban_id }, None)
+                    .await?;
+                if existing.is_some() {
+                    return Ok(existing);
+                }
+            }
+
+            let filter = with_year(
+                doc! { "vod_name_key": normalize_vod_name(&vod_data.vod_name) },
+                vod_data,
+            );
+            vods_collection.find_one(filter, None).await
+        }
+        _ => {
+            let filter = with_year(doc! { "vod_name": &vod_data.vod_name }, vod_data);
+            vods_collection.find_one(filter, None).await
+        }
+    }
+}
+
+// 记录名称不一致的合并，供合并报告查看
+pub async fn record_merge(
+    db: &Database,
+    task_id: &str,
+    collection: &Collection,
+    vod_data: &VodApiListEntry,
+    existing: &Vod,
+) -> Result<(), mongodb::error::Error> {
+    if existing.vod_name == vod_data.vod_name {
+        return Ok(());
+    }
+
+    let log = VodMergeLog {
+        id: None,
+        task_id: task_id.to_string(),
+        collect_id: collection.id,
+        collect_name: collection.collect_name.clone(),
+        collect_match: collection.collect_match,
+        source_vod_id: vod_data.vod_id.to_string(),
+        source_vod_name: vod_data.vod_name.clone(),
+        source_vod_year: vod_data.vod_year.clone(),
+        vod_id: existing.id,
+        vod_name: existing.vod_name.clone(),
+        vod_year: existing.vod_year.clone(),
+        created_at: DateTime::now(),
+    };
+
+    db.collection::<VodMergeLog>("vod_merge_logs")
+        .insert_one(log, None)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_converts_traditional_and_full_width() {
+        assert_eq!(normalize_vod_name("慶餘年"), "庆余年");
+        assert_eq!(normalize_vod_name("灌籃高手"), "灌篮高手");
+        assert_eq!(normalize_vod_name("ＯＮＥ　ＰＩＥＣＥ！"), "onepiece");
+        assert_eq!(normalize_vod_name("  庆余年 · 番外 "), "庆余年番外");
+    }
+
+    #[test]
+    fn normalize_unifies_season_suffixes() {
+        for name in ["庆余年第二季", "庆余年 第2季", "慶餘年 第二部", "庆余年 Season 2", "庆余年 S02"] {
+            assert_eq!(normalize_vod_name(name), "庆余年#s2", "{}", name);
+        }
+        assert_eq!(normalize_vod_name("请回答1988 第十二季"), "请回答1988#s12");
+        assert_eq!(normalize_vod_name("老友记 第二十一季"), "老友记#s21");
+    }
+
+    #[test]
+    fn first_season_matches_plain_name() {
+        assert_eq!(normalize_vod_name("庆余年第一季"), normalize_vod_name("庆余年"));
+        assert_eq!(normalize_vod_name("Friends S1"), "friends");
+    }
+
+    #[test]
+    fn parse_season_number_handles_chinese_numerals() {
+        assert_eq!(parse_season_number("3"), Some(3));
+        assert_eq!(parse_season_number("十"), Some(10));
+        assert_eq!(parse_season_number("十五"), Some(15));
+        assert_eq!(parse_season_number("二十"), Some(20));
+        assert_eq!(parse_season_number("九十九"), Some(99));
+        assert_eq!(parse_season_number("百"), None);
+    }
+
+    #[test]
+    fn directors_overlap_splits_names() {
+        assert!(directors_overlap("孙皓/王倦", "王倦"));
+        assert!(directors_overlap("Christopher Nolan", "christopher,nolan"));
+        assert!(!directors_overlap("孙皓", "王倦"));
+        assert!(!directors_overlap("", ""));
+    }
+}
@@ -1,10 +1,11 @@
 use crate::jwt_auth::AdminUser;
-use crate::models::{Card, Config, Type, User, Vod};
+use crate::models::{Art, Audio, Card, Config, GallerySeries, Image, Type, User, Vod};
 use crate::template::TERA;
+use crate::search_index;
 use actix_web::{web, HttpResponse, Responder, HttpMessage, Result, FromRequest};
 use chrono::Datelike;
 use futures::stream::TryStreamExt;
-use mongodb::{bson::doc, options::FindOptions, Database};
+use mongodb::{bson::{doc, Bson}, options::FindOptions, Database};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 
@@ -66,9 +67,18 @@ fn extract_line_info(error_str: &str) -> Option<String> {
     
     None
 }
-use crate::dto::ListPageParams;
+use crate::audio_handlers::{published_filter, track_name, AUDIOS_PER_PAGE, AUDIO_COLLECTION};
+use crate::image_handlers::{published_gallery_filter, GALLERIES_PER_PAGE, IMAGE_COLLECTION};
+use crate::dto::{AudioListParams, GalleryListParams, GalleryReaderParams, ListPageParams, RankParams};
 use crate::init_data;
-use crate::site_data::SiteDataManager;
+use crate::series_handlers::{
+    approved_series_filter, chapter_neighbors, load_series_chapters, visible_series_ids, SERIES_COLLECTION,
+    SERIES_PER_PAGE,
+};
+use crate::site_data::{SiteDataManager, RANK_BOARDS};
+use crate::comment_handlers::load_comment_page;
+use crate::favorite_handlers::favorite_count;
+use crate::hit_counter::HitCounter;
 use actix_web_flash_messages::FlashMessage;
 use std::error::Error;
 
@@ -257,17 +267,19 @@ pub async fn video_detail_handler_wrapper(
     path: web::Path<String>,
     db: web::Data<Database>,
     site_data_manager: web::Data<SiteDataManager>,
+    hit_counter: web::Data<HitCounter>,
 ) -> impl Responder {
-    video_detail_handler(path, db, site_data_manager).await
+    video_detail_handler(path, db, site_data_manager, hit_counter).await
 }
 
 pub async fn video_player_handler_wrapper(
     path: web::Path<(String, String)>,
     db: web::Data<Database>,
     site_data_manager: web::Data<SiteDataManager>,
+    hit_counter: web::Data<HitCounter>,
     req: actix_web::HttpRequest,
 ) -> impl Responder {
-    video_player_handler(path, db, site_data_manager, req).await
+    video_player_handler(path, db, site_data_manager, hit_counter, req).await
 }
 
 pub async fn list_page_handler_wrapper(
@@ -280,7 +292,7 @@ pub async fn list_page_handler_wrapper(
 }
 
 pub async fn search_page_handler_wrapper(
-    query: web::Query<crate::dto::ApiParams>,
+    query: web::Query<crate::dto::SearchParams>,
     db: web::Data<Database>,
     site_data_manager: web::Data<SiteDataManager>,
 ) -> impl Responder {
@@ -332,6 +344,11 @@ pub async fn home_page(
 
             context.insert("categorized_videos", &categorized_videos_list);
 
+            // 本周热播榜前10
+            let mut hot_videos = site_data.get_ranking("week", 0).await;
+            hot_videos.truncate(10);
+            context.insert("hot_videos", &hot_videos);
+
             TERA.render("index.html", &context)
                 .map_err(|e| {
                     handle_template_rendering_error(
@@ -485,6 +502,7 @@ pub async fn video_detail_handler(
     path: web::Path<String>,
     db: web::Data<Database>,
     site_data_manager: web::Data<SiteDataManager>,
+    hit_counter: web::Data<HitCounter>,
 ) -> impl Responder {
     let vod_id = path.into_inner();
 
@@ -505,6 +523,16 @@ pub async fn video_detail_handler(
                 Ok(Some(v)) => v,
                 _ => return Err("Video not found".into()),
             };
+            hit_counter.record(object_id);
+
+            let favorite_count =
+                favorite_count(&db, &crate::models::MediaType::Vod, object_id).await;
+            context.insert("favorite_count", &favorite_count);
+
+            match load_comment_page(&db, &crate::models::MediaType::Vod, object_id, 1).await {
+                Ok(comment_page) => context.insert("comment_page", &comment_page),
+                Err(e) => eprintln!("加载评论失败: {}", e),
+            }
 
             // Convert MongoDB DateTime to timestamp for template
             let pubdate_timestamp = video.vod_pubdate.timestamp_millis() / 1000;
@@ -575,6 +603,7 @@ pub async fn video_player_handler(
     path: web::Path<(String, String)>,
     db: web::Data<Database>,
     site_data_manager: web::Data<SiteDataManager>,
+    hit_counter: web::Data<HitCounter>,
     http_req: actix_web::HttpRequest,
 ) -> impl Responder {
     let (vod_id, play_index) = path.into_inner();
@@ -622,28 +651,8 @@ pub async fn video_player_handler(
                 _ => return Err("Video not found".into()),
             };
 
-            // Increment hit count
-            let current_hits = video.vod_hits.unwrap_or(0);
-            let current_hits_day = video.vod_hits_day.unwrap_or(0);
-            let current_hits_week = video.vod_hits_week.unwrap_or(0);
-            let current_hits_month = video.vod_hits_month.unwrap_or(0);
-
-            let update_result = vod_collection
-                .update_one(
-                    doc! {"_id": object_id},
-                    doc! {"$set": {
-                        "vod_hits": current_hits + 1,
-                        "vod_hits_day": current_hits_day + 1,
-                        "vod_hits_week": current_hits_week + 1,
-                        "vod_hits_month": current_hits_month + 1,
-                    }},
-                    None,
-                )
-                .await;
-
-            if let Err(e) = update_result {
-                println!("Warning: Failed to update hit count: {}", e);
-            }
+            // Increment hit count (buffered, written back periodically)
+            hit_counter.record(object_id);
 
             // Convert MongoDB DateTime to timestamp for template
             let pubdate_timestamp = video.vod_pubdate.timestamp_millis() / 1000;
@@ -886,82 +895,945 @@ pub async fn list_page_handler(
                     total_pages
                 };
 
-                for p in start_page..=end_page {
-                    pages.push(p);
-                }
+                for p in start_page..=end_page {
+                    pages.push(p);
+                }
+
+                let pagination = PaginationInfo {
+                    current_page: page,
+                    total_pages,
+                    total_items,
+                    pages,
+                };
+                context.insert("pagination", &pagination);
+            }
+
+            TERA.render("list.html", &context)
+                .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)
+        },
+    )
+    .await
+    {
+        Ok(response) => response,
+        Err(e) => {
+            println!("List page error: {}", e);
+            HttpResponse::InternalServerError().body(format!("Error: {}", e))
+        }
+    }
+}
+
+// Search page handler
+pub async fn search_page_handler(
+    query: web::Query<crate::dto::SearchParams>,
+    db: web::Data<Database>,
+    site_data_manager: web::Data<SiteDataManager>,
+) -> impl Responder {
+    // 图集和音频只在对应功能开启时参与搜索
+    let mut allowed_kinds = vec!["vod", "art"];
+    if gallery_enabled(&site_data_manager).await {
+        allowed_kinds.push("gallery");
+    }
+    if audio_enabled(&site_data_manager).await {
+        allowed_kinds.push("audio");
+    }
+    let keyword = query.wd.as_deref().map(str::trim).unwrap_or_default().to_string();
+    let kind = query
+        .kind
+        .clone()
+        .filter(|k| allowed_kinds.contains(&k.as_str()));
+    let page = query.pg.unwrap_or(1).max(1);
+
+    match with_site_data(
+        db.clone(),
+        site_data_manager.clone(),
+        |mut context, _site_data| async move {
+            let results = if keyword.is_empty() {
+                search_index::SearchResults::default()
+            } else {
+                search_index::search(
+                    &db,
+                    &keyword,
+                    &allowed_kinds,
+                    kind.as_deref(),
+                    page,
+                    search_index::SEARCH_PAGE_SIZE,
+                )
+                .await?
+            };
+
+            let kind_tabs: Vec<serde_json::Value> = allowed_kinds
+                .iter()
+                .map(|k| {
+                    serde_json::json!({
+                        "kind": k,
+                        "label": search_index::kind_label(k),
+                        "count": results.kind_counts.get(*k).copied().unwrap_or(0)
+                    })
+                })
+                .collect();
+            let all_count: u64 = results.kind_counts.values().sum();
+
+            context.insert("search_keyword", &keyword);
+            context.insert("search_results", &results.hits);
+            context.insert("kind_tabs", &kind_tabs);
+            context.insert("all_count", &all_count);
+            context.insert("current_kind", &kind);
+            context.insert("current_page", &page);
+            context.insert("total_pages", &results.total.div_ceil(search_index::SEARCH_PAGE_SIZE));
+            context.insert("total", &results.total);
+
+            TERA.render("search.html", &context)
+                .map_err(|e| {
+                    let context_variables = format!(
+                        "search_results count: {}, search_keyword: {:?}, kind: {:?}, page: {}",
+                        results.hits.len(),
+                        keyword,
+                        kind,
+                        page
+                    );
+
+                    handle_template_rendering_error(
+                        "search.html",
+                        &e,
+                        Some("Search results page"),
+                        Some(&context_variables)
+                    );
+                    Box::new(e) as Box<dyn std::error::Error>
+                })
+        },
+    )
+    .await
+    {
+        Ok(response) => response,
+        Err(e) => {
+            println!("Search page error: {}", e);
+            HttpResponse::InternalServerError().body(format!("Error: {}", e))
+        }
+    }
+}
+
+// Article list page handler
+pub async fn art_list_page_handler(
+    path: web::Path<i32>,
+    query: web::Query<ListPageParams>,
+    db: web::Data<Database>,
+    site_data_manager: web::Data<SiteDataManager>,
+) -> impl Responder {
+    let type_id = path.into_inner();
+
+    match with_site_data(
+        db.clone(),
+        site_data_manager.clone(),
+        |mut context, site_data| async move {
+            let category = match site_data.get_category_by_id(type_id).await {
+                Some(cat) => cat,
+                None => return Err("Category not found".into()),
+            };
+
+            // 子分类筛选，未选择时包含全部子分类
+            let sub_categories: Vec<Type> = site_data
+                .get_all_categories()
+                .await
+                .into_iter()
+                .filter(|cat| cat.type_pid == category.type_id)
+                .collect();
+            let mut display_category = category.clone();
+            let mut filter = doc! { "art_status": 1 };
+            match query.sub_type {
+                Some(sub_type) => {
+                    filter.insert("type_id", sub_type);
+                    if let Some(sub_cat) = site_data.get_category_by_id(sub_type).await {
+                        display_category = sub_cat;
+                    }
+                }
+                None => {
+                    let mut type_ids = vec![type_id];
+                    type_ids.extend(sub_categories.iter().map(|cat| cat.type_id));
+                    filter.insert("type_id", doc! { "$in": type_ids });
+                }
+            }
+            context.insert("category", &category);
+            context.insert("display_category", &display_category);
+            context.insert("sub_categories", &sub_categories);
+            context.insert("current_sub_type", &query.sub_type);
+            context.insert("current_sort", &query.sort);
+
+            let art_collection = db.collection::<Art>("arts");
+            let page = query.pg.unwrap_or(1).max(1);
+            let limit: u64 = 20;
+            let total_items = art_collection
+                .count_documents(filter.clone(), None)
+                .await
+                .unwrap_or(0);
+            let total_pages = total_items.div_ceil(limit);
+
+            let sort_doc = match query.sort.as_deref() {
+                Some("hits") => doc! { "art_hits": -1 },
+                _ => doc! { "art_pubdate": -1 },
+            };
+            let find_options = FindOptions::builder()
+                .skip(Some((page - 1) * limit))
+                .limit(Some(limit as i64))
+                .sort(sort_doc)
+                .projection(doc! { "art_content": 0 })
+                .build();
+            let arts: Vec<Art> = match art_collection.find(filter, find_options).await {
+                Ok(cursor) => cursor.try_collect().await.unwrap_or_else(|_| vec![]),
+                Err(_) => vec![],
+            };
+            context.insert("arts", &arts);
+            context.insert("total_items", &total_items);
+
+            if total_pages > 1 {
+                let start_page = if page > 3 { page - 3 } else { 1 };
+                let end_page = (page + 3).min(total_pages);
+                let pagination = PaginationInfo {
+                    current_page: page,
+                    total_pages,
+                    total_items,
+                    pages: (start_page..=end_page).collect(),
+                };
+                context.insert("pagination", &pagination);
+            }
+
+            TERA.render("art_list.html", &context).map_err(|e| {
+                handle_template_rendering_error(
+                    "art_list.html",
+                    &e,
+                    Some("Article list page"),
+                    Some(&format!("category: {}, arts: {} items", category.type_name, arts.len())),
+                );
+                Box::new(e) as Box<dyn std::error::Error>
+            })
+        },
+    )
+    .await
+    {
+        Ok(response) => response,
+        Err(e) => {
+            println!("Article list page error: {}", e);
+            HttpResponse::InternalServerError().body(format!("Error: {}", e))
+        }
+    }
+}
+
+// Article detail page handler
+pub async fn art_detail_handler(
+    path: web::Path<String>,
+    db: web::Data<Database>,
+    site_data_manager: web::Data<SiteDataManager>,
+) -> impl Responder {
+    let object_id = match mongodb::bson::oid::ObjectId::parse_str(path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::NotFound().body("Invalid article ID"),
+    };
+
+    match with_site_data(
+        db.clone(),
+        site_data_manager.clone(),
+        |mut context, site_data| async move {
+            let art_collection = db.collection::<Art>("arts");
+
+            let art = match art_collection
+                .find_one(doc! { "_id": object_id, "art_status": 1 }, None)
+                .await
+            {
+                Ok(Some(a)) => a,
+                _ => return Err("Article not found".into()),
+            };
+
+            // 累加阅读数，失败不影响页面展示
+            if let Err(e) = art_collection
+                .update_one(doc! { "_id": object_id }, doc! { "$inc": { "art_hits": 1 } }, None)
+                .await
+            {
+                eprintln!("Failed to update article hits: {}", e);
+            }
+
+            context.insert("art_pubdate_timestamp", &(art.art_pubdate.timestamp_millis() / 1000));
+            context.insert("art", &art);
+
+            if let Some(category) = site_data.get_category_by_id(art.type_id).await {
+                context.insert("category", &category);
+            }
+
+            // 同分类的最新文章
+            let find_options = FindOptions::builder()
+                .sort(doc! { "art_pubdate": -1 })
+                .limit(10)
+                .projection(doc! { "art_content": 0 })
+                .build();
+            let related_arts: Vec<Art> = match art_collection
+                .find(
+                    doc! { "type_id": art.type_id, "art_status": 1, "_id": { "$ne": object_id } },
+                    find_options,
+                )
+                .await
+            {
+                Ok(cursor) => cursor.try_collect().await.unwrap_or_else(|_| vec![]),
+                Err(_) => vec![],
+            };
+            context.insert("related_arts", &related_arts);
+
+            TERA.render("art_detail.html", &context).map_err(|e| {
+                handle_template_rendering_error(
+                    "art_detail.html",
+                    &e,
+                    Some("Article detail page"),
+                    Some(&format!("art: {}, related_arts: {} items", art.art_name, related_arts.len())),
+                );
+                Box::new(e) as Box<dyn std::error::Error>
+            })
+        },
+    )
+    .await
+    {
+        Ok(response) => response,
+        Err(e) => {
+            println!("Article detail error: {}", e);
+            HttpResponse::InternalServerError().body(format!("Error: {}", e))
+        }
+    }
+}
+
+// Audio page handlers
+
+// 音频功能由 open_audio 配置控制
+pub(crate) async fn audio_enabled(site_data_manager: &SiteDataManager) -> bool {
+    site_data_manager
+        .get_config("open_audio")
+        .await
+        .is_some_and(|v| v.trim() == "开启")
+}
+
+pub async fn audio_list_handler(
+    query: web::Query<AudioListParams>,
+    db: web::Data<Database>,
+    site_data_manager: web::Data<SiteDataManager>,
+) -> impl Responder {
+    if !audio_enabled(&site_data_manager).await {
+        return HttpResponse::NotFound().body("Audio is disabled");
+    }
+    let category = query.category.clone().filter(|c| !c.is_empty());
+    let page = query.pg.unwrap_or(1).max(1);
+
+    match with_site_data(
+        db.clone(),
+        site_data_manager.clone(),
+        |mut context, _site_data| async move {
+            let collection = db.collection::<Audio>(AUDIO_COLLECTION);
+
+            let audio_categories: Vec<String> = collection
+                .distinct("category", published_filter(), None)
+                .await
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|c| c.as_str().map(str::to_string))
+                .filter(|c| !c.is_empty())
+                .collect();
+
+            let mut filter = published_filter();
+            if let Some(category) = &category {
+                filter.insert("category", category);
+            }
+            let total = collection.count_documents(filter.clone(), None).await?;
+            let find_options = FindOptions::builder()
+                .sort(doc! { "created_at": -1 })
+                .skip((page - 1) * AUDIOS_PER_PAGE)
+                .limit(AUDIOS_PER_PAGE as i64)
+                .projection(doc! { "audios": 0 })
+                .build();
+            let audios: Vec<Audio> = collection.find(filter, find_options).await?.try_collect().await?;
+
+            context.insert("audios", &audios);
+            context.insert("audio_categories", &audio_categories);
+            context.insert("current_category", &category);
+            context.insert("current_page", &page);
+            context.insert("total_pages", &total.div_ceil(AUDIOS_PER_PAGE));
+            context.insert("total", &total);
+
+            TERA.render("audio_list.html", &context).map_err(|e| {
+                handle_template_rendering_error(
+                    "audio_list.html",
+                    &e,
+                    Some("Audio list page"),
+                    Some(&format!("category: {:?}, page: {}, audios: {}", category, page, audios.len())),
+                );
+                Box::new(e) as Box<dyn std::error::Error>
+            })
+        },
+    )
+    .await
+    {
+        Ok(response) => response,
+        Err(e) => {
+            println!("Audio list error: {}", e);
+            HttpResponse::InternalServerError().body(format!("Error: {}", e))
+        }
+    }
+}
+
+pub async fn audio_detail_handler(
+    path: web::Path<String>,
+    db: web::Data<Database>,
+    site_data_manager: web::Data<SiteDataManager>,
+) -> impl Responder {
+    if !audio_enabled(&site_data_manager).await {
+        return HttpResponse::NotFound().body("Audio is disabled");
+    }
+    let object_id = match mongodb::bson::oid::ObjectId::parse_str(path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::NotFound().body("Invalid audio ID"),
+    };
+
+    let mut filter = published_filter();
+    filter.insert("_id", object_id);
+    let mut audio = match db.collection::<Audio>(AUDIO_COLLECTION).find_one(filter, None).await {
+        Ok(Some(audio)) => audio,
+        Ok(None) => return HttpResponse::NotFound().body("Audio not found"),
+        Err(e) => {
+            eprintln!("Failed to fetch audio: {}", e);
+            return HttpResponse::InternalServerError().body("Failed to fetch audio");
+        }
+    };
+
+    match with_site_data(
+        db.clone(),
+        site_data_manager.clone(),
+        |mut context, _site_data| async move {
+            let media_type = crate::models::MediaType::Audio;
+
+            // 页面只输出曲目名，播放地址由 /api/audio/{id}/tracks 校验 VIP 后返回
+            let track_names: Vec<String> =
+                (0..audio.audios.len()).map(|index| track_name(&audio, index)).collect();
+            audio.audios.clear();
+
+            let related_options = FindOptions::builder()
+                .sort(doc! { "created_at": -1 })
+                .limit(6)
+                .projection(doc! { "audios": 0 })
+                .build();
+            let mut related_filter = published_filter();
+            related_filter.insert("category", &audio.category);
+            related_filter.insert("_id", doc! { "$ne": object_id });
+            related_filter.insert("series_id", Bson::Null);
+            let related_audios: Vec<Audio> = match db
+                .collection::<Audio>(AUDIO_COLLECTION)
+                .find(related_filter, related_options)
+                .await
+            {
+                Ok(cursor) => cursor.try_collect().await.unwrap_or_default(),
+                Err(_) => vec![],
+            };
+
+            context.insert("audio", &audio);
+            context.insert("track_names", &track_names);
+            context.insert("related_audios", &related_audios);
+            context.insert("favorite_count", &favorite_count(&db, &media_type, object_id).await);
+            match load_comment_page(&db, &media_type, object_id, 1).await {
+                Ok(comment_page) => context.insert("comment_page", &comment_page),
+                Err(e) => eprintln!("加载评论失败: {}", e),
+            }
+
+            TERA.render("audio_detail.html", &context).map_err(|e| {
+                handle_template_rendering_error(
+                    "audio_detail.html",
+                    &e,
+                    Some("Audio detail page"),
+                    Some(&format!("audio: {}, tracks: {}", audio.title, track_names.len())),
+                );
+                Box::new(e) as Box<dyn std::error::Error>
+            })
+        },
+    )
+    .await
+    {
+        Ok(response) => response,
+        Err(e) => {
+            println!("Audio detail error: {}", e);
+            HttpResponse::InternalServerError().body(format!("Error: {}", e))
+        }
+    }
+}
+
+// Gallery page handlers
+
+// 图集功能由 open_image 配置控制
+pub(crate) async fn gallery_enabled(site_data_manager: &SiteDataManager) -> bool {
+    site_data_manager
+        .get_config("open_image")
+        .await
+        .is_some_and(|v| v.trim() == "开启")
+}
+
+// 读取已发布的图集，页面上不输出图片地址，由 /api/gallery/{id}/pages 校验 VIP 后返回
+async fn find_published_gallery(
+    db: &Database,
+    image_id: mongodb::bson::oid::ObjectId,
+) -> Result<Option<Image>, mongodb::error::Error> {
+    let mut filter = published_gallery_filter();
+    filter.insert("_id", image_id);
+    let options = mongodb::options::FindOneOptions::builder()
+        .projection(doc! { "images": 0 })
+        .build();
+    db.collection::<Image>(IMAGE_COLLECTION)
+        .find_one(filter, options)
+        .await
+}
+
+// 列表页不需要图片数组
+fn gallery_list_options(skip: u64, limit: i64) -> FindOptions {
+    FindOptions::builder()
+        .sort(doc! { "created_at": -1 })
+        .skip(skip)
+        .limit(limit)
+        .projection(doc! { "images": 0 })
+        .build()
+}
+
+// 章节所属的连载及连载内全部可见章节，独立图集返回空
+async fn load_gallery_series(db: &Database, gallery: &Image) -> (Option<GallerySeries>, Vec<Image>) {
+    let Some(series_id) = gallery.series_id else {
+        return (None, Vec::new());
+    };
+    // 连载未审核通过时按独立图集展示
+    let mut filter = approved_series_filter();
+    filter.insert("_id", series_id);
+    let series = match db
+        .collection::<GallerySeries>(SERIES_COLLECTION)
+        .find_one(filter, None)
+        .await
+    {
+        Ok(Some(series)) => series,
+        Ok(None) => return (None, Vec::new()),
+        Err(e) => {
+            eprintln!("查询连载失败: {}", e);
+            return (None, Vec::new());
+        }
+    };
+    let chapters = load_series_chapters(db, series_id).await.unwrap_or_else(|e| {
+        eprintln!("查询章节失败: {}", e);
+        Vec::new()
+    });
+    (Some(series), chapters)
+}
+
+pub async fn gallery_list_handler(
+    query: web::Query<GalleryListParams>,
+    db: web::Data<Database>,
+    site_data_manager: web::Data<SiteDataManager>,
+) -> impl Responder {
+    if !gallery_enabled(&site_data_manager).await {
+        return HttpResponse::NotFound().body("Gallery is disabled");
+    }
+    let category = query.category.clone().filter(|c| !c.is_empty());
+    let tag = query.tag.clone().filter(|t| !t.is_empty());
+    let page = query.pg.unwrap_or(1).max(1);
+
+    match with_site_data(
+        db.clone(),
+        site_data_manager.clone(),
+        |mut context, _site_data| async move {
+            let collection = db.collection::<Image>(IMAGE_COLLECTION);
+
+            // 连载章节在 /series 中展示，图集列表只显示独立图集
+            let mut standalone_filter = published_gallery_filter();
+            standalone_filter.insert("series_id", Bson::Null);
+
+            let gallery_categories: Vec<String> = collection
+                .distinct("category", standalone_filter.clone(), None)
+                .await
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|c| c.as_str().map(str::to_string))
+                .filter(|c| !c.is_empty())
+                .collect();
+
+            let mut filter = standalone_filter;
+            if let Some(category) = &category {
+                filter.insert("category", category);
+            }
+            if let Some(tag) = &tag {
+                filter.insert("tags", tag);
+            }
+            let total = collection.count_documents(filter.clone(), None).await?;
+            let galleries: Vec<Image> = collection
+                .find(
+                    filter,
+                    gallery_list_options((page - 1) * GALLERIES_PER_PAGE, GALLERIES_PER_PAGE as i64),
+                )
+                .await?
+                .try_collect()
+                .await?;
+
+            context.insert("galleries", &galleries);
+            context.insert("gallery_categories", &gallery_categories);
+            context.insert("current_category", &category);
+            context.insert("current_tag", &tag);
+            context.insert("current_page", &page);
+            context.insert("total_pages", &total.div_ceil(GALLERIES_PER_PAGE));
+            context.insert("total", &total);
+
+            TERA.render("gallery_list.html", &context).map_err(|e| {
+                handle_template_rendering_error(
+                    "gallery_list.html",
+                    &e,
+                    Some("Gallery list page"),
+                    Some(&format!(
+                        "category: {:?}, tag: {:?}, page: {}, galleries: {}",
+                        category,
+                        tag,
+                        page,
+                        galleries.len()
+                    )),
+                );
+                Box::new(e) as Box<dyn std::error::Error>
+            })
+        },
+    )
+    .await
+    {
+        Ok(response) => response,
+        Err(e) => {
+            println!("Gallery list error: {}", e);
+            HttpResponse::InternalServerError().body(format!("Error: {}", e))
+        }
+    }
+}
+
+pub async fn gallery_detail_handler(
+    path: web::Path<String>,
+    db: web::Data<Database>,
+    site_data_manager: web::Data<SiteDataManager>,
+) -> impl Responder {
+    if !gallery_enabled(&site_data_manager).await {
+        return HttpResponse::NotFound().body("Gallery is disabled");
+    }
+    let object_id = match mongodb::bson::oid::ObjectId::parse_str(path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::NotFound().body("Invalid gallery ID"),
+    };
+    let gallery = match find_published_gallery(&db, object_id).await {
+        Ok(Some(gallery)) => gallery,
+        Ok(None) => return HttpResponse::NotFound().body("Gallery not found"),
+        Err(e) => {
+            eprintln!("Failed to fetch gallery: {}", e);
+            return HttpResponse::InternalServerError().body("Failed to fetch gallery");
+        }
+    };
+
+    match with_site_data(
+        db.clone(),
+        site_data_manager.clone(),
+        |mut context, _site_data| async move {
+            let media_type = crate::models::MediaType::Image;
+
+            let mut related_filter = published_gallery_filter();
+            related_filter.insert("category", &gallery.category);
+            related_filter.insert("_id", doc! { "$ne": object_id });
+            let related_galleries: Vec<Image> = match db
+                .collection::<Image>(IMAGE_COLLECTION)
+                .find(related_filter, gallery_list_options(0, 6))
+                .await
+            {
+                Ok(cursor) => cursor.try_collect().await.unwrap_or_default(),
+                Err(_) => vec![],
+            };
+
+            let (series, chapters) = load_gallery_series(&db, &gallery).await;
+            let (prev_chapter, next_chapter) = chapter_neighbors(&chapters, object_id);
+            context.insert("series", &series);
+            context.insert("prev_chapter", &prev_chapter);
+            context.insert("next_chapter", &next_chapter);
+            context.insert("gallery", &gallery);
+            context.insert("related_galleries", &related_galleries);
+            context.insert("favorite_count", &favorite_count(&db, &media_type, object_id).await);
+            match load_comment_page(&db, &media_type, object_id, 1).await {
+                Ok(comment_page) => context.insert("comment_page", &comment_page),
+                Err(e) => eprintln!("加载评论失败: {}", e),
+            }
+
+            TERA.render("gallery_detail.html", &context).map_err(|e| {
+                handle_template_rendering_error(
+                    "gallery_detail.html",
+                    &e,
+                    Some("Gallery detail page"),
+                    Some(&format!("gallery: {}, pages: {}", gallery.title, gallery.pages)),
+                );
+                Box::new(e) as Box<dyn std::error::Error>
+            })
+        },
+    )
+    .await
+    {
+        Ok(response) => response,
+        Err(e) => {
+            println!("Gallery detail error: {}", e);
+            HttpResponse::InternalServerError().body(format!("Error: {}", e))
+        }
+    }
+}
+
+pub async fn gallery_reader_handler(
+    path: web::Path<String>,
+    query: web::Query<GalleryReaderParams>,
+    db: web::Data<Database>,
+    site_data_manager: web::Data<SiteDataManager>,
+) -> impl Responder {
+    if !gallery_enabled(&site_data_manager).await {
+        return HttpResponse::NotFound().body("Gallery is disabled");
+    }
+    let object_id = match mongodb::bson::oid::ObjectId::parse_str(path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::NotFound().body("Invalid gallery ID"),
+    };
+    let gallery = match find_published_gallery(&db, object_id).await {
+        Ok(Some(gallery)) => gallery,
+        Ok(None) => return HttpResponse::NotFound().body("Gallery not found"),
+        Err(e) => {
+            eprintln!("Failed to fetch gallery: {}", e);
+            return HttpResponse::InternalServerError().body("Failed to fetch gallery");
+        }
+    };
+    // 页码从 1 开始，越界时由前端在拿到页面列表后修正
+    let start_page = query.p.unwrap_or(1).max(1);
+
+    match with_site_data(
+        db.clone(),
+        site_data_manager.clone(),
+        |mut context, _site_data| async move {
+            let (series, chapters) = load_gallery_series(&db, &gallery).await;
+            let (prev_chapter, next_chapter) = chapter_neighbors(&chapters, object_id);
+            context.insert("series", &series);
+            context.insert("prev_chapter", &prev_chapter);
+            context.insert("next_chapter", &next_chapter);
+            context.insert("gallery", &gallery);
+            context.insert("start_page", &start_page);
+
+            TERA.render("gallery_reader.html", &context).map_err(|e| {
+                handle_template_rendering_error(
+                    "gallery_reader.html",
+                    &e,
+                    Some("Gallery reader page"),
+                    Some(&format!("gallery: {}, start_page: {}", gallery.title, start_page)),
+                );
+                Box::new(e) as Box<dyn std::error::Error>
+            })
+        },
+    )
+    .await
+    {
+        Ok(response) => response,
+        Err(e) => {
+            println!("Gallery reader error: {}", e);
+            HttpResponse::InternalServerError().body(format!("Error: {}", e))
+        }
+    }
+}
+
+pub async fn series_list_handler(
+    query: web::Query<GalleryListParams>,
+    db: web::Data<Database>,
+    site_data_manager: web::Data<SiteDataManager>,
+) -> impl Responder {
+    if !gallery_enabled(&site_data_manager).await {
+        return HttpResponse::NotFound().body("Gallery is disabled");
+    }
+    let category = query.category.clone().filter(|c| !c.is_empty());
+    let tag = query.tag.clone().filter(|t| !t.is_empty());
+    let page = query.pg.unwrap_or(1).max(1);
+
+    match with_site_data(
+        db.clone(),
+        site_data_manager.clone(),
+        |mut context, _site_data| async move {
+            let collection = db.collection::<GallerySeries>(SERIES_COLLECTION);
 
-                let pagination = PaginationInfo {
-                    current_page: page,
-                    total_pages,
-                    total_items,
-                    pages,
-                };
-                context.insert("pagination", &pagination);
+            // 只展示审核通过且至少有一个已公开章节的连载
+            let mut filter = approved_series_filter();
+            filter.insert("_id", doc! { "$in": visible_series_ids(&db).await? });
+            let series_categories: Vec<String> = collection
+                .distinct("category", filter.clone(), None)
+                .await
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|c| c.as_str().map(str::to_string))
+                .filter(|c| !c.is_empty())
+                .collect();
+            if let Some(category) = &category {
+                filter.insert("category", category);
+            }
+            if let Some(tag) = &tag {
+                filter.insert("tags", tag);
+            }
+            let total = collection.count_documents(filter.clone(), None).await?;
+            let options = FindOptions::builder()
+                .sort(doc! { "updated_at": -1 })
+                .skip((page - 1) * SERIES_PER_PAGE)
+                .limit(SERIES_PER_PAGE as i64)
+                .build();
+            let series_list: Vec<GallerySeries> =
+                collection.find(filter, options).await?.try_collect().await?;
+
+            let mut chapter_counts = std::collections::HashMap::new();
+            for series in &series_list {
+                if let Some(id) = series.id {
+                    let mut chapter_filter = published_gallery_filter();
+                    chapter_filter.insert("series_id", id);
+                    let count = db
+                        .collection::<Image>(IMAGE_COLLECTION)
+                        .count_documents(chapter_filter, None)
+                        .await
+                        .unwrap_or(0);
+                    chapter_counts.insert(id.to_hex(), count);
+                }
             }
 
-            TERA.render("list.html", &context)
-                .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)
+            context.insert("series_list", &series_list);
+            context.insert("chapter_counts", &chapter_counts);
+            context.insert("series_categories", &series_categories);
+            context.insert("current_category", &category);
+            context.insert("current_tag", &tag);
+            context.insert("current_page", &page);
+            context.insert("total_pages", &total.div_ceil(SERIES_PER_PAGE));
+            context.insert("total", &total);
+
+            TERA.render("series_list.html", &context).map_err(|e| {
+                handle_template_rendering_error(
+                    "series_list.html",
+                    &e,
+                    Some("Series list page"),
+                    Some(&format!(
+                        "category: {:?}, tag: {:?}, page: {}, series: {}",
+                        category,
+                        tag,
+                        page,
+                        series_list.len()
+                    )),
+                );
+                Box::new(e) as Box<dyn std::error::Error>
+            })
         },
     )
     .await
     {
         Ok(response) => response,
         Err(e) => {
-            println!("List page error: {}", e);
+            println!("Series list error: {}", e);
             HttpResponse::InternalServerError().body(format!("Error: {}", e))
         }
     }
 }
 
-// Search page handler
-pub async fn search_page_handler(
-    query: web::Query<crate::dto::ApiParams>,
+pub async fn series_detail_handler(
+    path: web::Path<String>,
     db: web::Data<Database>,
     site_data_manager: web::Data<SiteDataManager>,
 ) -> impl Responder {
+    if !gallery_enabled(&site_data_manager).await {
+        return HttpResponse::NotFound().body("Gallery is disabled");
+    }
+    let object_id = match mongodb::bson::oid::ObjectId::parse_str(path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::NotFound().body("Invalid series ID"),
+    };
+    let mut filter = approved_series_filter();
+    filter.insert("_id", object_id);
+    let series = match db
+        .collection::<GallerySeries>(SERIES_COLLECTION)
+        .find_one(filter, None)
+        .await
+    {
+        Ok(Some(series)) => series,
+        Ok(None) => return HttpResponse::NotFound().body("Series not found"),
+        Err(e) => {
+            eprintln!("Failed to fetch series: {}", e);
+            return HttpResponse::InternalServerError().body("Failed to fetch series");
+        }
+    };
+    let chapters = match load_series_chapters(&db, object_id).await {
+        Ok(chapters) if !chapters.is_empty() => chapters,
+        // 还没有公开章节的连载不对外展示
+        Ok(_) => return HttpResponse::NotFound().body("Series not found"),
+        Err(e) => {
+            eprintln!("Failed to fetch chapters: {}", e);
+            return HttpResponse::InternalServerError().body("Failed to fetch series");
+        }
+    };
+
     match with_site_data(
         db.clone(),
         site_data_manager.clone(),
         |mut context, _site_data| async move {
-            let vod_collection = db.collection::<Vod>("vods");
-            let search_results = if let Some(ref keyword) = query.wd {
-                let search_filter = doc! {
-                    "$or": [
-                        { "vod_name": doc! { "$regex": keyword, "$options": "i" } },
-                        { "vod_actor": doc! { "$regex": keyword, "$options": "i" } },
-                        { "vod_director": doc! { "$regex": keyword, "$options": "i" } }
-                    ]
-                };
-
-                let find_options = FindOptions::builder()
-                    .sort(doc! { "vod_pubdate": -1 })
-                    .limit(50)
-                    .build();
-
-                match vod_collection.find(search_filter, find_options).await {
-                    Ok(cursor) => cursor.try_collect().await.unwrap_or_else(|_| vec![]),
-                    Err(_) => vec![],
-                }
-            } else {
-                vec![]
-            };
+            context.insert("series", &series);
+            context.insert("chapters", &chapters);
 
-            context.insert("search_results", &search_results);
-            context.insert("search_keyword", &query.wd);
+            TERA.render("series_detail.html", &context).map_err(|e| {
+                handle_template_rendering_error(
+                    "series_detail.html",
+                    &e,
+                    Some("Series detail page"),
+                    Some(&format!("series: {}, chapters: {}", series.title, chapters.len())),
+                );
+                Box::new(e) as Box<dyn std::error::Error>
+            })
+        },
+    )
+    .await
+    {
+        Ok(response) => response,
+        Err(e) => {
+            println!("Series detail error: {}", e);
+            HttpResponse::InternalServerError().body(format!("Error: {}", e))
+        }
+    }
+}
 
-            TERA.render("search.html", &context)
+pub async fn submit_series_page(
+    db: web::Data<Database>,
+    site_data_manager: web::Data<crate::site_data::SiteDataManager>,
+) -> impl Responder {
+    match with_site_data(
+        db.clone(),
+        site_data_manager.clone(),
+        |context, _site_data| async move {
+            TERA.render("user/submit_series.html", &context)
                 .map_err(|e| {
-                    let context_variables = format!(
-                        "search_results count: {}, search_keyword: {:?}",
-                        search_results.len(),
-                        query.wd
+                    handle_template_rendering_error(
+                        "user/submit_series.html",
+                        &e,
+                        Some("Series chapter submission page"),
+                        Some("Public access - authentication handled by frontend")
                     );
-                    
+                    Box::new(e) as Box<dyn std::error::Error>
+                })
+        },
+    )
+    .await
+    {
+        Ok(response) => response,
+        Err(e) => {
+            println!("Series submission page error: {}", e);
+            HttpResponse::InternalServerError().body(format!("Error: {}", e))
+        }
+    }
+}
+
+pub async fn submit_audio_page(
+    db: web::Data<Database>,
+    site_data_manager: web::Data<crate::site_data::SiteDataManager>,
+) -> impl Responder {
+    match with_site_data(
+        db.clone(),
+        site_data_manager.clone(),
+        |context, _site_data| async move {
+            TERA.render("user/submit_audio.html", &context)
+                .map_err(|e| {
                     handle_template_rendering_error(
-                        "search.html",
+                        "user/submit_audio.html",
                         &e,
-                        Some("Search results page"),
-                        Some(&context_variables)
+                        Some("Audio submission page"),
+                        Some("Public access - authentication handled by frontend")
                     );
                     Box::new(e) as Box<dyn std::error::Error>
                 })
@@ -971,7 +1843,100 @@ pub async fn search_page_handler(
     {
         Ok(response) => response,
         Err(e) => {
-            println!("Search page error: {}", e);
+            println!("Audio submission page error: {}", e);
+            HttpResponse::InternalServerError().body(format!("Error: {}", e))
+        }
+    }
+}
+
+// Ranking page handlers
+pub async fn rank_page_handler(
+    query: web::Query<RankParams>,
+    db: web::Data<Database>,
+    site_data_manager: web::Data<SiteDataManager>,
+) -> impl Responder {
+    let type_id = query.type_id.unwrap_or(0);
+    render_rank_page(type_id, query.board.clone(), db, site_data_manager).await
+}
+
+pub async fn rank_type_page_handler(
+    path: web::Path<i32>,
+    query: web::Query<RankParams>,
+    db: web::Data<Database>,
+    site_data_manager: web::Data<SiteDataManager>,
+) -> impl Responder {
+    render_rank_page(path.into_inner(), query.board.clone(), db, site_data_manager).await
+}
+
+async fn render_rank_page(
+    type_id: i32,
+    board: Option<String>,
+    db: web::Data<Database>,
+    site_data_manager: web::Data<SiteDataManager>,
+) -> HttpResponse {
+    // 未知的榜单类型回退到今日热播
+    let board = board
+        .filter(|b| RANK_BOARDS.iter().any(|(key, _)| key == b))
+        .unwrap_or_else(|| RANK_BOARDS[0].0.to_string());
+
+    match with_site_data(
+        db.clone(),
+        site_data_manager.clone(),
+        |mut context, site_data| async move {
+            let category = if type_id > 0 {
+                match site_data.get_category_by_id(type_id).await {
+                    Some(cat) => Some(cat),
+                    None => return Err("Category not found".into()),
+                }
+            } else {
+                None
+            };
+
+            // 榜单分类只列出顶级视频分类
+            let rank_categories: Vec<Type> = site_data
+                .get_navigation_categories()
+                .await
+                .into_iter()
+                .map(|nav| nav.category)
+                .filter(|cat| cat.type_mid.unwrap_or(1) == 1)
+                .collect();
+
+            let boards: Vec<serde_json::Value> = RANK_BOARDS
+                .iter()
+                .map(|(key, name)| serde_json::json!({ "key": key, "name": name }))
+                .collect();
+            let board_name = RANK_BOARDS
+                .iter()
+                .find(|(key, _)| *key == board)
+                .map(|(_, name)| *name)
+                .unwrap_or_default();
+
+            let items = site_data.get_ranking(&board, type_id).await;
+
+            context.insert("rank_items", &items);
+            context.insert("rank_boards", &boards);
+            context.insert("rank_categories", &rank_categories);
+            context.insert("current_board", &board);
+            context.insert("current_board_name", board_name);
+            context.insert("current_type_id", &type_id);
+            context.insert("rank_category", &category);
+
+            TERA.render("rank.html", &context).map_err(|e| {
+                handle_template_rendering_error(
+                    "rank.html",
+                    &e,
+                    Some("Ranking page"),
+                    Some(&format!("board: {}, type_id: {}, items: {}", board, type_id, items.len())),
+                );
+                Box::new(e) as Box<dyn std::error::Error>
+            })
+        },
+    )
+    .await
+    {
+        Ok(response) => response,
+        Err(e) => {
+            println!("Ranking page error: {}", e);
             HttpResponse::InternalServerError().body(format!("Error: {}", e))
         }
     }
@@ -1194,6 +2159,34 @@ pub async fn admin_vods_page(db: web::Data<Database>) -> Result<HttpResponse> {
     }
 }
 
+pub async fn admin_arts_page(db: web::Data<Database>) -> Result<HttpResponse> {
+    let find_options = FindOptions::builder().sort(doc! {"type_sort": 1}).build();
+    let types: Vec<Type> = match db.collection::<Type>("types").find(None, find_options).await {
+        Ok(cursor) => cursor.try_collect().await.unwrap_or_else(|_| vec![]),
+        Err(e) => {
+            eprintln!("Failed to fetch types: {}", e);
+            vec![]
+        }
+    };
+
+    let mut context = tera::Context::new();
+    context.insert("SITENAME", "maccms-rust");
+    context.insert("types", &types);
+
+    match TERA.render("admin/arts.html", &context) {
+        Ok(s) => Ok(HttpResponse::Ok().content_type("text/html").body(s)),
+        Err(e) => {
+            handle_template_rendering_error(
+                "admin/arts.html",
+                &e,
+                Some("Admin article management page"),
+                None
+            );
+            Ok(HttpResponse::InternalServerError().body("Template error"))
+        }
+    }
+}
+
 pub async fn admin_collect_page(db: web::Data<Database>) -> Result<HttpResponse> {
     let collection_collection = db.collection::<crate::models::Collection>("collections");
     let collections: Vec<crate::models::Collection> =
@@ -1334,6 +2327,78 @@ pub async fn admin_collect_vod_page(db: web::Data<Database>) -> Result<HttpRespo
     }
 }
 
+pub async fn admin_jobs_page() -> Result<HttpResponse> {
+    let mut context = tera::Context::new();
+    context.insert("SITENAME", "maccms-rust");
+
+    match TERA.render("admin/jobs.html", &context) {
+        Ok(s) => Ok(HttpResponse::Ok().content_type("text/html").body(s)),
+        Err(e) => {
+            handle_template_rendering_error(
+                "admin/jobs.html",
+                &e,
+                Some("Admin background jobs page"),
+                None
+            );
+            Ok(HttpResponse::InternalServerError().body("Template error"))
+        }
+    }
+}
+
+pub async fn admin_audios_page() -> Result<HttpResponse> {
+    let mut context = tera::Context::new();
+    context.insert("SITENAME", "maccms-rust");
+
+    match TERA.render("admin/audios.html", &context) {
+        Ok(s) => Ok(HttpResponse::Ok().content_type("text/html").body(s)),
+        Err(e) => {
+            handle_template_rendering_error(
+                "admin/audios.html",
+                &e,
+                Some("Admin audio management page"),
+                None
+            );
+            Ok(HttpResponse::InternalServerError().body("Template error"))
+        }
+    }
+}
+
+pub async fn admin_images_page() -> Result<HttpResponse> {
+    let mut context = tera::Context::new();
+    context.insert("SITENAME", "maccms-rust");
+
+    match TERA.render("admin/images.html", &context) {
+        Ok(s) => Ok(HttpResponse::Ok().content_type("text/html").body(s)),
+        Err(e) => {
+            handle_template_rendering_error(
+                "admin/images.html",
+                &e,
+                Some("Admin gallery moderation page"),
+                None
+            );
+            Ok(HttpResponse::InternalServerError().body("Template error"))
+        }
+    }
+}
+
+pub async fn admin_comments_page() -> Result<HttpResponse> {
+    let mut context = tera::Context::new();
+    context.insert("SITENAME", "maccms-rust");
+
+    match TERA.render("admin/comments.html", &context) {
+        Ok(s) => Ok(HttpResponse::Ok().content_type("text/html").body(s)),
+        Err(e) => {
+            handle_template_rendering_error(
+                "admin/comments.html",
+                &e,
+                Some("Admin comment moderation page"),
+                None
+            );
+            Ok(HttpResponse::InternalServerError().body("Template error"))
+        }
+    }
+}
+
 pub async fn admin_indexes_page() -> Result<HttpResponse> {
     let mut context = tera::Context::new();
     context.insert("SITENAME", "maccms-rust");
@@ -1728,6 +2793,43 @@ pub async fn vip_check_handler(
     }
 }
 
+/// 校验用户是否满足 need_vip 要求，返回是否有权限及提示信息。
+/// 令牌中不含会员信息，已登录用户的 VIP 等级和到期时间从数据库读取。
+pub async fn check_media_vip_access(
+    db: &Database,
+    user: Option<&crate::jwt_auth::AuthenticatedUser>,
+    need_vip: i32,
+) -> (bool, String) {
+    if need_vip <= 0 {
+        return (true, "公开内容，访问成功".to_string());
+    }
+    let Some(user_id) = user.and_then(|u| u.user.id) else {
+        return (false, "请先登录以访问VIP内容".to_string());
+    };
+
+    let user = match db
+        .collection::<User>("users")
+        .find_one(doc! { "_id": user_id }, None)
+        .await
+    {
+        Ok(Some(user)) => user,
+        Ok(None) => return (false, "用户不存在".to_string()),
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            return (false, "服务器错误".to_string());
+        }
+    };
+
+    let is_vip_valid = user
+        .vip_end_time
+        .is_some_and(|end| end.timestamp_millis() > chrono::Utc::now().timestamp_millis());
+    if user.vip_level.unwrap_or(0) >= need_vip && is_vip_valid {
+        (true, "访问权限验证成功".to_string())
+    } else {
+        (false, format!("该内容需要VIP{}权限", need_vip))
+    }
+}
+
 // Helper function to get play info and create response
 fn get_play_info_and_respond(
     video: &Vod,